@@ -9,9 +9,6 @@ CREATE TABLE IF NOT EXISTS public.guild_info
 
 TABLESPACE pg_default;
 
-ALTER TABLE public.guild_info
-    OWNER to postgres;
-
 COMMENT ON TABLE public.guild_info
     IS 'Information on guilds.';
 
@@ -33,8 +30,5 @@ CREATE TABLE IF NOT EXISTS public.profile_data
 
 TABLESPACE pg_default;
 
-ALTER TABLE public.profile_data
-    OWNER TO postgres;
-
 COMMENT ON TABLE public.profile_data
     IS 'Individual user profile data.';
@@ -1,3 +1,3 @@
 -- Add migration script here
 ALTER TABLE public.profile_data
-ADD COLUMN user_pronouns TEXT COLLATE pg_catalog."default"
+ADD COLUMN IF NOT EXISTS user_pronouns TEXT COLLATE pg_catalog."default";
@@ -1,6 +1,6 @@
 ALTER TABLE public.profile_data
-DROP COLUMN user_twitch_id,
-DROP COLUMN user_twitter_id,
-DROP COLUMN user_steam_id,
-DROP COLUMN user_xbox_id,
-DROP COLUMN user_playstation_id
+DROP COLUMN IF EXISTS user_twitch_id,
+DROP COLUMN IF EXISTS user_twitter_id,
+DROP COLUMN IF EXISTS user_steam_id,
+DROP COLUMN IF EXISTS user_xbox_id,
+DROP COLUMN IF EXISTS user_playstation_id;
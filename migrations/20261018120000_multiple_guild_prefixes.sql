@@ -0,0 +1,11 @@
+-- Allow guilds to set several prefixes. Existing values were only ever the
+-- default prefix at the time the guild was added, so they are cleared and a
+-- NULL value now means the guild uses the prefix from the configuration file.
+ALTER TABLE public.guild_info
+    ALTER COLUMN guild_prefix TYPE TEXT[] USING NULL;
+
+ALTER TABLE public.guild_info
+    RENAME COLUMN guild_prefix TO guild_prefixes;
+
+COMMENT ON COLUMN public.guild_info.guild_prefixes
+    IS 'Custom command prefixes for the guild. Uses the default prefix if NULL.';
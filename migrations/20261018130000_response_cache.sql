@@ -0,0 +1,17 @@
+-- Persisted entries of the external API response cache, used when
+-- bot.cache.persist is enabled.
+CREATE TABLE IF NOT EXISTS public.response_cache
+(
+    "key" TEXT COLLATE pg_catalog."default" NOT NULL,
+    "value" TEXT COLLATE pg_catalog."default" NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT response_cache_pkey PRIMARY KEY ("key")
+)
+
+TABLESPACE pg_default;
+
+CREATE INDEX IF NOT EXISTS response_cache_expires_at_idx
+    ON public.response_cache (expires_at);
+
+COMMENT ON TABLE public.response_cache
+    IS 'Cached responses of external APIs, stored as JSON.';
@@ -0,0 +1,28 @@
+-- Adds a bio, time zone and birthday to profiles, along with a table of
+-- accounts users have linked from other services. This replaces the per-service
+-- columns removed in 20201205032238_remove_social_fields.sql.
+ALTER TABLE public.profile_data
+    ADD COLUMN IF NOT EXISTS user_bio TEXT COLLATE pg_catalog."default",
+    ADD COLUMN IF NOT EXISTS user_timezone TEXT COLLATE pg_catalog."default",
+    ADD COLUMN IF NOT EXISTS user_birthday TEXT COLLATE pg_catalog."default";
+
+COMMENT ON COLUMN profile_data.user_bio IS 'A short description the user has written about themselves.';
+COMMENT ON COLUMN profile_data.user_timezone IS 'The user''s IANA time zone, e.g. Europe/London.';
+COMMENT ON COLUMN profile_data.user_birthday IS 'The user''s birthday, as YYYY-MM-DD or MM-DD if they left out the year.';
+
+CREATE TABLE IF NOT EXISTS public.linked_accounts
+(
+    "user_id" BIGINT NOT NULL,
+    service TEXT COLLATE pg_catalog."default" NOT NULL,
+    account TEXT COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT linked_accounts_pkey PRIMARY KEY ("user_id", service),
+    CONSTRAINT linked_accounts_user_id_fkey FOREIGN KEY ("user_id")
+        REFERENCES public.profile_data ("user_id") ON DELETE CASCADE
+)
+
+TABLESPACE pg_default;
+
+COMMENT ON TABLE public.linked_accounts
+    IS 'Accounts users have linked from other services.';
+COMMENT ON COLUMN linked_accounts.service IS 'The lowercase name of the service, e.g. steam.';
+COMMENT ON COLUMN linked_accounts.account IS 'The user''s username or identifier on the service.';
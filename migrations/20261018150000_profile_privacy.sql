@@ -0,0 +1,19 @@
+-- Lets users choose who can see each of their profile fields. Fields without a
+-- row here are public.
+CREATE TABLE IF NOT EXISTS public.profile_privacy
+(
+    "user_id" BIGINT NOT NULL,
+    field TEXT COLLATE pg_catalog."default" NOT NULL,
+    visibility TEXT COLLATE pg_catalog."default" NOT NULL,
+    CONSTRAINT profile_privacy_pkey PRIMARY KEY ("user_id", field),
+    CONSTRAINT profile_privacy_user_id_fkey FOREIGN KEY ("user_id")
+        REFERENCES public.profile_data ("user_id") ON DELETE CASCADE,
+    CONSTRAINT profile_privacy_visibility_check CHECK (visibility IN ('public', 'guild', 'private'))
+)
+
+TABLESPACE pg_default;
+
+COMMENT ON TABLE public.profile_privacy
+    IS 'Who can see each profile field of a user.';
+COMMENT ON COLUMN profile_privacy.field IS 'The profile field, e.g. location, or accounts for linked accounts.';
+COMMENT ON COLUMN profile_privacy.visibility IS 'public for anyone, guild for members of a guild the user is in, or private for the user alone.';
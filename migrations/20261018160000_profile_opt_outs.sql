@@ -0,0 +1,14 @@
+-- Users who have deleted their data, so that they aren't added back to
+-- profile_data the next time they send a message. Setting up a profile again
+-- removes the user from this table.
+CREATE TABLE IF NOT EXISTS public.profile_opt_outs
+(
+    "user_id" BIGINT NOT NULL,
+    opted_out_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT profile_opt_outs_pkey PRIMARY KEY ("user_id")
+)
+
+TABLESPACE pg_default;
+
+COMMENT ON TABLE public.profile_opt_outs
+    IS 'Users who deleted their data and are no longer added to profile_data automatically.';
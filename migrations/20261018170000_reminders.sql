@@ -0,0 +1,28 @@
+-- Reminders set with the remind command. One-off reminders are deleted once
+-- delivered, while recurring ones are moved on to their next occurrence.
+CREATE TABLE IF NOT EXISTS public.reminders
+(
+    id BIGSERIAL NOT NULL,
+    "user_id" BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    message TEXT COLLATE pg_catalog."default" NOT NULL,
+    due_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    recurrence TEXT COLLATE pg_catalog."default",
+    attempts INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT reminders_pkey PRIMARY KEY (id)
+)
+
+TABLESPACE pg_default;
+
+CREATE INDEX IF NOT EXISTS reminders_due_at_idx
+    ON public.reminders (due_at);
+
+CREATE INDEX IF NOT EXISTS reminders_user_id_idx
+    ON public.reminders ("user_id");
+
+COMMENT ON TABLE public.reminders
+    IS 'Reminders waiting to be delivered.';
+COMMENT ON COLUMN reminders.channel_id IS 'The channel the reminder was set in, which is a DM channel if it was set in DMs.';
+COMMENT ON COLUMN reminders.recurrence IS 'How the reminder repeats, e.g. daily 09:00 or weekly mon 09:00, read in the time zone of the user. NULL for one-off reminders.';
+COMMENT ON COLUMN reminders.attempts IS 'How many times delivering the reminder has failed in a row.';
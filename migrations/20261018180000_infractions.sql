@@ -0,0 +1,37 @@
+-- Moderation actions taken in guilds, numbered per guild. The next case number
+-- of each guild is kept in infraction_counters, so that numbers are never
+-- reused, even after a case is removed.
+CREATE TABLE IF NOT EXISTS public.infraction_counters
+(
+    guild_id BIGINT NOT NULL,
+    last_case INTEGER NOT NULL,
+    CONSTRAINT infraction_counters_pkey PRIMARY KEY (guild_id)
+)
+
+TABLESPACE pg_default;
+
+CREATE TABLE IF NOT EXISTS public.infractions
+(
+    guild_id BIGINT NOT NULL,
+    case_id INTEGER NOT NULL,
+    "user_id" BIGINT NOT NULL,
+    moderator_id BIGINT NOT NULL,
+    action TEXT COLLATE pg_catalog."default" NOT NULL,
+    reason TEXT COLLATE pg_catalog."default",
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    expires_at TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT infractions_pkey PRIMARY KEY (guild_id, case_id)
+)
+
+TABLESPACE pg_default;
+
+CREATE INDEX IF NOT EXISTS infractions_user_id_idx
+    ON public.infractions (guild_id, "user_id");
+
+COMMENT ON TABLE public.infraction_counters
+    IS 'The last case number given out in each guild.';
+COMMENT ON TABLE public.infractions
+    IS 'Moderation actions taken against users, numbered per guild.';
+COMMENT ON COLUMN infractions."user_id" IS 'The user the action was taken against.';
+COMMENT ON COLUMN infractions.action IS 'What was done, e.g. ban or kick.';
+COMMENT ON COLUMN infractions.expires_at IS 'When the action ends, for actions that do. NULL for permanent ones.';
@@ -0,0 +1,37 @@
+-- Warnings are infractions with the warn action. They stop counting towards
+-- escalation once they expire or are pardoned.
+ALTER TABLE public.infractions
+    ADD COLUMN IF NOT EXISTS pardoned_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS pardoned_by BIGINT;
+
+COMMENT ON COLUMN infractions.pardoned_at IS 'When the infraction was pardoned, if it was. Only warnings can be pardoned.';
+COMMENT ON COLUMN infractions.pardoned_by IS 'The moderator who pardoned the infraction.';
+
+CREATE TABLE IF NOT EXISTS public.moderation_settings
+(
+    guild_id BIGINT NOT NULL,
+    warning_decay BIGINT,
+    CONSTRAINT moderation_settings_pkey PRIMARY KEY (guild_id)
+)
+
+TABLESPACE pg_default;
+
+COMMENT ON TABLE public.moderation_settings
+    IS 'Moderation settings of guilds. Guilds without a row use the defaults.';
+COMMENT ON COLUMN moderation_settings.warning_decay IS 'How long, in seconds, warnings stay active. NULL if they never expire.';
+
+CREATE TABLE IF NOT EXISTS public.escalation_rules
+(
+    guild_id BIGINT NOT NULL,
+    warnings INTEGER NOT NULL,
+    action TEXT COLLATE pg_catalog."default" NOT NULL,
+    duration BIGINT,
+    CONSTRAINT escalation_rules_pkey PRIMARY KEY (guild_id, warnings)
+)
+
+TABLESPACE pg_default;
+
+COMMENT ON TABLE public.escalation_rules
+    IS 'Actions taken automatically once a user reaches a number of active warnings.';
+COMMENT ON COLUMN escalation_rules.action IS 'The action to take: timeout, kick or ban.';
+COMMENT ON COLUMN escalation_rules.duration IS 'How long, in seconds, a timeout lasts. NULL for other actions.';
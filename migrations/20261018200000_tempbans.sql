@@ -0,0 +1,21 @@
+-- Temporary bans waiting to be lifted. A row is removed once the user is
+-- unbanned, or when they are banned permanently instead.
+CREATE TABLE IF NOT EXISTS public.tempbans
+(
+    guild_id BIGINT NOT NULL,
+    "user_id" BIGINT NOT NULL,
+    case_id INTEGER NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT tempbans_pkey PRIMARY KEY (guild_id, "user_id")
+)
+
+TABLESPACE pg_default;
+
+CREATE INDEX IF NOT EXISTS tempbans_expires_at_idx
+    ON public.tempbans (expires_at);
+
+COMMENT ON TABLE public.tempbans
+    IS 'Temporary bans that are lifted automatically once they expire.';
+COMMENT ON COLUMN tempbans.case_id IS 'The case the ban was recorded as.';
+COMMENT ON COLUMN tempbans.attempts IS 'How many times lifting the ban has failed in a row.';
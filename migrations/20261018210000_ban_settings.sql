@@ -0,0 +1,6 @@
+-- How many days of messages are deleted when someone is banned, which used
+-- to always be one.
+ALTER TABLE public.moderation_settings
+    ADD COLUMN IF NOT EXISTS delete_message_days SMALLINT;
+
+COMMENT ON COLUMN moderation_settings.delete_message_days IS 'How many days of messages, from 0 to 7, are deleted when a user is banned. NULL to use the default of 1.';
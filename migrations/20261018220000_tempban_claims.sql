@@ -0,0 +1,6 @@
+-- Expired temporary bans are claimed for a while before they are lifted, so
+-- that no row stays locked while Discord is asked to lift them.
+ALTER TABLE public.tempbans
+    ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMP WITH TIME ZONE;
+
+COMMENT ON COLUMN tempbans.claimed_until IS 'Until when an instance of the bot has claimed the ban to lift it. Once this passes, the ban can be claimed again. NULL if unclaimed.';
@@ -0,0 +1,6 @@
+-- Due reminders are claimed for a while before they are delivered, so that no
+-- row stays locked while the reminder is sent.
+ALTER TABLE public.reminders
+    ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMP WITH TIME ZONE;
+
+COMMENT ON COLUMN reminders.claimed_until IS 'Until when an instance of the bot has claimed the reminder to deliver it. Once this passes, the reminder can be claimed again. NULL if unclaimed.';
@@ -0,0 +1,235 @@
+//! Command-line interface
+//!
+//! Without any arguments, the bot is started as usual. The other subcommands
+//! run maintenance tasks without connecting to the gateway, which makes them
+//! suitable for deployment pipelines. Each of them exits with a non-zero
+//! status code if it fails.
+
+use reqwest::Client;
+use serenity::{
+    http::Http,
+    model::{application::Command as ApplicationCommand, id::ApplicationId}
+};
+use std::time::Duration;
+
+use crate::{
+    config::{ConfigurationData, Integration},
+    constants::REQWEST_USER_AGENT,
+    database::{self, MigrationState},
+    error::TaliyahError,
+    interactions,
+    utils::{config::missing_keys, read_config_value}
+};
+
+const USAGE: &str = "Usage: taliyah [run | check-config | register-commands | doctor | migrate [up | status]]";
+
+/// How long each check of the doctor subcommand may take.
+const DOCTOR_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub enum Command {
+    /// Starts the bot.
+    Run,
+    /// Validates the configuration, reporting every problem found.
+    CheckConfig,
+    /// Registers the application commands with Discord.
+    RegisterCommands,
+    /// Checks connectivity to the database, Discord and each configured API.
+    Doctor,
+    /// Manages the database migrations.
+    Migrate(MigrateCommand)
+}
+
+pub enum MigrateCommand {
+    /// Applies every pending migration.
+    Up,
+    /// Lists the migrations and whether they have been applied.
+    Status
+}
+
+impl Command {
+    /// Parses the command from the arguments passed to the binary, excluding
+    /// the name of the binary itself.
+    pub fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let command = match arguments.next().as_deref() {
+            None | Some("run") => Command::Run,
+            Some("check-config") => Command::CheckConfig,
+            Some("register-commands") => Command::RegisterCommands,
+            Some("doctor") => Command::Doctor,
+            Some("migrate") => match arguments.next().as_deref() {
+                None | Some("up") => Command::Migrate(MigrateCommand::Up),
+                Some("status") => Command::Migrate(MigrateCommand::Status),
+                Some(other) => return Err(format!("Unknown migrate subcommand `{other}`.\n{USAGE}"))
+            },
+            Some(other) => return Err(format!("Unknown subcommand `{other}`.\n{USAGE}"))
+        };
+
+        match arguments.next() {
+            Some(extra) => Err(format!("Unexpected argument `{extra}`.\n{USAGE}")),
+            None => Ok(command)
+        }
+    }
+}
+
+/// Validates the configuration at the given path, printing every missing key
+/// and invalid value along with the state of each integration.
+pub fn check_config(path: &str) -> Result<(), TaliyahError> {
+    let value = read_config_value(path)?;
+
+    let missing = missing_keys(&value);
+    for key in &missing {
+        println!("missing: {key}");
+    }
+
+    if !missing.is_empty() {
+        return Err(format!("{} required key(s) are missing from the configuration.", missing.len()).into());
+    }
+
+    let config = value.try_into::<ConfigurationData>().map_err(|err| format!("Invalid configuration: {err}"))?;
+    let problems = config.problems();
+    for problem in &problems {
+        println!("invalid: {problem}");
+    }
+
+    for integration in Integration::ALL {
+        let state = if integration.is_configured(&config.api) {
+            "configured"
+        } else {
+            "not configured, its commands are disabled"
+        };
+        println!("{}: {state}", integration.name());
+    }
+
+    if problems.is_empty() {
+        println!("The configuration is valid.");
+        Ok(())
+    } else {
+        Err(format!("{} configuration value(s) are invalid.", problems.len()).into())
+    }
+}
+
+/// Registers the application commands globally, replacing any that were
+/// registered before.
+pub async fn register_commands(config: &ConfigurationData) -> Result<(), TaliyahError> {
+    let http = Http::new(&config.bot.discord.token);
+    http.set_application_id(ApplicationId::new(config.bot.discord.appid));
+
+    let commands = ApplicationCommand::set_global_commands(&http, interactions::commands(&config.api)).await?;
+    println!("Registered {} application command(s).", commands.len());
+
+    Ok(())
+}
+
+/// Checks connectivity to the database, Discord and each configured API,
+/// printing the result of every check.
+pub async fn doctor(config: &ConfigurationData) -> Result<(), TaliyahError> {
+    let client = Client::builder().user_agent(REQWEST_USER_AGENT).timeout(DOCTOR_TIMEOUT).build().map_err(|err| err.to_string())?;
+    let mut failures = 0;
+
+    let mut report = |name: &str, result: Result<String, String>| match result {
+        Ok(details) => println!("{name}: ok ({details})"),
+        Err(why) => {
+            failures += 1;
+            println!("{name}: failed ({why})");
+        }
+    };
+
+    report("PostgreSQL", check_database(config).await);
+    report("Discord", check_discord(config).await);
+
+    for integration in Integration::ALL.iter().copied() {
+        if integration.is_configured(&config.api) {
+            report(integration.name(), check_integration(&client, config, integration).await);
+        } else {
+            println!("{}: skipped (not configured)", integration.name());
+        }
+    }
+
+    if failures == 0 {
+        println!("Every check passed.");
+        Ok(())
+    } else {
+        Err(format!("{failures} check(s) failed.").into())
+    }
+}
+
+async fn check_database(config: &ConfigurationData) -> Result<String, String> {
+    let pool = database::connect(&config.bot.database).await.map_err(|err| err.to_string())?;
+    let migrations = database::migration_status(&pool).await.map_err(|err| err.to_string())?;
+    let count = |state: MigrationState| migrations.iter().filter(|migration| migration.state == state).count();
+    let (pending, outdated, modified) = (count(MigrationState::Pending), count(MigrationState::Outdated), count(MigrationState::Modified));
+
+    if modified > 0 {
+        Err(format!("{modified} migration(s) modified since they were applied"))
+    } else if pending + outdated > 0 {
+        Err(format!("{} migration(s) not up to date, run `migrate up`", pending + outdated))
+    } else {
+        Ok(format!("all {} migration(s) applied", migrations.len()))
+    }
+}
+
+async fn check_discord(config: &ConfigurationData) -> Result<String, String> {
+    let http = Http::new(&config.bot.discord.token);
+    let user = http.get_current_user().await.map_err(|err| err.to_string())?;
+    Ok(format!("logged in as {}", user.tag()))
+}
+
+async fn check_integration(client: &Client, config: &ConfigurationData, integration: Integration) -> Result<String, String> {
+    let api = &config.api;
+    let request = match integration {
+        #[cfg(feature = "music-lastfm")]
+        Integration::LastFm => client.get("https://ws.audioscrobbler.com/2.0/").query(&[
+            ("method", "chart.gettopartists"),
+            ("limit", "1"),
+            ("format", "json"),
+            ("api_key", api.lastfm().unwrap_or_default())
+        ]),
+        #[cfg(feature = "music-spotify")]
+        Integration::Spotify => {
+            let spotify = api.spotify().ok_or("Spotify is not configured.")?;
+            client
+                .post("https://accounts.spotify.com/api/token")
+                .basic_auth(&spotify.client_id, Some(&spotify.client_secret))
+                .form(&[("grant_type", "client_credentials")])
+        }
+        #[cfg(feature = "tmdb")]
+        Integration::Tmdb => client.get(format!("{}/configuration", api.urls.tmdb)).query(&[("api_key", api.tmdb().unwrap_or_default())]),
+        #[cfg(feature = "twitter")]
+        Integration::Twitter => client
+            .get(format!("{}/users/by/username/TwitterDev", api.urls.twitter))
+            .bearer_auth(api.twitter().unwrap_or_default())
+    };
+
+    let response = request.send().await.map_err(|err| err.to_string())?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(format!("HTTP {status}"))
+    } else {
+        Err(format!("HTTP {status}"))
+    }
+}
+
+/// Runs the given migrate subcommand, printing its results.
+pub async fn migrate(command: MigrateCommand, config: &ConfigurationData) -> Result<(), TaliyahError> {
+    let pool = database::connect(&config.bot.database).await?;
+
+    match command {
+        MigrateCommand::Up => {
+            database::run_migrations(&pool).await?;
+            println!("All migrations have been applied.");
+        }
+        MigrateCommand::Status => {
+            for migration in database::migration_status(&pool).await? {
+                let state = match migration.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::Outdated => "applied from an earlier version, `migrate up` updates its checksum",
+                    MigrationState::Modified => "modified since it was applied"
+                };
+
+                println!("{} {} ({state})", migration.version, migration.description);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Component listeners
+//!
+//! Paginators, selections and confirmations all wait for the person who used
+//! a command to press a button or choose from a menu below its response.
+//! [`ComponentListener`] does the waiting for them: it turns away anyone
+//! else, and removes the components once nobody has used them for a while.
+
+use serenity::{
+    builder::{CreateInteractionResponse, CreateInteractionResponseMessage, EditMessage},
+    client::Context,
+    model::{application::ComponentInteraction, channel::Message, id::UserId}
+};
+use std::time::Duration;
+use tracing::warn;
+
+pub struct ComponentListener {
+    message: Message,
+    author: UserId,
+    timeout: Duration,
+    refusal: &'static str,
+    expiry: Option<&'static str>
+}
+
+impl ComponentListener {
+    /// Listens to the components on the given message, waiting at most
+    /// `timeout` for each interaction. Anyone but `author` is told `refusal`.
+    pub fn new(message: Message, author: UserId, timeout: Duration, refusal: &'static str) -> ComponentListener {
+        ComponentListener {
+            message,
+            author,
+            timeout,
+            refusal,
+            expiry: None
+        }
+    }
+
+    /// Sets the text the message is replaced with once the components expire.
+    /// Without one, only the components are removed.
+    pub fn expiry(mut self, expiry: &'static str) -> ComponentListener {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// The message being listened to.
+    pub fn message(&mut self) -> &mut Message {
+        &mut self.message
+    }
+
+    /// Waits for the author to use one of the components, turning away
+    /// anyone else in the meantime. Returns `None` once the components have
+    /// gone unused for too long, after removing them.
+    pub async fn next(&mut self, context: &Context) -> Option<ComponentInteraction> {
+        while let Some(interaction) = self.message.await_component_interaction(&context.shard).timeout(self.timeout).await {
+            if interaction.user.id == self.author {
+                return Some(interaction);
+            }
+
+            let reply = CreateInteractionResponseMessage::new().content(self.refusal).ephemeral(true);
+            if let Err(why) = interaction.create_response(&context.http, CreateInteractionResponse::Message(reply)).await {
+                warn!("Unable to turn away a component interaction: {why}");
+            }
+        }
+
+        let mut expired = EditMessage::new().components(Vec::new());
+        if let Some(expiry) = self.expiry {
+            expired = expired.content(expiry);
+        }
+
+        if let Err(why) = self.message.edit(&context, expired).await {
+            warn!("Unable to remove expired components: {why}");
+        }
+
+        None
+    }
+}
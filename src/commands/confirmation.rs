@@ -0,0 +1,97 @@
+//! Confirmation prompts
+//!
+//! Commands that can't be undone ask the person who used them to confirm with
+//! a button before going ahead. The prompt is withdrawn if it isn't answered
+//! for a while, and nothing is done.
+
+use serenity::{
+    builder::{CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage},
+    client::Context,
+    framework::standard::CommandResult,
+    model::{application::ButtonStyle, channel::Message, id::UserId}
+};
+use std::{future::Future, pin::Pin, time::Duration};
+use tracing::warn;
+
+use super::{components::ComponentListener, response::Response};
+use crate::error::report;
+
+/// How long the prompt waits for an answer.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+const CONFIRM: &str = "confirmation_confirm";
+const CANCEL: &str = "confirmation_cancel";
+
+type Action = Box<dyn FnOnce(Context) -> Pin<Box<dyn Future<Output = CommandResult<Response>> + Send>> + Send + Sync>;
+
+pub struct Confirmation {
+    command: &'static str,
+    prompt: String,
+    label: String,
+    action: Action
+}
+
+impl Confirmation {
+    /// Creates a prompt that runs `action` once confirmed through a button with
+    /// the given label, replacing the prompt with its response.
+    pub fn new<F, Fut>(command: &'static str, prompt: impl Into<String>, label: impl Into<String>, action: F) -> Confirmation
+    where
+        F: FnOnce(Context) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = CommandResult<Response>> + Send + 'static
+    {
+        Confirmation {
+            command,
+            prompt: prompt.into(),
+            label: label.into(),
+            action: Box::new(move |context| Box::pin(action(context)))
+        }
+    }
+
+    /// The text asking for confirmation.
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    /// The buttons to confirm or cancel with.
+    pub fn components(&self) -> Vec<CreateActionRow> {
+        vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(CONFIRM).label(&self.label).style(ButtonStyle::Danger),
+            CreateButton::new(CANCEL).label("Cancel").style(ButtonStyle::Secondary),
+        ])]
+    }
+
+    /// Waits for the given user to answer the prompt on the given message,
+    /// running the action if they confirm. Answers by anyone else are turned
+    /// away.
+    pub fn listen(self, context: Context, message: Message, author: UserId) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            let mut listener =
+                ComponentListener::new(message, author, TIMEOUT, "Only the person who used this command can answer this.").expiry("No answer was given in time, so nothing was done.");
+
+            if let Some(interaction) = listener.next(&context).await {
+                if interaction.data.custom_id != CONFIRM {
+                    let update = CreateInteractionResponseMessage::new().content("Cancelled, so nothing was done.").components(Vec::new());
+                    if let Err(why) = interaction.create_response(&context.http, CreateInteractionResponse::UpdateMessage(update)).await {
+                        warn!("Unable to cancel a confirmation: {why}");
+                    }
+                    return;
+                }
+
+                // The action can take longer than Discord waits for a reply,
+                // so the interaction is acknowledged first.
+                if let Err(why) = interaction.defer(&context.http).await {
+                    warn!("Unable to acknowledge a confirmation: {why}");
+                }
+
+                let response = match (self.action)(context.clone()).await {
+                    Ok(response) => response,
+                    Err(why) => report(self.command, why.as_ref()).into()
+                };
+
+                if let Err(why) = response.edit(&context, listener.message(), author).await {
+                    warn!("Unable to show the result of a confirmation: {why}");
+                }
+            }
+        })
+    }
+}
@@ -1,6 +1,5 @@
-use crate::data::ReqwestContainer;
+use crate::{models::tokei::Statistics, services::get_services};
 use itertools::Itertools;
-use serde::Deserialize;
 use serenity::{
     builder::EditMessage,
     client::Context,
@@ -8,25 +7,10 @@ use serenity::{
     model::prelude::Message
 };
 
-#[derive(Deserialize)]
-struct Response {
-    languages: Vec<Language>,
-    total: Language
-}
-
-#[derive(Deserialize)]
-pub struct Language {
-    name: String,
-    files: u64,
-    lines: u64,
-    code: u64,
-    comments: u64,
-    blanks: u64
-}
-
 #[command("sloc")]
 #[description = "Fetches the source lines of code for a GitHub Repository. **Note**: Does not work with large repositories."]
 #[usage = "<username> <repository>"]
+#[example = "evelynmarie Taliyah"]
 #[delimiters("/", " ")]
 #[aliases("tokei")]
 async fn sloc(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
@@ -40,9 +24,21 @@ async fn sloc(context: &Context, message: &Message, mut arguments: Args) -> Comm
 
     let mut msg = message.channel_id.say(context, format!("Getting statistics for `{owner}/{name}`, please wait...")).await?;
 
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-    let request: Response = client.get(format!("https://tokei.vercel.app/{owner}/{name}")).send().await?.json().await?;
+    let content = get_statistics(context, &owner, &name).await?;
+
+    msg.edit(&context, EditMessage::new().content(content)).await?;
+
+    Ok(())
+}
+
+/// Retrieves the code statistics for the given repository.
+pub async fn get_statistics(context: &Context, owner: &str, name: &str) -> CommandResult<String> {
+    let statistics = get_services(context).await.code_counter.statistics(owner, name).await?;
+    Ok(statistics_message(owner, name, &statistics))
+}
 
+/// Formats the code statistics of the given repository.
+pub fn statistics_message(owner: &str, name: &str, request: &Statistics) -> String {
     let title = format!("**Code statistics for repository `{owner}/{name}`**:");
 
     let mut language_string: String = String::new();
@@ -61,7 +57,7 @@ async fn sloc(context: &Context, message: &Message, mut arguments: Args) -> Comm
 
     language_string.push_str(format!("{languages}\n\n").as_str());
 
-    let name = request.total.name;
+    let name = &request.total.name;
     let files = request.total.files;
     let lines = request.total.lines;
     let code_lines = request.total.code;
@@ -70,7 +66,5 @@ async fn sloc(context: &Context, message: &Message, mut arguments: Args) -> Comm
     let total = format!("**{name}**: {files} files, {lines} lines, {code_lines} code lines, {comments} comments, {blanks} blank lines");
     language_string.push_str(total.as_str());
 
-    msg.edit(&context, EditMessage::new().content(language_string)).await?;
-
-    Ok(())
+    language_string
 }
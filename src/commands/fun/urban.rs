@@ -1,34 +1,20 @@
-use serde::Deserialize;
-
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
 };
 
-use crate::data::ReqwestContainer;
-
-#[derive(Deserialize)]
-pub struct Response {
-    #[serde(rename = "list")]
-    definitions: Vec<Definition>
-}
-
-#[derive(Deserialize)]
-pub struct Definition {
-    #[serde(rename = "definition")]
-    description: String,
-    example: String,
-    word: String,
-    thumbs_up: usize,
-    thumbs_down: usize,
-    permalink: String
-}
+use crate::{
+    commands::{paginator::Paginator, response::Response},
+    models::urban::Definition,
+    services::get_services
+};
 
 #[command]
 #[description = "Looks up a definition from the Urban Dictionary."]
 #[usage = "<name of word>"]
+#[example = "yeet"]
 #[aliases("urbandict", "ud", "urban", "define")]
 async fn urban(context: &Context, message: &Message, arguments: Args) -> CommandResult {
     if arguments.rest().is_empty() {
@@ -36,47 +22,42 @@ async fn urban(context: &Context, message: &Message, arguments: Args) -> Command
         return Ok(());
     }
 
-    let term = arguments.rest();
-
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-    let request = client.get("https://api.urbandictionary.com/v0/define").query(&[("term", term)]).send().await?;
-    let response: Response = request.json().await?;
+    urban_response(context, arguments.rest()).await?.send(context, message).await?;
 
-    if response.definitions.is_empty() {
-        message.channel_id.say(context, format!("No definitions found for `{term}`. Try a different word.")).await?;
-        return Ok(());
-    }
+    Ok(())
+}
 
-    let definition = response.definitions.get(0).unwrap();
+#[command]
+#[description = "Gets a random definition from the Urban Dictionary."]
+async fn randefine(context: &Context, message: &Message) -> CommandResult {
+    randefine_response(context).await?.send(context, message).await?;
 
-    let word = &definition.word;
-    let description = &definition.description;
-    let example = &definition.example;
-    let permalink = &definition.permalink;
-    let thumbs_up = &definition.thumbs_up;
-    let thumbs_down = &definition.thumbs_down;
+    Ok(())
+}
 
-    let rating = format!("{thumbs_up} 👍 | {thumbs_down} 👎");
+/// Looks up the given term on the Urban Dictionary.
+pub async fn urban_response(context: &Context, term: &str) -> CommandResult<Response> {
+    let definitions = get_services(context).await.dictionary.define(term).await?;
 
-    let embed = CreateEmbed::new()
-        .author(CreateEmbedAuthor::new(word).url(permalink))
-        .color(0x00EF_FF00)
-        .description(format!("*{description}*\n\n{example}\n\n**Ratings**: {rating}"))
-        .footer(CreateEmbedFooter::new("Powered by the Urban Dictionary."));
+    if definitions.is_empty() {
+        return Ok(format!("No definitions found for `{term}`. Try a different word.").into());
+    }
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
+    let paginator = Paginator::new(definitions.iter().map(definition_embed).collect()).footer("Powered by the Urban Dictionary.");
 
-    Ok(())
+    Ok(Response::Paginated(paginator))
 }
 
-#[command]
-#[description = "Gets a random definition from the Urban Dictionary."]
-async fn randefine(context: &Context, message: &Message) -> CommandResult {
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-    let request = client.get("http://api.urbandictionary.com/v0/random").send().await?;
-    let response: Response = request.json().await?;
-    let definition = response.definitions.get(0).unwrap();
+/// Retrieves a random definition from the Urban Dictionary.
+pub async fn randefine_response(context: &Context) -> CommandResult<Response> {
+    let definitions = get_services(context).await.dictionary.random().await?;
+    let definition = definitions.first().ok_or("No random definition was returned.")?;
+
+    Ok(Response::embed(definition_embed(definition)))
+}
 
+/// Builds the embed showing the given definition.
+pub fn definition_embed(definition: &Definition) -> CreateEmbed {
     let word = &definition.word;
     let description = &definition.description;
     let example = &definition.example;
@@ -85,13 +66,32 @@ async fn randefine(context: &Context, message: &Message) -> CommandResult {
     let thumbs_down = &definition.thumbs_down;
     let rating = format!("{thumbs_up} 👍 | {thumbs_down} 👎");
 
-    let embed = CreateEmbed::new()
+    CreateEmbed::new()
         .author(CreateEmbedAuthor::new(word).url(permalink))
         .color(0x00EF_FF00)
         .description(format!("*{description}*\n\n{example}\n\n**Ratings**: {rating}"))
-        .footer(CreateEmbedFooter::new("Powered by the Urban Dictionary."));
-
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
+        .footer(CreateEmbedFooter::new("Powered by the Urban Dictionary."))
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::urban::UrbanResponse,
+        testing::{parse_fixture, to_json}
+    };
+
+    #[test]
+    fn definition_embed_shows_the_definition_and_ratings() {
+        let response: UrbanResponse = parse_fixture("urban");
+        let embed = to_json(&definition_embed(&response.definitions[0]));
+
+        assert_eq!(embed["author"]["name"], "yeet");
+        assert_eq!(embed["author"]["url"], "http://yeet.urbanup.com/10702289");
+        assert_eq!(
+            embed["description"],
+            "*A word used to express excitement or to throw something.*\n\n\"Yeet!\" he shouted, throwing the ball across the field.\n\n**Ratings**: 120 👍 | 30 👎"
+        );
+        assert_eq!(embed["footer"]["text"], "Powered by the Urban Dictionary.");
+    }
 }
@@ -1,35 +1,38 @@
-use crate::data::ReqwestContainer;
-use reqwest::StatusCode;
-use serde::Deserialize;
+use crate::{commands::response::Response, models::xkcd::XkcdComic, services::get_services};
 use serenity::{
-    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
 };
 
-#[derive(Debug, Deserialize)]
-struct XkcdComic {
-    num: u16,      // the numeric ID of the xkcd comic.
-    alt: String,   // the caption of the xkcd comic.
-    img: String,   // the image URL of the xkcd comic.
-    title: String  // the title of the xkcd comic.
-}
-
 /// Retrieves the latest or a given comic from xkcd.
 #[command]
+#[usage = "[number]"]
+#[example = "927"]
 async fn xkcd(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
     let comic_num = arguments.single::<u16>().unwrap_or(0);
-    let latest_comic = "https://xkcd.com/info.0.json";
-    let selected_comic = format!("https://xkcd.com/{comic_num}/info.0.json");
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-    let request = client.get(if comic_num == 0 { latest_comic } else { &selected_comic }).send().await?;
-    if request.status() == StatusCode::NOT_FOUND {
-        message.reply(context, "You did not provide a valid comic id.").await?;
-        return Ok(());
-    }
+    xkcd_response(context, comic_num).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Retrieves the given comic from xkcd, or the latest comic if `comic_num` is zero.
+pub async fn xkcd_response(context: &Context, comic_num: u16) -> CommandResult<Response> {
+    let number = (comic_num != 0).then_some(comic_num);
+    let comic = match get_services(context).await.comics.comic(number).await? {
+        Some(comic) => comic,
+        None => return Ok("You did not provide a valid comic id.".into())
+    };
+
+    let (embed, links) = comic_embed(&comic);
 
-    let response: XkcdComic = request.json().await?;
+    Ok(Response::Embed(embed, vec![links]))
+}
+
+/// Builds the embed showing the given comic, along with links to it and its
+/// explanation.
+pub fn comic_embed(response: &XkcdComic) -> (CreateEmbed, CreateActionRow) {
     let num = response.num;
     let page = format!("https://xkcd.com/{num}");
     let wiki = format!("https://explainxkcd.com/wiki/index.php/{num}");
@@ -42,7 +45,25 @@ async fn xkcd(context: &Context, message: &Message, mut arguments: Args) -> Comm
         .footer(CreateEmbedFooter::new(format!("xkcd comic no. {num}")));
 
     let links = CreateActionRow::Buttons(vec![CreateButton::new_link(page).label("View image on xkcd"), CreateButton::new_link(wiki).label("View wiki")]);
-    message.channel_id.send_message(context, CreateMessage::new().embed(embed).components(vec![links])).await?;
 
-    Ok(())
+    (embed, links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{parse_fixture, to_json};
+
+    #[test]
+    fn comic_embed_links_to_the_comic_and_its_explanation() {
+        let (embed, links) = comic_embed(&parse_fixture("xkcd"));
+        let (embed, links) = (to_json(&embed), to_json(&links));
+
+        assert_eq!(embed["title"], "Barrel - Part 1");
+        assert_eq!(embed["description"], "Don't we all.");
+        assert_eq!(embed["image"]["url"], "https://imgs.xkcd.com/comics/barrel_cropped_(1).jpg");
+        assert_eq!(embed["footer"]["text"], "xkcd comic no. 1");
+        assert_eq!(links["components"][0]["url"], "https://xkcd.com/1");
+        assert_eq!(links["components"][1]["url"], "https://explainxkcd.com/wiki/index.php/1");
+    }
 }
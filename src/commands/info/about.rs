@@ -1,11 +1,14 @@
 use crate::{
-    data::ConfigContainer,
-    utils::git::{get_current_branch, get_head_revision}
+    commands::response::Response,
+    utils::{
+        config::get_config,
+        git::{get_current_branch, get_head_revision}
+    }
 };
 
 use git2::Repository;
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, CommandResult},
     model::prelude::Message
@@ -14,8 +17,14 @@ use serenity::{
 #[command]
 #[aliases("info", "botinfo")]
 async fn about(context: &Context, message: &Message) -> CommandResult {
-    let data = context.data.read().await;
-    let config = data.get::<ConfigContainer>().unwrap();
+    about_response(context).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds the about embed containing Taliyah's version and statistics.
+pub async fn about_response(context: &Context) -> CommandResult<Response> {
+    let config = get_config(context).await;
     let repo = Repository::open(env!("CARGO_MANIFEST_DIR"))?;
 
     let version = env!("CARGO_PKG_VERSION").to_string();
@@ -54,7 +63,5 @@ async fn about(context: &Context, message: &Message) -> CommandResult {
         .fields(about_fields)
         .footer(CreateEmbedFooter::new("Written with Rust & serenity."));
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
-
-    Ok(())
+    Ok(Response::embed(embed))
 }
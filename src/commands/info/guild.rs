@@ -1,24 +1,33 @@
 use itertools::Itertools;
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, CommandResult},
     model::{
         channel::ChannelType,
-        prelude::{ExplicitContentFilter, Message, MfaLevel, PremiumTier, VerificationLevel}
+        prelude::{ExplicitContentFilter, GuildId, Message, MfaLevel, PremiumTier, VerificationLevel}
     }
 };
 
 use std::fmt::Write;
 
+use crate::commands::response::Response;
+
 #[command]
 #[description = "Shows various information about the current guild."]
 #[aliases("guild", "guildinfo", "ginfo", "server", "serverinfo", "serverstats", "sinfo")]
 #[only_in(guilds)]
 async fn guild(context: &Context, message: &Message) -> CommandResult {
+    let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
+    guild_response(context, guild_id).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed summarizing the given guild.
+pub async fn guild_response(context: &Context, guild_id: GuildId) -> CommandResult<Response> {
     let cache = &context.cache;
-    let guild_id = message.guild_id.unwrap();
     let guild_id_u64 = guild_id.get();
     let cached_guild = cache.guild(guild_id).unwrap().clone();
 
@@ -114,7 +123,5 @@ async fn guild(context: &Context, message: &Message) -> CommandResult {
         .description(&summary)
         .footer(CreateEmbedFooter::new(format!("{guild_name} server ID: {guild_id}")));
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
-
-    Ok(())
+    Ok(Response::embed(embed))
 }
@@ -1,33 +1,65 @@
-use lastfm_rs::{
-    error::{Error, LastFMErrorResponse::InvalidParameters},
-    Client
-};
+#[cfg(feature = "music-lastfm")]
+use lastfm_rs::error::{Error, LastFMErrorResponse::InvalidParameters};
 
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateMessage},
+    builder::{CreateAttachment, CreateEmbed, CreateEmbedAuthor, CreateMessage},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::colour::Colour,
-    model::prelude::Message
+    model::{guild::Member, prelude::Message, user::User}
 };
 
 use crate::{
-    read_config,
-    utils::{get_profile_field, parsing::parse_user},
-    DatabasePool
+    commands::{confirmation::Confirmation, response::Response},
+    models::profile::{LinkedAccount, PrivacyTarget, ProfileField, Visibility},
+    utils::{
+        parsing::parse_user,
+        profiles::{
+            clear_profile_field, delete_user_data, export_user_data, get_linked_accounts, get_privacy_settings, get_visible_linked_accounts, get_visible_profile, link_account,
+            set_profile_field, set_visibility, unlink_account, Viewer
+        }
+    }
 };
 
+#[cfg(feature = "twitter")]
+use crate::services::get_services;
+#[cfg(any(feature = "music-lastfm", feature = "twitter"))]
+use crate::utils::config::get_config;
+#[cfg(feature = "music-lastfm")]
+use crate::utils::net::get_lastfm_client;
+
 const LASTFM_USER_BASE: &str = "https://www.last.fm/user";
 
+/// Shown in place of the fields a user hasn't set.
+const NOT_SET: &str = "Not set";
+
+/// The property linked accounts are set and unset through.
+const ACCOUNT: &str = "account";
+
+/// The longest bio a user can set.
+const BIO_LIMIT: usize = 300;
+
+/// The most accounts a user can link.
+const MAX_LINKED_ACCOUNTS: usize = 8;
+
+/// The longest name a service or linked account can have.
+const ACCOUNT_LIMIT: usize = 32;
+
+/// A leap year, so that birthdays on February 29th without a year are valid.
+const LEAP_YEAR: i32 = 2000;
+
 #[command]
 #[usage = "<user> or <blank>"]
-#[sub_commands(set)]
+#[example = "@someone"]
+#[sub_commands(set, unset, privacy, export, delete)]
 #[only_in(guilds)]
 /// Shows the profile of a given user.
 ///
 /// To set your profile parameters, use the set command.
 async fn profile(context: &Context, message: &Message, arguments: Args) -> CommandResult {
-    let cache = &context.cache;
     let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
     let member = if message.mentions.is_empty() {
         if arguments.is_empty() {
@@ -42,6 +74,16 @@ async fn profile(context: &Context, message: &Message, arguments: Args) -> Comma
         guild_id.member(&context, message.mentions.first().ok_or("Failed to get user mentioned.")?).await?
     };
 
+    let viewer = Viewer::new(message.author.id, message.guild_id);
+    profile_response(context, member, viewer).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds the profile embed for the given guild member, leaving out anything
+/// the viewer isn't allowed to see.
+pub async fn profile_response(context: &Context, member: Member, viewer: Viewer) -> CommandResult<Response> {
+    let cache = &context.cache;
     let color = if member.colour(cache).is_none() {
         Colour::new(0x00FF_FFFF)
     } else {
@@ -49,35 +91,45 @@ async fn profile(context: &Context, message: &Message, arguments: Args) -> Comma
     };
 
     let user_name = member.user.tag();
-    let user_id = member.user.id;
-
-    let name = get_profile_field(context, "user_name", user_id).await?;
-    let location = get_profile_field(context, "user_location", user_id).await?;
-    let gender = get_profile_field(context, "user_gender", user_id).await?;
-    let pronouns = get_profile_field(context, "user_pronouns", user_id).await?;
-    let lastfm = get_profile_field(context, "user_lastfm_id", user_id).await?;
-    let lastfm_url = format!("[{lastfm}]({LASTFM_USER_BASE}/{lastfm})");
-
-    let profile_fields = vec![
-        ("Name", name, true),
-        ("Location", location, true),
-        ("Gender", gender, true),
-        ("Pronouns", pronouns, true),
-        ("Last.fm", lastfm_url, false),
-    ];
-
-    let embed = CreateEmbed::new()
+    let profile = get_visible_profile(context, member.user.id, &viewer).await?.unwrap_or_default();
+    let accounts = get_visible_linked_accounts(context, member.user.id, &viewer).await?;
+
+    let profile_fields: Vec<_> = ProfileField::ALL
+        .into_iter()
+        .filter(|field| *field != ProfileField::Bio)
+        .map(|field| {
+            let value = match profile.get(field) {
+                Some(value) => display_value(field, value),
+                None => NOT_SET.to_string()
+            };
+
+            (field.label(), value, field != ProfileField::LastFm)
+        })
+        .collect();
+
+    let mut embed = CreateEmbed::new()
         .author(CreateEmbedAuthor::new(format!("Profile for: {user_name}")).icon_url(member.user.face()))
         .color(color)
         .fields(profile_fields);
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
+    if let Some(bio) = profile.bio {
+        embed = embed.description(bio);
+    }
 
-    Ok(())
+    if !accounts.is_empty() {
+        embed = embed.field("Linked Accounts", accounts.iter().map(display_account).join("\n"), false);
+    }
+
+    Ok(Response::embed(embed))
 }
 
 #[command]
 #[only_in(guilds)]
+#[usage = "<property> <value>"]
+#[example = "pronouns they/them"]
+#[example = "timezone Europe/London"]
+#[example = "birthday 03-14"]
+#[example = "account steam gaben"]
 /// Sets your profile parameters. Available parameters are below.
 ///
 /// `name`: Sets your display name. This is used in multiple places; mainly the profile and Last.fm commands.
@@ -85,107 +137,323 @@ async fn profile(context: &Context, message: &Message, arguments: Args) -> Comma
 /// `gender`: Sets your gender. No forced gender options, so use what you want as long as its Male / Female, or non-binary.
 /// `pronouns`: Sets your pronouns. This doesn't have any forced pronoun options, however please stick to the normal ones.
 /// `lastfm`: Sets your Last.fm username. Used for the Last.fm command for listing Last.fm statistics.
+/// `bio`: Sets a short description of yourself, shown at the top of your profile.
+/// `timezone`: Sets your time zone, e.g. `Europe/London` or `America/New_York`.
+/// `birthday`: Sets your birthday, as `YYYY-MM-DD`, or `MM-DD` to leave out the year.
+/// `account`: Links an account from another service, e.g. `account steam <username>`.
 async fn set(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
-    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
     let property = arguments.single::<String>()?;
-    let value = arguments.rest();
-    let config = read_config("config.toml");
-    let user_id = message.author.id.get() as i64;
-
-    match property.as_str() {
-        "location" => {
-            if value.is_empty() {
-                message.channel_id.say(&context, "You did not provide a location. Please provide one!").await?;
-                return Ok(());
-            };
+    let response = if property.eq_ignore_ascii_case(ACCOUNT) {
+        let service = arguments.single::<String>().unwrap_or_default();
+        link_response(context, &message.author, &service, arguments.rest()).await?
+    } else {
+        set_response(context, &message.author, &property, arguments.rest()).await?
+    };
+
+    response.send(context, message).await?;
+
+    Ok(())
+}
 
-            sqlx::query("UPDATE profile_data SET user_location = $1 WHERE user_id = $2;")
-                .bind(value)
-                .bind(user_id)
-                .execute(&pool)
-                .await
-                .unwrap();
+/// Sets the given profile property for the given user.
+pub async fn set_response(context: &Context, user: &User, property: &str, value: &str) -> CommandResult<Response> {
+    let field = match ProfileField::parse(property) {
+        Some(field) => field,
+        None => return Ok("That is not a valid profile property.".into())
+    };
+
+    if value.is_empty() {
+        let response = match field {
+            ProfileField::Name => "You did not provide a name. Please provide one!",
+            ProfileField::Location => "You did not provide a location. Please provide one!",
+            ProfileField::Gender => "You did not provide your gender. Please provide it.",
+            ProfileField::Pronouns => "You did not provide any pronouns. Please provide them.",
+            ProfileField::LastFm => "You did not provide your Last.fm username. Please provide one!",
+            ProfileField::Bio => "You did not provide a bio. Please provide one!",
+            ProfileField::Timezone => "You did not provide a time zone. Please provide one, e.g. `Europe/London`.",
+            ProfileField::Birthday => "You did not provide your birthday. Please provide it as `YYYY-MM-DD` or `MM-DD`."
+        };
+
+        return Ok(response.into());
+    }
 
-            message.channel_id.say(&context, format!("Your location has been set to `{value}`.")).await?;
+    let value = match field {
+        ProfileField::Bio if value.chars().count() > BIO_LIMIT => {
+            return Ok(format!("Your bio cannot be longer than {BIO_LIMIT} characters.").into());
         }
-        "lastfm" => {
-            if value.is_empty() {
-                message.channel_id.say(&context, "You did not provide your Last.fm username. Please provide one!").await?;
-                return Ok(());
-            };
+        ProfileField::Timezone => match value.parse::<Tz>() {
+            Ok(timezone) => timezone.name().to_string(),
+            Err(_) => return Ok("That is not a valid time zone. Please use a name like `Europe/London` or `America/New_York`.".into())
+        },
+        ProfileField::Birthday => match parse_birthday(value) {
+            Some(birthday) => birthday,
+            None => return Ok("That is not a valid birthday. Please provide it as `YYYY-MM-DD`, or `MM-DD` to leave out the year.".into())
+        },
+        _ => value.to_string()
+    };
 
-            let api_key = config.api.music.lastfm.api_key;
-            let mut client: Client = Client::new(&api_key);
-
-            match client.user_info(value).await.send().await {
-                Ok(_) => (),
-                Err(e) => {
-                    if let Error::LastFMError(InvalidParameters(e)) = e {
-                        if let "User not found" = e.message.as_str() {
-                            message.channel_id.say(context, "You cannot use this as your username.").await?;
-                        }
-                    }
+    // The username can only be checked against Last.fm if the integration is configured.
+    #[cfg(feature = "music-lastfm")]
+    if field == ProfileField::LastFm {
+        if get_config(context).await.api.lastfm().is_some() {
+            let (mut client, _permit) = get_lastfm_client(context).await;
+            if let Err(Error::LastFMError(InvalidParameters(e))) = client.user_info(&value).await.send().await {
+                if let "User not found" = e.message.as_str() {
+                    return Ok("You cannot use this as your username.".into());
                 }
             }
+        }
+    }
 
-            sqlx::query("UPDATE profile_data SET user_lastfm_id = $1 WHERE user_id = $2")
-                .bind(value)
-                .bind(user_id)
-                .execute(&pool)
-                .await
-                .unwrap();
+    set_profile_field(context, user, field, &value).await?;
 
-            message.channel_id.say(&context, format!("Your Last.fm username has been set to `{value}`.")).await?;
-        }
-        "name" => {
-            if value.is_empty() {
-                message.channel_id.say(&context, "You did not provide a name. Please provide one!").await?;
-                return Ok(());
-            };
+    let response = match field {
+        ProfileField::Name => format!("Your name has been set to {value}."),
+        ProfileField::Location => format!("Your location has been set to `{value}`."),
+        ProfileField::Gender => format!("Your gender has been set to {value}."),
+        ProfileField::Pronouns => format!("Your pronouns have been set to {value}."),
+        ProfileField::LastFm => format!("Your Last.fm username has been set to `{value}`."),
+        ProfileField::Bio => "Your bio has been set.".to_string(),
+        ProfileField::Timezone => format!("Your time zone has been set to `{value}`."),
+        ProfileField::Birthday => format!("Your birthday has been set to {}.", display_value(field, &value))
+    };
 
-            sqlx::query("UPDATE profile_data SET user_name = $1 WHERE user_id = $2")
-                .bind(value)
-                .bind(user_id)
-                .execute(&pool)
-                .await
-                .unwrap();
+    Ok(response.into())
+}
 
-            message.channel_id.say(&context, format!("Your name has been set to {value}.")).await?;
-        }
-        "gender" => {
-            if value.is_empty() {
-                message.channel_id.say(&context, "You did not provide your gender. Please provide it.").await?;
-                return Ok(());
-            };
+/// Links an account from the given service to the given user's profile.
+pub async fn link_response(context: &Context, user: &User, service: &str, account: &str) -> CommandResult<Response> {
+    let service = service.to_lowercase();
+    if service.is_empty() || account.is_empty() {
+        return Ok("Please provide the service and your username on it, e.g. `account steam gaben`.".into());
+    }
 
-            sqlx::query("UPDATE profile_data SET user_gender = $1 WHERE user_id = $2")
-                .bind(value)
-                .bind(user_id)
-                .execute(&pool)
-                .await
-                .unwrap();
+    if !is_valid_service(&service) {
+        return Ok("Service names can only contain letters, numbers, dashes and dots.".into());
+    }
 
-            message.channel_id.say(&context, format!("Your gender has been set to {value}.")).await?;
+    if account.chars().count() > ACCOUNT_LIMIT {
+        return Ok(format!("Account names cannot be longer than {ACCOUNT_LIMIT} characters.").into());
+    }
+
+    let linked = LinkedAccount {
+        service,
+        account: account.to_string()
+    };
+
+    // Accounts end up in profile links and embeds, so they can only contain
+    // what the service itself allows.
+    match linked.known_service() {
+        Some(known) if !known.is_valid_account(account) => {
+            return Ok(format!("{} accounts can only contain {} and be up to {} characters long.", known.name, known.allowed, known.max_length).into());
         }
-        "pronouns" => {
-            if value.is_empty() {
-                message.channel_id.say(context, "You did not provide any pronouns. Please provide them.").await?;
-                return Ok(());
-            }
+        None if !is_valid_account(account) => {
+            return Ok("Account names can only contain letters, numbers, spaces, dashes, dots, underscores and hashes.".into());
+        }
+        _ => {}
+    }
+
+    let accounts = get_linked_accounts(context, user.id).await?;
+    if accounts.len() >= MAX_LINKED_ACCOUNTS && !accounts.iter().any(|other| other.service == linked.service) {
+        return Ok(format!("You cannot link more than {MAX_LINKED_ACCOUNTS} accounts. Unlink one first.").into());
+    }
+
+    // Twitter accounts can only be checked if the integration is configured.
+    #[cfg(feature = "twitter")]
+    if linked.service == "twitter" && get_config(context).await.api.twitter().is_some() && get_services(context).await.microblog.user(account).await.is_err() {
+        return Ok("Could not find that Twitter account.".into());
+    }
+
+    link_account(context, user, &linked.service, account).await?;
+
+    Ok(format!("Your {} account has been linked.", linked.service_name()).into())
+}
+
+#[command]
+#[only_in(guilds)]
+#[usage = "<property>"]
+#[example = "location"]
+#[example = "account steam"]
+/// Clears one of your profile parameters, or unlinks an account with `account <service>`.
+async fn unset(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
+    let property = arguments.single::<String>()?;
+    unset_response(context, &message.author, &property, arguments.rest()).await?.send(context, message).await?;
 
-            sqlx::query("UPDATE profile_data SET user_pronouns = $1 WHERE user_id = $2")
-                .bind(value)
-                .bind(user_id)
-                .execute(&pool)
-                .await
-                .unwrap();
+    Ok(())
+}
 
-            message.channel_id.say(&context, format!("Your pronouns have been set to {value}.")).await?;
+/// Clears the given profile property for the given user. For linked
+/// accounts, `service` names the service to unlink.
+pub async fn unset_response(context: &Context, user: &User, property: &str, service: &str) -> CommandResult<Response> {
+    if property.eq_ignore_ascii_case(ACCOUNT) {
+        let service = service.trim().to_lowercase();
+        if service.is_empty() {
+            return Ok("Please provide the service to unlink, e.g. `account steam`.".into());
         }
-        _ => {
-            message.channel_id.say(&context, "That is not a valid profile property.").await?;
+
+        if unlink_account(context, user.id, &service).await? {
+            return Ok(format!("Your `{service}` account has been unlinked.").into());
         }
+
+        return Ok(format!("You have not linked a `{service}` account.").into());
+    }
+
+    let field = match ProfileField::parse(property) {
+        Some(field) => field,
+        None => return Ok("That is not a valid profile property.".into())
+    };
+
+    clear_profile_field(context, user.id, field).await?;
+
+    Ok(format!("Your {} has been cleared.", field.label().to_lowercase()).into())
+}
+
+#[command]
+#[only_in(guilds)]
+#[usage = "[property] [public | guild | private]"]
+#[example = "location guild"]
+#[example = "accounts private"]
+/// Shows or changes who can see each part of your profile.
+///
+/// `public`: Anyone can see it.
+/// `guild`: Only members of a server you're in can see it, from within that server.
+/// `private`: Only you can see it.
+///
+/// Linked accounts are set together, as `accounts`.
+async fn privacy(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
+    let property = arguments.single::<String>().ok();
+    let visibility = arguments.single::<String>().ok();
+    privacy_response(context, &message.author, property.as_deref(), visibility.as_deref())
+        .await?
+        .send(context, message)
+        .await?;
+
+    Ok(())
+}
+
+/// Sets who can see the given property of the given user's profile, or shows
+/// their current settings if no property is given.
+pub async fn privacy_response(context: &Context, user: &User, property: Option<&str>, visibility: Option<&str>) -> CommandResult<Response> {
+    let property = match property {
+        Some(property) => property,
+        None => {
+            let settings = get_privacy_settings(context, user.id).await?;
+            let fields: Vec<_> = PrivacyTarget::all().map(|target| (target.label(), settings.visibility(target).description(), true)).collect();
+
+            let embed = CreateEmbed::new()
+                .title("Profile Privacy")
+                .description("Who can see each part of your profile. Use `profile privacy <property> <public | guild | private>` to change it.")
+                .fields(fields);
+
+            return Ok(Response::embed(embed));
+        }
+    };
+
+    let target = match PrivacyTarget::parse(property) {
+        Some(target) => target,
+        None => return Ok("That is not a valid profile property.".into())
+    };
+
+    let visibility = match visibility.and_then(Visibility::parse) {
+        Some(visibility) => visibility,
+        None => return Ok("Please choose who can see it: `public`, `guild` or `private`.".into())
+    };
+
+    set_visibility(context, user, target, visibility).await?;
+
+    Ok(format!("{} on your profile can now be seen by: {}.", target.label(), visibility.description().to_lowercase()).into())
+}
+
+#[command]
+/// Sends you a file with all of the data stored about you.
+async fn export(context: &Context, message: &Message) -> CommandResult {
+    export_response(context, &message.author).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Sends the given user every row stored about them as JSON, through a DM.
+pub async fn export_response(context: &Context, user: &User) -> CommandResult<Response> {
+    let data = export_user_data(context, user.id).await?;
+    let json = serde_json::to_string_pretty(&data)?;
+    let attachment = CreateAttachment::bytes(json.into_bytes(), format!("taliyah-data-{}.json", user.id));
+    let dm = CreateMessage::new().content("Here is all of the data stored about you.").add_file(attachment);
+
+    match user.direct_message(context, dm).await {
+        Ok(_) => Ok("I've sent you a DM with all of the data stored about you.".into()),
+        Err(_) => Ok("I couldn't DM you. Please allow direct messages from server members and try again.".into())
     }
+}
+
+#[command]
+/// Deletes all of the data stored about you.
+///
+/// Your profile, linked accounts, privacy settings and pending reminders are
+/// removed, and you won't be added back when you send messages. Setting up
+/// your profile again undoes this.
+async fn delete(context: &Context, message: &Message) -> CommandResult {
+    delete_response(&message.author).send(context, message).await?;
 
     Ok(())
 }
+
+/// Asks the given user to confirm the deletion of their data.
+pub fn delete_response(user: &User) -> Response {
+    let user_id = user.id;
+    let prompt = "This deletes your profile, linked accounts, privacy settings and pending reminders, and can't be undone. \
+        You also won't be added back when you send messages. Are you sure?";
+
+    Response::Confirmation(Confirmation::new("profile delete", prompt, "Delete my data", move |context| async move {
+        delete_user_data(&context, user_id).await?;
+        Ok("Your data has been deleted. Setting up your profile again will store it anew.".into())
+    }))
+}
+
+/// Formats the value of a field for display on a profile.
+fn display_value(field: ProfileField, value: &str) -> String {
+    match field {
+        ProfileField::LastFm => format!("[{value}]({LASTFM_USER_BASE}/{value})"),
+        ProfileField::Timezone => match value.parse::<Tz>() {
+            Ok(timezone) => format!("{value} ({})", Utc::now().with_timezone(&timezone).format("%H:%M")),
+            Err(_) => value.to_string()
+        },
+        ProfileField::Birthday => match value.len() {
+            5 => NaiveDate::parse_from_str(&format!("{LEAP_YEAR}-{value}"), "%Y-%m-%d").map(|date| date.format("%B %-d").to_string()),
+            _ => NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| date.format("%B %-d, %Y").to_string())
+        }
+        .unwrap_or_else(|_| value.to_string()),
+        _ => value.to_string()
+    }
+}
+
+/// Formats a linked account, linking to its profile where possible.
+fn display_account(account: &LinkedAccount) -> String {
+    match account.url() {
+        Some(url) => format!("**{}**: [{}]({url})", account.service_name(), account.account),
+        None => format!("**{}**: {}", account.service_name(), account.account)
+    }
+}
+
+/// Parses a birthday given as `YYYY-MM-DD` or `MM-DD`, returning it in the
+/// same form with any missing zeroes added. Dates in the future are refused.
+fn parse_birthday(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return (date <= Utc::now().naive_utc().date()).then(|| date.format("%Y-%m-%d").to_string());
+    }
+
+    NaiveDate::parse_from_str(&format!("{LEAP_YEAR}-{value}"), "%Y-%m-%d")
+        .ok()
+        .map(|date| date.format("%m-%d").to_string())
+}
+
+/// Whether a service name is made up of characters safe to show and store.
+fn is_valid_service(service: &str) -> bool {
+    service.chars().count() <= ACCOUNT_LIMIT && service.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+/// Whether an account on a service the bot doesn't know is made up of
+/// characters that can't be mistaken for formatting.
+fn is_valid_account(account: &str) -> bool {
+    account.chars().all(|c| c.is_alphanumeric() || " -._#".contains(c))
+}
@@ -1,12 +1,14 @@
-use crate::utils::parsing::parse_user;
+use crate::{commands::response::Response, utils::parsing::parse_user};
 use itertools::Itertools;
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedAuthor},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::{
         colour::Colour,
         gateway::{Activity, ActivityType},
+        guild::Member,
+        id::GuildId,
         prelude::Message,
         user::OnlineStatus
     }
@@ -16,12 +18,11 @@ use tracing::info;
 #[command]
 #[description = "Shows various information about a user."]
 #[usage = "<user> or <blank>"]
+#[example = "@someone"]
 #[aliases("user", "userinfo", "uinfo", "u")]
 #[only_in("guilds")]
 async fn user(context: &Context, message: &Message, args: Args) -> CommandResult {
-    let cache = &context.cache.clone();
     let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
-    let cached_guild = cache.guild(guild_id).ok_or("Unable to retrieve guild")?.clone();
     let member = if message.mentions.is_empty() {
         if args.is_empty() {
             message.member(&context).await.map_err(|_| "Could not find member.")?
@@ -35,6 +36,15 @@ async fn user(context: &Context, message: &Message, args: Args) -> CommandResult
         guild_id.member(&context, message.mentions.first().ok_or("Failed to get user mentioned.")?).await?
     };
 
+    user_response(context, guild_id, member).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed containing information about the given guild member.
+pub async fn user_response(context: &Context, guild_id: GuildId, member: Member) -> CommandResult<Response> {
+    let cache = &context.cache.clone();
+    let cached_guild = cache.guild(guild_id).ok_or("Unable to retrieve guild")?.clone();
     let user = &member.user;
 
     let mut track_art = String::new();
@@ -222,7 +232,5 @@ async fn user(context: &Context, message: &Message, args: Args) -> CommandResult
             **Roles ({role_count})**: {roles}"
         ));
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
-
-    Ok(())
+    Ok(Response::embed(embed))
 }
@@ -1,8 +1,20 @@
+pub mod components;
+pub mod confirmation;
+#[cfg(feature = "extra")]
 pub mod extra;
+#[cfg(feature = "fun")]
 pub mod fun;
+#[cfg(feature = "info")]
 pub mod info;
+#[cfg(feature = "moderation")]
 pub mod moderation;
+#[cfg(any(feature = "music-lastfm", feature = "music-spotify"))]
 pub mod music;
+pub mod paginator;
+pub mod response;
+#[cfg(feature = "tmdb")]
 pub mod search;
+pub mod selection;
+#[cfg(feature = "twitter")]
 pub mod social;
 pub mod utilities;
@@ -1,38 +1,398 @@
-use crate::utils::parsing::parse_user;
+use crate::{
+    commands::{confirmation::Confirmation, paginator::Paginator, response::Response},
+    models::infraction::{Action, Infraction},
+    utils::{
+        infractions::*,
+        parsing::{parse_user, parse_user_id},
+        time::{format_duration, parse_duration}
+    }
+};
+use chrono::Utc;
 use serenity::{
+    builder::CreateEmbed,
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::channel::Message
+    http::HttpError,
+    model::{
+        channel::Message,
+        id::{GuildId, UserId},
+        user::User
+    }
 };
+use std::collections::{HashMap, HashSet};
+use tracing::{error, warn};
+
+use super::cases::{check_reason, preview_reason};
+
+/// How many bans are listed per page.
+const PAGE_SIZE: usize = 10;
+
+/// The most users that can be banned at once.
+pub const MASSBAN_LIMIT: usize = 50;
 
 #[command("ban")]
-#[usage = "<member>"]
+#[usage = "<user> [reason]"]
+#[example = "@someone"]
+#[example = "123456789012345678 Raiding"]
 #[required_permissions(BAN_MEMBERS)]
+#[only_in(guilds)]
 #[min_args(1)]
-/// Bans the given member from the server.
+/// Bans the given user from the server. Users who aren't members can be
+/// banned by their ID.
 async fn ban(context: &Context, message: &Message, mut args: Args) -> CommandResult {
-    if message.is_private() {
-        message.channel_id.say(context, "You can't kick anyone in private messages!").await?;
-        return Ok(());
+    let guild_id = message.guild_id.unwrap();
+    let mention = args.single_quoted::<String>()?;
+    let reason = args.remains();
+
+    let response = match resolve_user(context, guild_id, &mention).await {
+        Some(user) => ban_member(context, guild_id, user, message.author.id, reason).await?,
+        None => "Could not find that user.".to_string()
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Bans the given user from the given guild and records it as a case,
+/// returning a confirmation message. Any temporary ban of the user is made
+/// permanent.
+pub async fn ban_member(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, reason: Option<&str>) -> CommandResult<String> {
+    if let Some(error) = check_reason(reason) {
+        return Ok(error);
     }
 
+    let (user, case_id) = ban_user(context, guild_id, user, moderator, reason).await?;
+
+    let name = &user.name;
+    let disc = &user.discriminator;
+    let id = &user.id;
+    if let Some(reason) = reason {
+        return Ok(format!("Banned user `{name}#{disc}` with id `{id}` for reason `{reason}`! Recorded as case #{case_id}."));
+    }
+
+    Ok(format!("Banned user `{name}#{disc}` with id `{id}`. Recorded as case #{case_id}."))
+}
+
+#[command("tempban")]
+#[usage = "<user> <duration> [reason]"]
+#[example = "@someone 1d12h Spamming"]
+#[required_permissions(BAN_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(2)]
+/// Bans the given user from the server for a while. The ban is lifted
+/// automatically once it expires, even if the bot was restarted in between.
+async fn tempban(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
     let mention = args.single_quoted::<String>()?;
+    let duration = args.single::<String>()?;
+    let reason = args.remains();
+
+    let response = match (resolve_user(context, guild_id, &mention).await, parse_duration(&duration)) {
+        (None, _) => "Could not find that user.".to_string(),
+        (_, None) => "Please give a valid duration, e.g. `1h` or `1d12h`.".to_string(),
+        (Some(user), Some(duration)) => tempban_member(context, guild_id, user, message.author.id, duration.num_seconds(), reason).await?
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Bans the given user from the given guild for the given number of seconds
+/// and records it as a case, returning a confirmation message. A temporary
+/// ban replaces any earlier one, but users who are banned permanently are
+/// left as they are, as their ban would otherwise be lifted when this one
+/// expires.
+pub async fn tempban_member(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, duration: i64, reason: Option<&str>) -> CommandResult<String> {
+    if let Some(error) = check_reason(reason) {
+        return Ok(error);
+    }
+
+    let banned = is_banned(context, guild_id, user).await?;
+    if banned && !is_unban_scheduled(context, guild_id, user).await? {
+        return Ok(format!(
+            "<@{user}> is already banned from this server permanently. Unban them first to ban them temporarily instead."
+        ));
+    }
+
+    let user = apply_ban(context, guild_id, user, reason).await?;
+
+    let name = &user.name;
+    let disc = &user.discriminator;
+    let id = &user.id;
+    let length = format_duration(duration);
+    let expires_at = Utc::now().timestamp() + duration;
+    let case_id = match record_tempban(context, guild_id, user.id, moderator, reason, expires_at).await {
+        Ok(case_id) => case_id,
+        Err(why) => {
+            // A ban that isn't recorded would never be lifted, so it is undone.
+            // Users who were banned temporarily already keep their earlier ban,
+            // which is still scheduled to be lifted.
+            error!("Unable to record the temporary ban of user {id} in guild {guild_id}: {why}");
+            if banned {
+                return Ok(format!("Could not record the ban of `{name}#{disc}`, so their earlier temporary ban stays as it was."));
+            }
+
+            return match guild_id.unban(context, user.id).await {
+                Ok(()) => Ok(format!("Could not record the ban of `{name}#{disc}`, so they have been unbanned again. Please try again.")),
+                Err(unban_why) => {
+                    error!("Unable to undo the unrecorded ban of user {id} in guild {guild_id}: {unban_why}");
+                    Ok(format!(
+                        "Could not record the ban of `{name}#{disc}`, nor undo it. It won't be lifted on its own, so please unban them by hand."
+                    ))
+                }
+            };
+        }
+    };
+
+    if let Some(reason) = reason {
+        return Ok(format!(
+            "Banned user `{name}#{disc}` with id `{id}` for {length} for reason `{reason}`! Recorded as case #{case_id}."
+        ));
+    }
+
+    Ok(format!("Banned user `{name}#{disc}` with id `{id}` for {length}. Recorded as case #{case_id}."))
+}
+
+#[command("massban")]
+#[usage = "<ids…> [reason]"]
+#[example = "123456789012345678 234567890123456789 Raiding"]
+#[required_permissions(BAN_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Bans every user with the given IDs from the server, after asking you to
+/// confirm. Anything after the IDs is used as the reason.
+async fn massban(context: &Context, message: &Message, mut args: Args) -> CommandResult {
     let guild_id = message.guild_id.unwrap();
-    let user = parse_user(&mention, guild_id, context).await.unwrap();
-    let guild = message.guild(&context.cache).unwrap().clone();
-    let member = guild.member(context, user).await.unwrap();
+    let mut users = Vec::new();
+    while let Some(user) = args.current().and_then(parse_user_id) {
+        users.push(user);
+        args.advance();
+    }
 
-    let name = &member.user.name;
-    let disc = &member.user.discriminator;
-    let id = &member.user.id;
-    let reason = args.remains();
+    let reason = args.remains().map(str::to_string);
+    massban_response(guild_id, users, message.author.id, reason).send(context, message).await?;
+
+    Ok(())
+}
+
+/// Asks for confirmation before banning every one of the given users.
+pub fn massban_response(guild_id: GuildId, mut users: Vec<UserId>, moderator: UserId, reason: Option<String>) -> Response {
+    let mut seen = HashSet::new();
+    users.retain(|user| seen.insert(*user));
+
+    if users.is_empty() {
+        return "Please give the IDs of the users to ban.".into();
+    }
+
+    if users.len() > MASSBAN_LIMIT {
+        return format!("You can ban at most {MASSBAN_LIMIT} users at once.").into();
+    }
+
+    if let Some(error) = check_reason(reason.as_deref()) {
+        return error.into();
+    }
+
+    let count = users.len();
+    let prompt = match &reason {
+        Some(reason) => format!("This bans {count} user(s) for reason `{reason}`. Are you sure?"),
+        None => format!("This bans {count} user(s). Are you sure?")
+    };
+
+    Response::Confirmation(Confirmation::new("massban", prompt, format!("Ban {count} user(s)"), move |context| async move {
+        let mut cases = Vec::with_capacity(count);
+        let mut failed = Vec::new();
+        for user in users {
+            match ban_user(&context, guild_id, user, moderator, reason.as_deref()).await {
+                Ok((_, case_id)) => cases.push(case_id),
+                Err(why) => {
+                    warn!("Unable to ban user {user} from guild {guild_id}: {why}");
+                    failed.push(format!("`{user}`"));
+                }
+            }
+        }
+
+        // Other cases of the guild may have been recorded in between, so the
+        // case numbers aren't necessarily consecutive.
+        let mut response = match cases.as_slice() {
+            [] => "Nobody could be banned.".to_string(),
+            [case_id] => format!("Banned 1 of {count} user(s). Recorded as case #{case_id}."),
+            cases => {
+                let numbers = cases.iter().map(|case_id| format!("#{case_id}")).collect::<Vec<_>>().join(", ");
+                format!("Banned {} of {count} user(s). Recorded as cases {numbers}.", cases.len())
+            }
+        };
+
+        if !failed.is_empty() {
+            response.push_str(&format!("\nCould not ban: {}", failed.join(", ")));
+        }
+
+        Ok(response.into())
+    }))
+}
+
+#[command("unban")]
+#[usage = "<user> [reason]"]
+#[example = "123456789012345678"]
+#[required_permissions(BAN_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Lifts the ban of the given user, which also stops any temporary ban of
+/// theirs from being lifted later.
+async fn unban(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let response = match parse_user_id(&args.single_quoted::<String>()?) {
+        Some(user) => unban_member(context, guild_id, user, message.author.id, args.remains()).await?,
+        None => "Please give the ID or mention of the user to unban.".to_string()
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Lifts the ban of the given user and records it as a case, returning a
+/// confirmation message.
+pub async fn unban_member(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, reason: Option<&str>) -> CommandResult<String> {
+    if let Some(error) = check_reason(reason) {
+        return Ok(error);
+    }
+
+    match guild_id.unban(context, user).await {
+        Ok(()) => {}
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(response))) if response.status_code.as_u16() == 404 => {
+            return Ok(format!("<@{user}> isn't banned from this server."));
+        }
+        Err(why) => return Err(why.into())
+    }
+
+    cancel_unban(context, guild_id, user).await?;
+    let case_id = record_infraction(context, guild_id, user, moderator, Action::Unban, reason, None).await?;
+
+    let name = user.to_user(context).await.map(|user| user.tag()).unwrap_or_else(|_| user.to_string());
     if let Some(reason) = reason {
-        member.ban_with_reason(context, 1, reason).await?;
-        message.reply(context, format!("Banned member `{name}#{disc}` with id `{id}` for reason `{reason}`!")).await?;
-        return Ok(());
+        return Ok(format!("Unbanned user `{name}` with id `{user}` for reason `{reason}`! Recorded as case #{case_id}."));
+    }
+
+    Ok(format!("Unbanned user `{name}` with id `{user}`. Recorded as case #{case_id}."))
+}
+
+#[command("bans")]
+#[required_permissions(BAN_MEMBERS)]
+#[only_in(guilds)]
+/// Lists everyone who is banned from this server, with the reasons of their
+/// bans.
+async fn bans(context: &Context, message: &Message) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    bans_response(context, guild_id).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Lists the bans of a guild. Bans made through the bot show their case and
+/// its reason, and others the reason given to Discord.
+pub async fn bans_response(context: &Context, guild_id: GuildId) -> CommandResult<Response> {
+    let bans = guild_id.bans(context).await?;
+    if bans.is_empty() {
+        return Ok("Nobody is banned from this server.".into());
+    }
+
+    let cases: HashMap<i64, Infraction> = get_latest_bans(context, guild_id).await?.into_iter().map(|case| (case.user_id, case)).collect();
+    let lines = bans
+        .iter()
+        .map(|ban| match cases.get(&(ban.user.id.get() as i64)) {
+            Some(case) => format!("**{}** (`{}`) case #{}: {}", ban.user.tag(), ban.user.id, case.case_id, preview_reason(case.reason.as_deref())),
+            None => format!("**{}** (`{}`): {}", ban.user.tag(), ban.user.id, preview_reason(ban.reason.as_deref()))
+        })
+        .collect();
+
+    let title = format!("Bans ({})", bans.len());
+    let paginator = Paginator::from_lines(lines, PAGE_SIZE, |list| CreateEmbed::new().title(&title).description(list)).footer("Use unban <user> to lift a ban.");
+
+    Ok(Response::Paginated(paginator))
+}
+
+#[command("bandays")]
+#[usage = "[days]"]
+#[example = "0"]
+#[example = "7"]
+#[required_permissions(MANAGE_GUILD)]
+#[only_in(guilds)]
+/// Shows or sets how many days of messages, from 0 to 7, are deleted when
+/// someone is banned.
+async fn bandays(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let response = if args.is_empty() {
+        ban_days_response(context, guild_id, None).await?
+    } else {
+        match args.single::<i64>() {
+            Ok(days) => ban_days_response(context, guild_id, Some(days)).await?,
+            Err(_) => format!("Please give a number of days from 0 to {MAX_DELETE_MESSAGE_DAYS}.")
+        }
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Shows how many days of messages are deleted on a ban in a guild, or sets
+/// it if given.
+pub async fn ban_days_response(context: &Context, guild_id: GuildId, days: Option<i64>) -> CommandResult<String> {
+    let days = match days {
+        None => {
+            let days = get_delete_message_days(context, guild_id).await?;
+            return Ok(format!("Banning someone deletes {days} day(s) of their messages."));
+        }
+        Some(days) => match u8::try_from(days) {
+            Ok(days) if days <= MAX_DELETE_MESSAGE_DAYS => days,
+            _ => return Ok(format!("Please give a number of days from 0 to {MAX_DELETE_MESSAGE_DAYS}."))
+        }
+    };
+
+    set_delete_message_days(context, guild_id, days).await?;
+
+    Ok(format!("Banning someone will now delete {days} day(s) of their messages."))
+}
+
+/// Bans the given user and records it as a case, returning the user and the
+/// case number. Any temporary ban of the user is made permanent.
+async fn ban_user(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, reason: Option<&str>) -> CommandResult<(User, i32)> {
+    let user = apply_ban(context, guild_id, user, reason).await?;
+    cancel_unban(context, guild_id, user.id).await?;
+    let case_id = record_infraction(context, guild_id, user.id, moderator, Action::Ban, reason, None).await?;
+
+    Ok((user, case_id))
+}
+
+/// Bans the given user, deleting as many days of their messages as the guild
+/// is set to. The user doesn't have to be a member of the guild.
+async fn apply_ban(context: &Context, guild_id: GuildId, user: UserId, reason: Option<&str>) -> CommandResult<User> {
+    let user = user.to_user(context).await.map_err(|_| "Could not find that user.")?;
+    let days = get_delete_message_days(context, guild_id).await?;
+    match reason {
+        Some(reason) => guild_id.ban_with_reason(context, user.id, days, reason).await?,
+        None => guild_id.ban(context, user.id, days).await?
     }
 
-    member.ban(context, 1).await?;
-    message.reply(context, format!("Banned member `{name}#{disc}` with id `{id}`.")).await?;
-    return Ok(());
+    Ok(user)
+}
+
+/// Returns whether the given user is banned from the given guild.
+async fn is_banned(context: &Context, guild_id: GuildId, user: UserId) -> CommandResult<bool> {
+    match context.http.get_ban(guild_id, user).await {
+        Ok(_) => Ok(true),
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(response))) if response.status_code.as_u16() == 404 => Ok(false),
+        Err(why) => Err(why.into())
+    }
+}
+
+/// Finds a user by their mention or ID, or by their name if they are a member
+/// of the guild.
+async fn resolve_user(context: &Context, guild_id: GuildId, name: &str) -> Option<UserId> {
+    match parse_user_id(name) {
+        Some(user) => Some(user),
+        None => parse_user(name, guild_id, context).await
+    }
 }
@@ -0,0 +1,191 @@
+use chrono::Utc;
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        channel::Message,
+        id::{GuildId, UserId}
+    }
+};
+
+use crate::{
+    commands::{paginator::Paginator, response::Response},
+    models::infraction::{Action, Infraction},
+    utils::{
+        infractions::{get_infraction, get_user_infractions, set_infraction_reason},
+        parsing::{parse_user, parse_user_id}
+    }
+};
+
+/// How many cases are listed per page.
+const PAGE_SIZE: usize = 10;
+
+/// How much of a reason is shown when listing cases.
+const PREVIEW_LENGTH: usize = 100;
+
+/// The longest reason a case can have, in characters.
+pub const REASON_LIMIT: usize = 512;
+
+#[command("case")]
+#[usage = "<case>"]
+#[example = "12"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Shows a moderation case of this server.
+async fn case(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let response = match parse_case(&args.single::<String>()?) {
+        Some(case_id) => case_response(context, guild_id, case_id).await?,
+        None => "That is not a valid case number.".into()
+    };
+
+    response.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed describing the given case.
+pub async fn case_response(context: &Context, guild_id: GuildId, case_id: i32) -> CommandResult<Response> {
+    let infraction = match get_infraction(context, guild_id, case_id).await? {
+        Some(infraction) => infraction,
+        None => return Ok(format!("There is no case #{case_id} in this server.").into())
+    };
+
+    let reason = match &infraction.reason {
+        Some(reason) => reason.clone(),
+        None => format!("No reason given. Use `reason {case_id} <reason>` to add one.")
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("Case #{} | {}", infraction.case_id, infraction.action_label()))
+        .field("User", format!("<@{0}> (`{0}`)", infraction.user_id), true)
+        .field("Moderator", format!("<@{0}> (`{0}`)", infraction.moderator_id), true)
+        .field("Reason", reason, false)
+        .field("Date", format!("<t:{}:F>", infraction.created_at), true);
+
+    if let Some(expires_at) = infraction.expires_at {
+        embed = embed.field("Expires", format!("<t:{expires_at}:F> (<t:{expires_at}:R>)"), true);
+    }
+
+    if let (Some(pardoned_at), Some(pardoned_by)) = (infraction.pardoned_at, infraction.pardoned_by) {
+        embed = embed.field("Pardoned", format!("<t:{pardoned_at}:F> by <@{pardoned_by}>"), true);
+    }
+
+    Ok(Response::embed(embed))
+}
+
+#[command("cases")]
+#[usage = "<user>"]
+#[example = "@someone"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Lists the moderation cases of a user in this server, including users who
+/// have left or been banned.
+async fn cases(context: &Context, message: &Message, args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let name = args.rest();
+    let user = match parse_user_id(name) {
+        Some(user) => Some(user),
+        None => parse_user(name, guild_id, context).await
+    };
+
+    let response = match user {
+        Some(user) => cases_response(context, guild_id, user).await?,
+        None => "Could not find that user.".into()
+    };
+
+    response.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Lists the cases against the given user, newest first.
+pub async fn cases_response(context: &Context, guild_id: GuildId, user_id: UserId) -> CommandResult<Response> {
+    let infractions = get_user_infractions(context, guild_id, user_id).await?;
+    if infractions.is_empty() {
+        return Ok(format!("<@{user_id}> has no cases in this server.").into());
+    }
+
+    let name = user_id.to_user(context).await.map(|user| user.tag()).unwrap_or_else(|_| user_id.to_string());
+    let title = format!("Cases for {name} ({})", infractions.len());
+    let lines = infractions.iter().map(case_line).collect();
+    let paginator = Paginator::from_lines(lines, PAGE_SIZE, |list| CreateEmbed::new().title(&title).description(list)).footer("Use case <number> for details on a case.");
+
+    Ok(Response::Paginated(paginator))
+}
+
+#[command("reason")]
+#[usage = "<case> <reason>"]
+#[example = "12 Spamming invite links"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(2)]
+/// Changes the reason of a moderation case.
+async fn reason(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let response = match parse_case(&args.single::<String>()?) {
+        Some(case_id) => reason_response(context, guild_id, case_id, args.rest()).await?,
+        None => "That is not a valid case number.".to_string()
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Replaces the reason of the given case.
+pub async fn reason_response(context: &Context, guild_id: GuildId, case_id: i32, reason: &str) -> CommandResult<String> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Ok("Please provide a reason.".to_string());
+    }
+
+    if let Some(error) = check_reason(Some(reason)) {
+        return Ok(error);
+    }
+
+    if !set_infraction_reason(context, guild_id, case_id, reason).await? {
+        return Ok(format!("There is no case #{case_id} in this server."));
+    }
+
+    Ok(format!("Updated the reason of case #{case_id}."))
+}
+
+/// Checks that a reason fits in a case, returning the message to show if it
+/// doesn't. Moderation commands call this before doing anything, so that an
+/// action is never carried out without its case being recorded.
+pub fn check_reason(reason: Option<&str>) -> Option<String> {
+    reason
+        .filter(|reason| reason.chars().count() > REASON_LIMIT)
+        .map(|_| format!("Reasons can't be longer than {REASON_LIMIT} characters."))
+}
+
+/// Summarizes a case in a single line.
+fn case_line(infraction: &Infraction) -> String {
+    let reason = preview_reason(infraction.reason.as_deref());
+
+    let status = match infraction.action() {
+        Some(Action::Warn) if infraction.pardoned_at.is_some() => " (pardoned)",
+        Some(Action::Warn) if !infraction.is_active(Utc::now().timestamp()) => " (expired)",
+        _ => ""
+    };
+
+    format!("**#{}** {}{status} <t:{}:d>: {reason}", infraction.case_id, infraction.action_label(), infraction.created_at)
+}
+
+/// Parses a case number, allowing it to start with `#`.
+pub fn parse_case(case: &str) -> Option<i32> {
+    case.trim_start_matches('#').parse().ok().filter(|case_id| *case_id > 0)
+}
+
+/// Shortens a reason for listings.
+pub fn preview_reason(reason: Option<&str>) -> String {
+    match reason {
+        Some(reason) if reason.chars().count() > PREVIEW_LENGTH => format!("{}…", reason.chars().take(PREVIEW_LENGTH).collect::<String>()),
+        Some(reason) => reason.to_string(),
+        None => "No reason given".to_string()
+    }
+}
@@ -1,12 +1,22 @@
-use crate::utils::parsing::parse_user;
+use crate::{
+    models::infraction::Action,
+    utils::{infractions::record_infraction, parsing::parse_user}
+};
 use serenity::{
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::channel::Message
+    model::{
+        channel::Message,
+        id::{GuildId, UserId}
+    }
 };
 
+use super::cases::check_reason;
+
 #[command("kick")]
-#[usage = "<member>"]
+#[usage = "<member> [reason]"]
+#[example = "@someone"]
+#[example = "123456789012345678 Spamming"]
 #[required_permissions(KICK_MEMBERS)]
 #[min_args(1)]
 /// Kicks the given member from the server.
@@ -18,21 +28,42 @@ async fn kick(context: &Context, message: &Message, mut args: Args) -> CommandRe
 
     let mention = args.single_quoted::<String>()?;
     let guild_id = message.guild_id.unwrap();
-    let user = parse_user(&mention, guild_id, context).await.unwrap();
-    let guild = message.guild(&context.cache).unwrap().clone();
-    let member = guild.member(context, user).await.unwrap();
+    let reason = args.remains();
+
+    let response = match parse_user(&mention, guild_id, context).await {
+        Some(user) => kick_member(context, guild_id, user, message.author.id, reason).await?,
+        None => "Could not find that member.".to_string()
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Kicks the given user from the given guild and records it as a case,
+/// returning a confirmation message.
+pub async fn kick_member(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, reason: Option<&str>) -> CommandResult<String> {
+    if let Some(error) = check_reason(reason) {
+        return Ok(error);
+    }
+
+    let member = match guild_id.member(context, user).await {
+        Ok(member) => member,
+        Err(_) => return Ok("That user isn't a member of this server.".to_string())
+    };
 
     let name = &member.user.name;
     let disc = &member.user.discriminator;
     let id = &member.user.id;
-    let reason = args.remains();
+    match reason {
+        Some(reason) => member.kick_with_reason(context, reason).await?,
+        None => member.kick(context).await?
+    }
+
+    let case_id = record_infraction(context, guild_id, user, moderator, Action::Kick, reason, None).await?;
     if let Some(reason) = reason {
-        member.kick_with_reason(context, reason).await?;
-        message.reply(context, format!("Kicked member `{name}#{disc}` with id `{id}` for reason `{reason}`!")).await?;
-        return Ok(());
+        return Ok(format!("Kicked member `{name}#{disc}` with id `{id}` for reason `{reason}`! Recorded as case #{case_id}."));
     }
 
-    member.kick(context).await?;
-    message.reply(context, format!("Kicked member `{name}#{disc}` with id `{id}`.")).await?;
-    return Ok(());
+    Ok(format!("Kicked member `{name}#{disc}` with id `{id}`. Recorded as case #{case_id}."))
 }
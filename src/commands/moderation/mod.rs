@@ -1,3 +1,6 @@
 pub mod ban;
+pub mod cases;
 pub mod kick;
 pub mod slowmode;
+pub mod timeout;
+pub mod warn;
@@ -2,13 +2,17 @@ use serenity::{
     builder::EditChannel,
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::channel::{Channel::Guild, Message}
+    model::{
+        channel::{Channel::Guild, Message},
+        id::ChannelId
+    }
 };
 
 use tracing::error;
 
 #[command("slowmode")]
 #[usage = "<num of secs>"]
+#[example = "30"]
 #[required_permissions(MANAGE_CHANNELS)]
 /// Sets the slowmode rate for a channel.
 ///
@@ -16,8 +20,20 @@ use tracing::error;
 /// moderators and server owners / administrators have to send the command in
 /// the appropriate channel they want to apply slowmode to.
 async fn slowmode(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
-    let slowmode_content = if let Ok(slowmode_rate) = arguments.single::<u64>() {
-        if let Err(why) = message.channel_id.edit(&context, EditChannel::new().rate_limit_per_user(slowmode_rate)).await {
+    let slowmode_content = apply_slowmode(context, message.channel_id, arguments.single::<u64>().ok()).await;
+
+    if let Err(why) = message.channel_id.say(&context, slowmode_content).await {
+        error!("Error sending message: {:?}", why);
+    }
+
+    Ok(())
+}
+
+/// Sets the slowmode rate of the given channel if a rate is provided, otherwise
+/// describes the channel's current slowmode rate.
+pub async fn apply_slowmode(context: &Context, channel_id: ChannelId, rate: Option<u64>) -> String {
+    if let Some(slowmode_rate) = rate {
+        if let Err(why) = channel_id.edit(&context, EditChannel::new().rate_limit_per_user(slowmode_rate)).await {
             error!("Error setting channel's slowmode rate: {:?}", why);
             format!("Failed to set slowmode to `{slowmode_rate}` seconds.")
         } else if slowmode_rate == 0 {
@@ -25,7 +41,7 @@ async fn slowmode(context: &Context, message: &Message, mut arguments: Args) ->
         } else {
             format!("Successfully set the slowmode rate to `{slowmode_rate}` seconds.")
         }
-    } else if let Some(Guild(channel)) = message.channel_id.to_channel_cached(context) {
+    } else if let Some(Guild(channel)) = channel_id.to_channel_cached(context) {
         match channel.rate_limit_per_user {
             Some(rate) => {
                 if rate == 0 {
@@ -38,11 +54,5 @@ async fn slowmode(context: &Context, message: &Message, mut arguments: Args) ->
         }
     } else {
         "Failed to find channel in cache.".to_string()
-    };
-
-    if let Err(why) = message.channel_id.say(&context, slowmode_content).await {
-        error!("Error sending message: {:?}", why);
     }
-
-    Ok(())
 }
@@ -0,0 +1,94 @@
+use chrono::Utc;
+use serenity::{
+    builder::EditMember,
+    client::Context,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        channel::Message,
+        id::{GuildId, UserId},
+        Timestamp
+    }
+};
+
+use super::cases::check_reason;
+
+use crate::{
+    models::infraction::Action,
+    utils::{
+        infractions::record_infraction,
+        parsing::parse_user,
+        time::{format_duration, parse_duration}
+    }
+};
+
+/// The longest Discord lets a member be timed out for, in seconds.
+pub const MAX_TIMEOUT: i64 = 28 * 24 * 60 * 60;
+
+#[command("timeout")]
+#[usage = "<member> <duration> [reason]"]
+#[example = "@someone 1h Spamming"]
+#[example = "@someone 1d12h"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(2)]
+/// Times out the given member, so that they can't talk or react until the
+/// timeout ends. Timeouts last at most 28 days.
+async fn timeout(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let mention = args.single_quoted::<String>()?;
+    let duration = args.single::<String>()?;
+    let reason = args.remains();
+
+    let response = match (parse_user(&mention, guild_id, context).await, parse_duration(&duration)) {
+        (None, _) => "Could not find that member.".to_string(),
+        (_, None) => "Please give a valid duration, e.g. `1h` or `1d12h`.".to_string(),
+        (Some(user), Some(duration)) => timeout_member(context, guild_id, user, message.author.id, duration.num_seconds(), reason).await?
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Times out the given member for the given number of seconds and records it
+/// as a case, returning a confirmation message.
+pub async fn timeout_member(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, duration: i64, reason: Option<&str>) -> CommandResult<String> {
+    if let Some(error) = check_reason(reason) {
+        return Ok(error);
+    }
+
+    if duration < 1 {
+        return Ok("Timeouts have to last at least a second.".to_string());
+    }
+
+    if duration > MAX_TIMEOUT {
+        return Ok(format!("Timeouts can't be longer than {}.", format_duration(MAX_TIMEOUT)));
+    }
+
+    let mut member = match guild_id.member(context, user).await {
+        Ok(member) => member,
+        Err(_) => return Ok("That user isn't a member of this server.".to_string())
+    };
+
+    let until = Utc::now().timestamp() + duration;
+    let timestamp = Timestamp::from_unix_timestamp(until).map_err(|_| "Unable to work out when the timeout ends.")?;
+    let mut builder = EditMember::new().disable_communication_until(timestamp.to_string());
+    if let Some(reason) = reason {
+        builder = builder.audit_log_reason(reason);
+    }
+
+    member.edit(context, builder).await?;
+
+    let name = &member.user.name;
+    let disc = &member.user.discriminator;
+    let id = &member.user.id;
+    let length = format_duration(duration);
+    let case_id = record_infraction(context, guild_id, user, moderator, Action::Timeout, reason, Some(until)).await?;
+    if let Some(reason) = reason {
+        return Ok(format!(
+            "Timed out member `{name}#{disc}` with id `{id}` for {length} for reason `{reason}`! Recorded as case #{case_id}."
+        ));
+    }
+
+    Ok(format!("Timed out member `{name}#{disc}` with id `{id}` for {length}. Recorded as case #{case_id}."))
+}
@@ -0,0 +1,375 @@
+use chrono::Utc;
+use serenity::{
+    builder::{CreateEmbed, CreateMessage},
+    client::Context,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        channel::Message,
+        id::{GuildId, UserId}
+    }
+};
+use tracing::warn;
+
+use super::{
+    ban::ban_member,
+    cases::{check_reason, parse_case},
+    kick::kick_member,
+    timeout::{timeout_member, MAX_TIMEOUT}
+};
+use crate::{
+    commands::{paginator::Paginator, response::Response},
+    error::report,
+    models::infraction::{Action, Infraction},
+    utils::{
+        infractions::*,
+        parsing::{parse_user, parse_user_id},
+        time::{format_duration, parse_duration}
+    }
+};
+
+/// How many warnings are listed per page.
+const PAGE_SIZE: usize = 10;
+
+/// The most escalation rules a guild can have.
+const MAX_RULES: usize = 10;
+
+#[command("warn")]
+#[usage = "<member> [reason]"]
+#[example = "@someone Spamming"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Warns the given member. Reaching a number of active warnings can time out,
+/// kick or ban them, as set up with the `escalation` command.
+async fn warn(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let mention = args.single_quoted::<String>()?;
+    let reason = args.remains();
+    let response = match parse_user(&mention, guild_id, context).await {
+        Some(user) => warn_member(context, guild_id, user, message.author.id, reason).await?,
+        None => "Could not find that member.".to_string()
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Warns the given member and records it as a case, then applies the guild's
+/// escalation rule for their number of active warnings, if it has one.
+pub async fn warn_member(context: &Context, guild_id: GuildId, user: UserId, moderator: UserId, reason: Option<&str>) -> CommandResult<String> {
+    if let Some(error) = check_reason(reason) {
+        return Ok(error);
+    }
+
+    let member = match guild_id.member(context, user).await {
+        Ok(member) => member,
+        Err(_) => return Ok("That user isn't a member of this server.".to_string())
+    };
+
+    let expires_at = get_warning_decay(context, guild_id).await?.map(|decay| Utc::now().timestamp() + decay);
+    let case_id = record_infraction(context, guild_id, user, moderator, Action::Warn, reason, expires_at).await?;
+
+    let guild_name = guild_id.name(context).unwrap_or_else(|| "a server".to_string());
+    let notice = match reason {
+        Some(reason) => format!("You were warned in **{guild_name}** for the following reason: {reason}"),
+        None => format!("You were warned in **{guild_name}**.")
+    };
+
+    if let Err(why) = member.user.direct_message(context, CreateMessage::new().content(notice)).await {
+        warn!("Unable to tell {} about their warning: {why}", member.user.id);
+    }
+
+    let count = count_active_warnings(context, guild_id, user).await?;
+    let name = &member.user.name;
+    let disc = &member.user.discriminator;
+    let mut response = format!("Warned member `{name}#{disc}` with id `{user}`, who now has {count} active warning(s). Recorded as case #{case_id}.");
+    if let Some(escalation) = escalate(context, guild_id, user, count).await? {
+        response.push('\n');
+        response.push_str(&escalation);
+    }
+
+    Ok(response)
+}
+
+/// Applies the escalation rule for the given number of active warnings, if
+/// there is one, returning what was done. Rules go through the same code as
+/// the commands for their actions, and so are recorded as cases of their own,
+/// made by the bot.
+async fn escalate(context: &Context, guild_id: GuildId, user: UserId, count: i64) -> CommandResult<Option<String>> {
+    let rule = match get_escalation_rule(context, guild_id, count).await? {
+        Some(rule) => rule,
+        None => return Ok(None)
+    };
+
+    let moderator = context.cache.current_user().id;
+    let reason = format!("Reached {count} active warnings.");
+    let result = match (rule.action(), rule.duration) {
+        (Some(Action::Timeout), Some(duration)) => timeout_member(context, guild_id, user, moderator, duration, Some(&reason)).await,
+        (Some(Action::Kick), _) => kick_member(context, guild_id, user, moderator, Some(&reason)).await,
+        (Some(Action::Ban), _) => ban_member(context, guild_id, user, moderator, Some(&reason)).await,
+        _ => {
+            warn!("Ignoring invalid escalation rule for {count} warnings in guild {guild_id}.");
+            return Ok(None);
+        }
+    };
+
+    Ok(Some(match result {
+        Ok(response) => response,
+        Err(why) => format!("Unable to apply the escalation rule for {count} warnings. {}", report("warn", why.as_ref()))
+    }))
+}
+
+#[command("warnings")]
+#[usage = "<user>"]
+#[example = "@someone"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Lists the warnings of a user in this server, including expired and
+/// pardoned ones.
+async fn warnings(context: &Context, message: &Message, args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let name = args.rest();
+    let user = match parse_user_id(name) {
+        Some(user) => Some(user),
+        None => parse_user(name, guild_id, context).await
+    };
+
+    let response = match user {
+        Some(user) => warnings_response(context, guild_id, user).await?,
+        None => "Could not find that user.".into()
+    };
+
+    response.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Lists the warnings of the given user, newest first.
+pub async fn warnings_response(context: &Context, guild_id: GuildId, user_id: UserId) -> CommandResult<Response> {
+    let warnings: Vec<Infraction> = get_user_infractions(context, guild_id, user_id)
+        .await?
+        .into_iter()
+        .filter(|infraction| infraction.action() == Some(Action::Warn))
+        .collect();
+
+    if warnings.is_empty() {
+        return Ok(format!("<@{user_id}> has no warnings in this server.").into());
+    }
+
+    let now = Utc::now().timestamp();
+    let active = warnings.iter().filter(|warning| warning.is_active(now)).count();
+    let lines = warnings.iter().map(|warning| warning_line(warning, now)).collect();
+
+    let name = user_id.to_user(context).await.map(|user| user.tag()).unwrap_or_else(|_| user_id.to_string());
+    let title = format!("Warnings for {name} ({active} active)");
+    let paginator = Paginator::from_lines(lines, PAGE_SIZE, |list| CreateEmbed::new().title(&title).description(list)).footer("Use pardon <case> to pardon a warning.");
+
+    Ok(Response::Paginated(paginator))
+}
+
+#[command("pardon")]
+#[usage = "<case>"]
+#[example = "12"]
+#[required_permissions(MODERATE_MEMBERS)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Pardons a warning, so that it no longer counts towards escalation.
+async fn pardon(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let response = match parse_case(&args.single::<String>()?) {
+        Some(case_id) => pardon_response(context, guild_id, case_id, message.author.id).await?,
+        None => "That is not a valid case number.".to_string()
+    };
+
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Pardons the warning with the given case number.
+pub async fn pardon_response(context: &Context, guild_id: GuildId, case_id: i32, moderator: UserId) -> CommandResult<String> {
+    if pardon_warning(context, guild_id, case_id, moderator).await? {
+        return Ok(format!("Pardoned the warning of case #{case_id}."));
+    }
+
+    let response = match get_infraction(context, guild_id, case_id).await? {
+        None => format!("There is no case #{case_id} in this server."),
+        Some(infraction) if infraction.action() != Some(Action::Warn) => format!("Case #{case_id} isn't a warning."),
+        Some(_) => format!("The warning of case #{case_id} has already been pardoned.")
+    };
+
+    Ok(response)
+}
+
+#[command("escalation")]
+#[required_permissions(MANAGE_GUILD)]
+#[only_in(guilds)]
+#[sub_commands(escalation_set, escalation_remove, escalation_decay)]
+/// Shows what happens automatically once members reach a number of active
+/// warnings, and how long warnings stay active.
+async fn escalation(context: &Context, message: &Message) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    escalation_response(context, guild_id).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed listing the escalation rules and warning decay of a guild.
+pub async fn escalation_response(context: &Context, guild_id: GuildId) -> CommandResult<Response> {
+    let rules = get_escalation_rules(context, guild_id).await?;
+    let decay = get_warning_decay(context, guild_id).await?;
+
+    let rules = if rules.is_empty() {
+        "No escalation rules are set. Add one with `escalation set <warnings> <timeout | kick | ban> [duration]`.".to_string()
+    } else {
+        let lines = rules.iter().map(|rule| {
+            let action = match (rule.action(), rule.duration) {
+                (Some(Action::Timeout), Some(duration)) => format!("Timeout for {}", format_duration(duration)),
+                (Some(action), _) => action.label().to_string(),
+                (None, _) => rule.action.clone()
+            };
+
+            format!("**{}** warning(s): {action}", rule.warnings)
+        });
+
+        lines.collect::<Vec<_>>().join("\n")
+    };
+
+    let decay = match decay {
+        Some(decay) => format!("Warnings stay active for {}.", format_duration(decay)),
+        None => "Warnings stay active until pardoned.".to_string()
+    };
+
+    let embed = CreateEmbed::new().title("Warning Escalation").description(rules).field("Decay", decay, false);
+
+    Ok(Response::embed(embed))
+}
+
+#[command("set")]
+#[usage = "<warnings> <timeout | kick | ban> [duration]"]
+#[example = "3 timeout 1h"]
+#[example = "5 kick"]
+#[required_permissions(MANAGE_GUILD)]
+#[only_in(guilds)]
+#[min_args(2)]
+/// Sets what happens once a member reaches the given number of active
+/// warnings. Timeouts need a duration.
+async fn escalation_set(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let warnings = args.single::<i32>().unwrap_or(0);
+    let action = args.single::<String>()?;
+    let duration = args.remains();
+
+    let response = set_escalation_response(context, guild_id, warnings, &action, duration).await?;
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Sets the escalation rule for the given number of warnings.
+pub async fn set_escalation_response(context: &Context, guild_id: GuildId, warnings: i32, action: &str, duration: Option<&str>) -> CommandResult<String> {
+    if warnings < 1 {
+        return Ok("The number of warnings has to be at least 1.".to_string());
+    }
+
+    let rules = get_escalation_rules(context, guild_id).await?;
+    if rules.len() >= MAX_RULES && !rules.iter().any(|rule| rule.warnings == warnings) {
+        return Ok(format!("A server can't have more than {MAX_RULES} escalation rules."));
+    }
+
+    let action = match Action::parse(action) {
+        Some(action @ (Action::Timeout | Action::Kick | Action::Ban)) => action,
+        _ => return Ok("Escalation rules can time out, kick or ban members.".to_string())
+    };
+
+    let duration = match (action, duration.map(parse_duration)) {
+        (Action::Timeout, Some(Some(duration))) if duration.num_seconds() <= MAX_TIMEOUT => Some(duration.num_seconds()),
+        (Action::Timeout, Some(Some(_))) => return Ok(format!("Timeouts can't be longer than {}.", format_duration(MAX_TIMEOUT))),
+        (Action::Timeout, _) => return Ok("Please give a valid duration for the timeout, e.g. `1h` or `1d12h`.".to_string()),
+        _ => None
+    };
+
+    set_escalation_rule(context, guild_id, warnings, action, duration).await?;
+
+    let action = match duration {
+        Some(duration) => format!("be timed out for {}", format_duration(duration)),
+        None if action == Action::Kick => "be kicked".to_string(),
+        None => "be banned".to_string()
+    };
+
+    Ok(format!("Members reaching {warnings} active warning(s) will now {action}."))
+}
+
+#[command("remove")]
+#[usage = "<warnings>"]
+#[example = "3"]
+#[required_permissions(MANAGE_GUILD)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Removes the escalation rule for the given number of warnings.
+async fn escalation_remove(context: &Context, message: &Message, mut args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let warnings = args.single::<i32>()?;
+    let response = remove_escalation_response(context, guild_id, warnings).await?;
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Removes the escalation rule for the given number of warnings.
+pub async fn remove_escalation_response(context: &Context, guild_id: GuildId, warnings: i32) -> CommandResult<String> {
+    if remove_escalation_rule(context, guild_id, warnings).await? {
+        Ok(format!("Removed the escalation rule for {warnings} warning(s)."))
+    } else {
+        Ok(format!("There is no escalation rule for {warnings} warning(s)."))
+    }
+}
+
+#[command("decay")]
+#[usage = "<duration | off>"]
+#[example = "30d"]
+#[example = "off"]
+#[required_permissions(MANAGE_GUILD)]
+#[only_in(guilds)]
+#[min_args(1)]
+/// Sets how long new warnings stay active, or turns expiry off.
+async fn escalation_decay(context: &Context, message: &Message, args: Args) -> CommandResult {
+    let guild_id = message.guild_id.unwrap();
+    let response = decay_response(context, guild_id, args.rest()).await?;
+    message.reply(context, response).await?;
+
+    Ok(())
+}
+
+/// Sets how long new warnings stay active in a guild. Warnings already given
+/// keep their expiry.
+pub async fn decay_response(context: &Context, guild_id: GuildId, decay: &str) -> CommandResult<String> {
+    if decay.trim().eq_ignore_ascii_case("off") {
+        set_warning_decay(context, guild_id, None).await?;
+        return Ok("New warnings will stay active until pardoned.".to_string());
+    }
+
+    let decay = match parse_duration(decay) {
+        Some(decay) => decay.num_seconds(),
+        None => return Ok("Please give a valid duration, e.g. `30d`, or `off`.".to_string())
+    };
+
+    set_warning_decay(context, guild_id, Some(decay)).await?;
+
+    Ok(format!("New warnings will stay active for {}.", format_duration(decay)))
+}
+
+/// Summarizes a warning in a single line.
+fn warning_line(warning: &Infraction, now: i64) -> String {
+    let reason = warning.reason.as_deref().unwrap_or("No reason given");
+    let status = match (warning.pardoned_at, warning.expires_at) {
+        (Some(_), _) => "pardoned".to_string(),
+        (None, Some(expires_at)) if expires_at <= now => "expired".to_string(),
+        (None, Some(expires_at)) => format!("expires <t:{expires_at}:R>"),
+        (None, None) => "active".to_string()
+    };
+
+    format!("**#{}** <t:{}:d> ({status}): {reason}", warning.case_id, warning.created_at)
+}
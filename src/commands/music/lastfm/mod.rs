@@ -5,7 +5,14 @@ pub mod scrobbles;
 use serenity::{
     client::Context,
     framework::standard::{macros::command, CommandResult},
-    model::prelude::Message
+    model::{id::UserId, prelude::Message}
+};
+
+use crate::{
+    error::TaliyahError,
+    listeners::checks::LASTFM_CHECK,
+    models::profile::ProfileField,
+    utils::profiles::{get_profile, get_profile_field, get_visible_profile_field, Viewer}
 };
 
 use self::nowplaying::*;
@@ -16,7 +23,33 @@ use self::scrobbles::*;
 #[command]
 #[aliases("lfm", "fm", "last")]
 #[sub_commands(profile, nowplaying, scrobbles)]
+#[checks(LastFm)]
 async fn lastfm(context: &Context, message: &Message) -> CommandResult {
     message.channel_id.say(context, "No valid subcommand provided. Do `help lastfm` to see the commands.").await?;
     Ok(())
 }
+
+/// Resolves the Last.fm username to look up, preferring the stored username of a
+/// mentioned user, then an explicitly provided username, and finally the stored
+/// username of the viewer. A mentioned user's username is only used if the
+/// viewer is allowed to see it.
+pub async fn resolve_username(context: &Context, viewer: &Viewer, mention: Option<UserId>, username: Option<String>) -> Result<Option<String>, TaliyahError> {
+    if let Some(mention) = mention {
+        return get_visible_profile_field(context, mention, ProfileField::LastFm, viewer).await;
+    }
+
+    match username {
+        Some(username) => Ok(Some(username)),
+        None => get_profile_field(context, viewer.user_id, ProfileField::LastFm).await
+    }
+}
+
+/// The name to show for the given Last.fm user: the invoking user's profile
+/// name if the Last.fm account is theirs, and the Last.fm username otherwise.
+pub async fn resolve_display_name(context: &Context, author: UserId, user: &str, lastfm_username: String) -> Result<String, TaliyahError> {
+    let profile = get_profile(context, author).await?.unwrap_or_default();
+    match (profile.name, profile.lastfm) {
+        (Some(name), Some(lastfm)) if lastfm == user => Ok(name),
+        _ => Ok(lastfm_username)
+    }
+}
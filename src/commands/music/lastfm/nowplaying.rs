@@ -1,93 +1,81 @@
-use crate::utils::{get_profile_field, net::*};
+use super::{resolve_display_name, resolve_username};
+use crate::{
+    commands::response::Response,
+    error::TaliyahError,
+    listeners::checks::LASTFM_CHECK,
+    utils::{net::*, profiles::Viewer}
+};
 use lastfm_rs::error::{
     Error,
     LastFMErrorResponse::{InvalidParameters, OperationFailed}
 };
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::Message
+    model::{prelude::Message, user::User}
 };
 
 use tracing::error;
 
 #[command]
+#[checks(LastFm)]
 #[description("Retrieves the Last.fm now playing state of a given user.")]
 #[aliases("np")]
 #[usage("<user>, or leave blank")]
+#[example("rj")]
 async fn nowplaying(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
-    let user = if !arguments.rest().is_empty() {
-        if !message.mentions.is_empty() {
-            get_profile_field(context, "user_lastfm_id", message.mentions.first().unwrap().id).await.unwrap()
-        } else {
-            arguments.single::<String>().unwrap()
-        }
-    } else {
-        match get_profile_field(context, "user_lastfm_id", message.author.id).await {
-            Ok(user) => user,
-            Err(_) => match arguments.single::<String>() {
-                Ok(argument) => argument,
-                Err(_) => {
-                    message.channel_id.say(context, "No username found. Please set one via `profile set` or provide one.").await?;
-                    return Ok(());
-                }
-            }
+    let mention = message.mentions.first().map(|user| user.id);
+    let username = arguments.single::<String>().ok();
+    let viewer = Viewer::new(message.author.id, message.guild_id);
+    let user = match resolve_username(context, &viewer, mention, username).await? {
+        Some(user) => user,
+        None => {
+            message.channel_id.say(context, "No username found. Please set one via `profile set` or provide one.").await?;
+            return Ok(());
         }
     };
 
-    let mut client = get_lastfm_client(context).await;
+    nowplaying_response(context, &message.author, &user).await?.send(context, message).await?;
+
+    Ok(())
+}
 
-    let recent_tracks = match client.recent_tracks(&user).await.with_limit(1).send().await {
+/// Builds an embed containing the given Last.fm user's currently playing track.
+pub async fn nowplaying_response(context: &Context, author: &User, user: &str) -> CommandResult<Response> {
+    let (mut client, _permit) = get_lastfm_client(context).await;
+
+    let recent_tracks = match client.recent_tracks(user).await.with_limit(1).send().await {
         Ok(recent) => recent.tracks,
         Err(error) => match error {
             Error::LastFMError(OperationFailed(error)) => match error.message.as_str() {
                 "Operation failed - Most likely the backend service failed. Please try again." => {
-                    message.channel_id.say(context, "Last.fm's servers are currently offline. Please try again later.").await?;
-                    return Ok(());
+                    return Ok("Last.fm's servers are currently offline. Please try again later.".into());
                 }
                 _ => {
                     error!("Last.fm operation failed: {:#?}", error);
-                    message.channel_id.say(context, "An unknown Last.fm operation error occurred. Try again later.").await?;
-                    return Ok(());
+                    return Ok("An unknown Last.fm operation error occurred. Try again later.".into());
                 }
             },
             Error::LastFMError(InvalidParameters(error)) => match error.message.as_str() {
                 "User not found" => {
-                    message.channel_id.say(context, "Invalid username provided. Please provide a valid one and try again.").await?;
-                    return Ok(());
+                    return Ok("Invalid username provided. Please provide a valid one and try again.".into());
                 }
                 _ => {
                     error!("Unknown Last.fm parameter error: {:#?}", error);
-                    message.channel_id.say(context, "An invalid Last.fm parameter was provided.").await?;
-                    return Ok(());
+                    return Ok("An invalid Last.fm parameter was provided.".into());
                 }
             },
             _ => {
                 error!("Unrecognized Last.fm error encountered: {:#?}", error);
-                message.channel_id.say(context, "An unrecognized Last.fm error was detected. Please try again later.").await?;
-                return Ok(());
+                return Ok("An unrecognized Last.fm error was detected. Please try again later.".into());
             }
         }
     };
 
-    let user_info = client.user_info(&user).await.send().await.unwrap().user;
-    let username = match get_profile_field(context, "user_name", message.author.id).await {
-        Ok(database_name) => {
-            let lastfm_name = match get_profile_field(context, "user_lastfm_id", message.author.id).await {
-                Ok(name) => name,
-                Err(_) => user_info.username.to_string()
-            };
-
-            if lastfm_name == user {
-                database_name
-            } else {
-                user_info.username.to_string()
-            }
-        }
-        Err(_) => user_info.username.as_str().to_string()
-    };
+    let user_info = client.user_info(user).await.send().await.map_err(TaliyahError::LastFm)?.user;
+    let username = resolve_display_name(context, author.id, user, user_info.username.to_string()).await?;
 
     let avatar = user_info.images[3].image_url.as_str();
     let url = user_info.url;
@@ -101,16 +89,17 @@ async fn nowplaying(context: &Context, message: &Message, mut arguments: Args) -
     let artwork = get_album_artwork(context, artist, name, &album).await;
     let header = format!("{username} is currently playing:");
 
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .author(CreateEmbedAuthor::new(header).url(url).icon_url(avatar))
         .title(name)
         .url(track_url)
         .description(format!("**{artist}** | {album}"))
-        .thumbnail(artwork)
         .color(0x00d5_1007)
         .footer(CreateEmbedFooter::new("Powered by Last.fm."));
 
-    message.channel_id.send_message(context, CreateMessage::new().embed(embed)).await?;
+    if let Some(artwork) = artwork {
+        embed = embed.thumbnail(artwork);
+    }
 
-    Ok(())
+    Ok(Response::embed(embed))
 }
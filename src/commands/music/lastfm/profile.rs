@@ -11,159 +11,207 @@ use lastfm_rs::{
         Error,
         LastFMErrorResponse::{InvalidParameters, OperationFailed}
     },
-    user::top_artists::Period
+    user::top_artists::Period,
+    Client
 };
 
+use serde::{Deserialize, Serialize};
+
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedAuthor},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::Message
+    model::{prelude::Message, user::User}
 };
 
 use tracing::error;
 
-use crate::utils::{format_int, get_profile_field, net::*};
+use super::{resolve_display_name, resolve_username};
+use crate::{
+    commands::{paginator::Paginator, response::Response},
+    error::TaliyahError,
+    listeners::checks::LASTFM_CHECK,
+    utils::{
+        cache::{get_cache, Endpoint},
+        format_int,
+        net::*,
+        profiles::Viewer
+    }
+};
+
+/// How many entries of each list are shown on the first page.
+const SUMMARY_SIZE: usize = 5;
+
+/// How many entries of a list are shown on each of the following pages.
+const PAGE_SIZE: usize = 10;
+
+/// The parts of a Last.fm profile that change slowly enough to be cached.
+#[derive(Deserialize, Serialize)]
+struct ProfileStats {
+    username: String,
+    display_name: String,
+    avatar: String,
+    country: String,
+    url: String,
+    registered: String,
+    scrobbles: String,
+    loved_tracks: String,
+    total_artists: String,
+    top_artists: Vec<String>
+}
 
 #[command]
+#[checks(LastFm)]
 #[description("Retrieves various Last.fm user stats.")]
 #[aliases("p", "prof", "pf")]
 #[usage("<user>")]
+#[example("rj")]
 async fn profile(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
-    let user = if !arguments.rest().is_empty() {
-        if !message.mentions.is_empty() {
-            get_profile_field(context, "user_lastfm_id", message.mentions.first().unwrap().id).await.unwrap()
-        } else {
-            arguments.single::<String>().unwrap()
-        }
-    } else {
-        match get_profile_field(context, "user_lastfm_id", message.author.id).await {
-            Ok(user) => user,
-            Err(_) => match arguments.single::<String>() {
-                Ok(argument) => argument,
-                Err(_) => {
-                    message.channel_id.say(context, "No username found. Please set one via `profile set` or provide one.").await?;
-                    return Ok(());
-                }
-            }
+    let mention = message.mentions.first().map(|user| user.id);
+    let username = arguments.single::<String>().ok();
+    let viewer = Viewer::new(message.author.id, message.guild_id);
+    let user = match resolve_username(context, &viewer, mention, username).await? {
+        Some(user) => user,
+        None => {
+            message.channel_id.say(context, "No username found. Please set one via `profile set` or provide one.").await?;
+            return Ok(());
         }
     };
 
-    let mut client = get_lastfm_client(context).await;
+    lastfm_profile_response(context, &message.author, &user).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed containing the given user's Last.fm profile and statistics.
+pub async fn lastfm_profile_response(context: &Context, author: &User, user: &str) -> CommandResult<Response> {
+    let (mut client, _permit) = get_lastfm_client(context).await;
 
-    let recent_tracks = match client.recent_tracks(&user).await.with_limit(5).send().await {
+    let recent_tracks = match client.recent_tracks(user).await.with_limit(50).send().await {
         Ok(recent) => recent.tracks,
         Err(error) => match error {
             Error::LastFMError(OperationFailed(error)) => match error.message.as_str() {
                 "Operation failed - Most likely the backend service failed. Please try again." => {
-                    message.channel_id.say(context, "Last.fm's servers are currently offline. Please try again later.").await?;
-                    return Ok(());
+                    return Ok("Last.fm's servers are currently offline. Please try again later.".into());
                 }
                 _ => {
                     error!("Last.fm operation failed: {:#?}", error);
-                    message.channel_id.say(context, "An unknown Last.fm operation error occurred. Try again later.").await?;
-                    return Ok(());
+                    return Ok("An unknown Last.fm operation error occurred. Try again later.".into());
                 }
             },
             Error::LastFMError(InvalidParameters(error)) => match error.message.as_str() {
                 "User not found" => {
-                    message.channel_id.say(context, "Invalid username provided. Please provide a valid one and try again.").await?;
-                    return Ok(());
+                    return Ok("Invalid username provided. Please provide a valid one and try again.".into());
                 }
                 _ => {
                     error!("Unknown Last.fm parameter error: {:#?}", error);
-                    message.channel_id.say(context, "An invalid Last.fm parameter was provided.").await?;
-                    return Ok(());
+                    return Ok("An invalid Last.fm parameter was provided.".into());
                 }
             },
             _ => {
                 error!("Unrecognized Last.fm error encountered: {:#?}", error);
-                message.channel_id.say(context, "An unrecognized Last.fm error was detected. Please try again later.").await?;
-                return Ok(());
+                return Ok("An unrecognized Last.fm error was detected. Please try again later.".into());
             }
         }
     };
 
-    let loved_tracks = client.loved_tracks(&user).await.send().await.unwrap().attrs.total;
-    let top_artists = client.top_artists(&user).await.within_period(Period::Overall).with_limit(5).send().await.unwrap();
-    let user_info = client.user_info(&user).await.send().await.unwrap().user;
-
-    let display_name = if user_info.display_name.is_empty() { "None".to_string() } else { user_info.display_name };
-    let avatar = user_info.images[3].image_url.as_str();
-    let country = user_info.country;
-    let url = user_info.url;
-    let total_artists = format_int(top_artists.attrs.total.parse::<u64>().unwrap());
-    let artists = top_artists
-        .artists
-        .iter()
-        .map(|artist| {
-            let name = &artist.name;
-            let plays = format_int(artist.scrobbles.parse::<u64>().unwrap());
-            format!("**{name}** — {plays} scrobbles")
-        })
-        .join("\n");
-
-    let username = match get_profile_field(context, "user_name", message.author.id).await {
-        Ok(database_name) => {
-            let lastfm_name = match get_profile_field(context, "user_lastfm_id", message.author.id).await {
-                Ok(name) => name,
-                Err(_) => user_info.username.to_string()
-            };
-
-            if lastfm_name == user {
-                database_name
-            } else {
-                user_info.username.to_string()
-            }
-        }
-        Err(_) => user_info.username.as_str().to_string()
-    };
-
-    let registered = user_info.registered.date.format("%B %e, %Y");
-    let scrobbles = format_int(user_info.scrobbles.parse::<u64>().unwrap());
+    let stats = get_cache(context).await.get_or_fetch(Endpoint::LastFmProfile, user, profile_stats(&mut client, user)).await?;
+    let ProfileStats {
+        username: lastfm_username,
+        display_name,
+        avatar,
+        country,
+        url,
+        registered,
+        scrobbles,
+        loved_tracks,
+        total_artists,
+        top_artists: artists
+    } = stats;
+
+    let username = resolve_display_name(context, author.id, user, lastfm_username).await?;
 
     let track = recent_tracks.first().unwrap();
     let name = &track.name;
     let artist = &track.artist.name;
     let album = if track.album.name.is_empty() { String::new() } else { track.album.name.to_owned() };
     let artwork = get_album_artwork(context, artist, name, &album).await;
-    let tracks = if recent_tracks.is_empty() {
-        "Unknown".to_owned()
-    } else {
-        recent_tracks
-            .iter()
-            .map(|track| {
-                let status = if track.attrs.is_none() { "" } else { "\x5c▶️" };
-                let name = &track.name.replace("**", "\x5c**");
-                let url = &track.url.replace("**", "\x5c**");
-                let artist = &track.artist.name;
-                format!("{status} **[{name}]({url})** — {artist}")
-            })
-            .join("\n")
-    };
+    let tracks: Vec<String> = recent_tracks
+        .iter()
+        .map(|track| {
+            let status = if track.attrs.is_none() { "" } else { "\x5c▶️" };
+            let name = &track.name.replace("**", "\x5c**");
+            let url = &track.url.replace("**", "\x5c**");
+            let artist = &track.artist.name;
+            format!("{status} **[{name}]({url})** — {artist}")
+        })
+        .collect();
 
     let play_state = if track.attrs.as_ref().is_none() { "last listened to" } else { "is currently listening to" };
     let now_playing = format!("{username} {play_state} **{name}** by **{artist}** on **{album}**.");
     let fields = vec![
         ("**Display Name**", display_name, true),
         ("**Country**", country, true),
-        ("**Join Date**", registered.to_string(), true),
+        ("**Join Date**", registered, true),
         ("**Loved Tracks**", loved_tracks, true),
         ("**Total Artists**", total_artists, true),
         ("**Total Scrobbles**", scrobbles, true),
-        ("**Top Artists:**", artists, false),
-        ("**Recently Played:**", tracks, false),
+        ("**Top Artists:**", summary(&artists), false),
+        ("**Recently Played:**", summary(&tracks), false),
     ];
 
-    let embed = CreateEmbed::new()
-        .author(CreateEmbedAuthor::new(username).url(url).icon_url(avatar))
-        .thumbnail(artwork)
-        .color(0x00d5_1007)
-        .description(now_playing)
-        .fields(fields)
-        .footer(CreateEmbedFooter::new("Powered by Last.fm."));
+    let author = CreateEmbedAuthor::new(username).url(url).icon_url(avatar);
+    let page = |title: &str, list: String| CreateEmbed::new().author(author.clone()).color(0x00d5_1007).title(title).description(list);
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
+    let mut overview = CreateEmbed::new().author(author.clone()).color(0x00d5_1007).description(now_playing).fields(fields);
+    if let Some(artwork) = artwork {
+        overview = overview.thumbnail(artwork);
+    }
 
-    Ok(())
+    let paginator = Paginator::new(vec![overview])
+        .chain(Paginator::from_lines(tracks, PAGE_SIZE, |list| page("Recently Played", list)))
+        .chain(Paginator::from_lines(artists, PAGE_SIZE, |list| page("Top Artists", list)))
+        .footer("Powered by Last.fm.");
+
+    Ok(Response::Paginated(paginator))
+}
+
+/// The first few entries of a list, as shown on the first page.
+fn summary(lines: &[String]) -> String {
+    if lines.is_empty() {
+        "Unknown".to_string()
+    } else {
+        lines.iter().take(SUMMARY_SIZE).join("\n")
+    }
+}
+
+/// Retrieves the statistics of the given user that are shown alongside their
+/// recent tracks.
+async fn profile_stats(client: &mut Client, user: &str) -> Result<ProfileStats, TaliyahError> {
+    let loved_tracks = client.loved_tracks(user).await.send().await?.attrs.total;
+    let top_artists = client.top_artists(user).await.within_period(Period::Overall).with_limit(50).send().await?;
+    let user_info = client.user_info(user).await.send().await?.user;
+
+    let artists = top_artists
+        .artists
+        .iter()
+        .map(|artist| {
+            let name = &artist.name;
+            let plays = format_int(artist.scrobbles.parse::<u64>().unwrap_or_default());
+            format!("**{name}** — {plays} scrobbles")
+        })
+        .collect();
+
+    Ok(ProfileStats {
+        username: user_info.username,
+        display_name: if user_info.display_name.is_empty() { "None".to_string() } else { user_info.display_name },
+        avatar: user_info.images[3].image_url.clone(),
+        country: user_info.country,
+        url: user_info.url,
+        registered: user_info.registered.date.format("%B %e, %Y").to_string(),
+        scrobbles: format_int(user_info.scrobbles.parse::<u64>()?),
+        loved_tracks,
+        total_artists: format_int(top_artists.attrs.total.parse::<u64>()?),
+        top_artists: artists
+    })
 }
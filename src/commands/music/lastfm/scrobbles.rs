@@ -3,40 +3,45 @@
 use serenity::{
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::Message
+    model::{prelude::Message, user::User}
 };
 
-use crate::utils::{format_int, get_profile_field, net::*};
+use super::resolve_username;
+use crate::{
+    commands::response::Response,
+    error::TaliyahError,
+    listeners::checks::LASTFM_CHECK,
+    utils::{format_int, net::*, profiles::Viewer}
+};
 
 #[command]
+#[checks(LastFm)]
 #[description("Retrieves a given Last.fm user's scrobble count.")]
 #[usage("<user>")]
+#[example("rj")]
 async fn scrobbles(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
-    let user = if !arguments.rest().is_empty() {
-        if !message.mentions.is_empty() {
-            get_profile_field(context, "user_lastfm_id", message.mentions.first().unwrap().id).await.unwrap()
-        } else {
-            arguments.single::<String>().unwrap()
-        }
-    } else {
-        match get_profile_field(context, "user_lastfm_id", message.author.id).await {
-            Ok(user) => user,
-            Err(_) => match arguments.single::<String>() {
-                Ok(argument) => argument,
-                Err(_) => {
-                    message.channel_id.say(context, "No username found. Please set one via `profile set` or provide one.").await?;
-                    return Ok(());
-                }
-            }
+    let mention = message.mentions.first().map(|user| user.id);
+    let username = arguments.single::<String>().ok();
+    let viewer = Viewer::new(message.author.id, message.guild_id);
+    let user = match resolve_username(context, &viewer, mention, username).await? {
+        Some(user) => user,
+        None => {
+            message.channel_id.say(context, "No username found. Please set one via `profile set` or provide one.").await?;
+            return Ok(());
         }
     };
 
-    let mut client = get_lastfm_client(context).await;
-    let user_info = client.user_info(&user).await.send().await.unwrap().user;
-    let name = &message.author.name;
-    let scrobbles = format_int(user_info.scrobbles.parse::<u64>().unwrap());
-
-    message.channel_id.say(context, format!("**{name}** has **{scrobbles}** scrobbles on Last.fm.")).await?;
+    scrobbles_response(context, &message.author, &user).await?.send(context, message).await?;
 
     Ok(())
 }
+
+/// Retrieves the given Last.fm user's total scrobble count.
+pub async fn scrobbles_response(context: &Context, author: &User, user: &str) -> CommandResult<Response> {
+    let (mut client, _permit) = get_lastfm_client(context).await;
+    let user_info = client.user_info(user).await.send().await.map_err(TaliyahError::LastFm)?.user;
+    let name = &author.name;
+    let scrobbles = format_int(user_info.scrobbles.parse::<u64>().unwrap());
+
+    Ok(format!("**{name}** has **{scrobbles}** scrobbles on Last.fm.").into())
+}
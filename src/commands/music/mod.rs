@@ -1,2 +1,4 @@
+#[cfg(feature = "music-lastfm")]
 pub mod lastfm;
+#[cfg(feature = "music-spotify")]
 pub mod spotify;
@@ -3,7 +3,7 @@ use humantime::format_duration;
 use itertools::Itertools;
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
@@ -11,38 +11,84 @@ use serenity::{
 
 use std::time::Duration;
 
-use crate::data::SpotifyContainer;
+use super::spotify_link;
+use crate::{
+    commands::{
+        response::Response,
+        selection::{choose, describe, take_first_flag, Candidate, CANDIDATES}
+    },
+    config::Integration,
+    error::TaliyahError,
+    listeners::checks::SPOTIFY_CHECK,
+    utils::{
+        cache::{get_cache, Endpoint},
+        http::Service,
+        net::{get_spotify, reserve}
+    }
+};
 
 #[command]
+#[checks(Spotify)]
 #[description("Displays information about a specified album on Spotify.")]
+#[usage = "<name> [--first]"]
+#[example = "In Rainbows"]
 async fn album(context: &Context, message: &Message, args: Args) -> CommandResult {
     if args.rest().is_empty() {
         message.channel_id.say(context, "No album name provided. Please provide one & try again.").await?;
         return Ok(());
     }
 
-    let data = context.data.read().await;
-    let spotify = data.get::<SpotifyContainer>().unwrap();
+    album_response(context, args.rest()).await?.send(context, message).await?;
 
-    let album_search = spotify.search().search(args.rest(), [ItemType::Album].iter().copied(), false, 1, 0, None);
-    let album_result = &album_search.await.unwrap().data;
-    let albums = album_result.albums.clone();
-    let items = albums.unwrap().items;
+    Ok(())
+}
 
-    if items.is_empty() {
-        message.channel_id.say(context, format!("No album found for `{}`. Try a different name.", args.rest())).await?;
-        return Ok(());
+/// Builds an embed containing information about the album matching the given
+/// query, asking which one was meant if there are several.
+pub async fn album_response(context: &Context, query: &str) -> CommandResult<Response> {
+    let (query, first) = take_first_flag(query);
+    let candidates: Vec<Candidate> = {
+        let spotify = get_spotify(context).await.ok_or_else(|| Integration::Spotify.unavailable_message())?;
+        let _permit = reserve(context, Service::Spotify).await;
+        let album_search = spotify.search().search(&query, [ItemType::Album].iter().copied(), false, CANDIDATES, 0, None).await?.data;
+
+        #[rustfmt::skip]
+        let candidates = album_search.albums.map(|albums| albums.items).unwrap_or_default().into_iter().filter_map(|album| {
+            let artists = album.artists.iter().map(|artist| &artist.name).join(", ");
+            let year = album.release_date.map(|date| date.format("%Y").to_string()).unwrap_or_default();
+            Some(Candidate::new(&album.name, describe("Album", &[&year, &artists]), album.id?))
+        }).collect();
+
+        candidates
+    };
+
+    if candidates.is_empty() {
+        return Ok(format!("No album found for `{query}`. Try a different name.").into());
     }
 
-    let album_id = items.first().unwrap().id.as_ref().unwrap();
-    let album = spotify.albums().get_album(album_id, None).await.unwrap().data;
+    // Spotify's search already narrows results down to the given year.
+    let first = first || query.contains("year:");
+    let prompt = format!("Several albums match `{query}`. Which one did you mean?");
+    choose(context, "spotify", prompt, candidates, first, |context, id| async move { album_by_id(&context, &id).await }).await
+}
+
+/// Builds an embed containing information about the album with the given ID.
+pub async fn album_by_id(context: &Context, id: &str) -> CommandResult<Response> {
+    let cache = get_cache(context).await;
+    let spotify = get_spotify(context).await.ok_or_else(|| Integration::Spotify.unavailable_message())?;
+    let _permit = reserve(context, Service::Spotify).await;
+
+    let fetch = async { spotify.albums().get_album(id, None).await.map(|album| album.data) };
+    let album = cache.get_or_fetch(Endpoint::SpotifyAlbum, id, fetch).await.map_err(TaliyahError::Spotify)?;
     let album_name = &album.name;
     let album_date = album.release_date.to_string();
-    let album_artists = &album.artists.iter().map(|a| format!("[{}]({})", &a.name, &a.external_urls["spotify"])).join(", ");
-    let album_image = &album.images.first().unwrap().url;
-    let album_markets = album.available_markets.unwrap().len();
+    let album_artists = album.artists.iter().map(|a| spotify_link(&a.name, &a.external_urls)).join(", ");
+    let album_image = album.images.first().map(|image| &image.url);
+    let album_markets = match &album.available_markets {
+        Some(markets) => markets.len().to_string(),
+        None => "Unknown".to_string()
+    };
     let album_track_count = album.tracks.total;
-    let album_url = &album.external_urls["spotify"];
     let album_type = match album.album_type {
         AlbumType::Album => "Album".to_owned(),
         AlbumType::Single => {
@@ -55,10 +101,9 @@ async fn album(context: &Context, message: &Message, args: Args) -> CommandResul
         AlbumType::Compilation => "Compilation".to_owned()
     };
 
-    let album_copyright = if album.copyrights.is_empty() {
-        album.label
-    } else {
-        format!("{} ({})", album.copyrights.first().unwrap().text, album.label)
+    let album_copyright = match album.copyrights.first() {
+        Some(copyright) => format!("{} ({})", copyright.text, album.label),
+        None => album.label.clone()
     };
 
     let album_track_items = &album.tracks.items;
@@ -66,33 +111,36 @@ async fn album(context: &Context, message: &Message, args: Args) -> CommandResul
     let album_length = format_duration(Duration::from_millis(album_track_lengths / 1000 * 1000));
     #[rustfmt::skip]
     let album_tracks = album_track_items.iter().map(|track| {
-        let name = &track.name;
+        let name = spotify_link(&track.name, &track.external_urls);
         let position = &track.track_number;
-        let url = &track.external_urls["spotify"];
         let length = format_duration(Duration::from_millis((track.duration.as_millis() as u64) / 1000 * 1000));
         let explicit = if track.explicit { "(explicit)" } else { "" };
-        format!("**{position}.** [{name}]({url}) — {length} {explicit}")
+        format!("**{position}.** {name} — {length} {explicit}")
     }).join("\n");
 
     let album_fields = vec![
         ("Type", album_type, true),
         ("Length", album_length.to_string(), true),
-        ("Artists", album_artists.to_string(), true),
+        ("Artists", album_artists, true),
         ("Release Date", album_date, true),
-        ("Markets", album_markets.to_string(), true),
+        ("Markets", album_markets, true),
         ("Tracks", album_track_count.to_string(), true),
     ];
 
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .title(album_name)
-        .url(album_url)
-        .thumbnail(album_image)
         .color(0x001D_B954)
         .fields(album_fields)
         .description(album_tracks)
         .footer(CreateEmbedFooter::new(album_copyright));
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?;
+    if let Some(url) = album.external_urls.get("spotify") {
+        embed = embed.url(url);
+    }
 
-    Ok(())
+    if let Some(image) = album_image {
+        embed = embed.thumbnail(image);
+    }
+
+    Ok(Response::embed(embed))
 }
@@ -9,6 +9,10 @@ use serenity::{
     model::prelude::Message
 };
 
+use std::collections::HashMap;
+
+use crate::listeners::checks::SPOTIFY_CHECK;
+
 use self::album::*;
 use self::newreleases::*;
 use self::status::*;
@@ -21,7 +25,17 @@ use self::track::*;
 #[command]
 #[aliases("sp", "spot")]
 #[sub_commands(album, newreleases, status, track)]
+#[checks(Spotify)]
 async fn spotify(context: &Context, message: &Message) -> CommandResult {
     message.channel_id.say(context, "No valid subcommand provided. Do `help spotify` to see the commands.").await?;
     Ok(())
 }
+
+/// Links the given name to its page on Spotify, or leaves it as is if Spotify
+/// didn't return one.
+fn spotify_link(name: &str, external_urls: &HashMap<String, String>) -> String {
+    match external_urls.get("spotify") {
+        Some(url) => format!("[{name}]({url})"),
+        None => name.to_string()
+    }
+}
@@ -2,48 +2,65 @@ use aspotify::CountryCode;
 use itertools::Itertools;
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::CreateEmbed,
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
 };
 
-use crate::{data::SpotifyContainer, utils::locale};
+use crate::{
+    commands::{paginator::Paginator, response::Response},
+    config::Integration,
+    listeners::checks::SPOTIFY_CHECK,
+    utils::{
+        http::Service,
+        locale,
+        net::{get_spotify, reserve}
+    }
+};
 
 #[command]
+#[checks(Spotify)]
 #[description("Displays information about the new releases for a given market.")]
+#[usage = "[market]"]
+#[example = "US"]
 async fn newreleases(context: &Context, message: &Message, args: Args) -> CommandResult {
-    let market = args.rest().to_string();
+    newreleases_response(context, args.rest()).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed listing the new releases on Spotify for the given market.
+pub async fn newreleases_response(context: &Context, market: &str) -> CommandResult<Response> {
     if !market.is_empty() {
         if market.len() < 2 || market.len() > 2 {
-            message.channel_id.say(context, "The market name you provided is more or less than 2 characters long.").await?;
-            return Ok(());
+            return Ok("The market name you provided is more or less than 2 characters long.".into());
         }
     } else if market.is_empty() {
-        message.channel_id.say(context, "You did not provide a valid market name.").await?;
-        return Ok(());
+        return Ok("You did not provide a valid market name.".into());
+    }
+
+    let spotify = get_spotify(context).await.ok_or_else(|| Integration::Spotify.unavailable_message())?;
+    let _permit = reserve(context, Service::Spotify).await;
+    let country_iso = CountryCode::for_alpha2_caseless(market).ok_or("Unrecognized market code.")?;
+    let country_name = locale::get_country_name_from_iso(market);
+    let new_releases = spotify.browse().get_new_releases(50, 0, Some(country_iso)).await?;
+    if new_releases.data.items.is_empty() {
+        return Ok(format!("There are no new releases on Spotify for {country_name}.").into());
     }
 
-    let data = context.data.read().await;
-    let spotify = data.get::<SpotifyContainer>().unwrap();
-    let country_iso = CountryCode::for_alpha2_caseless(&market).unwrap();
-    let country_name = locale::get_country_name_from_iso(&market);
-    let new_releases = spotify.browse().get_new_releases(20, 0, Some(country_iso)).await?;
     #[rustfmt::skip]
     let nr_items = new_releases.data.items.iter().map(|album| {
         let album_name = &album.name;
         let album_artists = &album.artists.iter().map(|a| &a.name).join(", ");
-        let album_date = album.release_date.unwrap().format("%B %-d, %Y").to_string();
-        format!("**{album_name}** — {album_artists} — {album_date}")
-    }).join("\n");
-
-    let embed = CreateEmbed::new()
-        .title(format!("New Releases on Spotify for {country_name}"))
-        .colour(0x001D_B954)
-        .description(nr_items)
-        .footer(CreateEmbedFooter::new("Powered by the Spotify Web API."));
+        match album.release_date {
+            Some(date) => format!("**{album_name}** — {album_artists} — {}", date.format("%B %-d, %Y")),
+            None => format!("**{album_name}** — {album_artists}")
+        }
+    }).collect();
 
-    message.channel_id.send_message(context, CreateMessage::new().embed(embed)).await?;
+    let title = format!("New Releases on Spotify for {country_name}");
+    let paginator = Paginator::from_lines(nr_items, 10, |list| CreateEmbed::new().title(&title).colour(0x001D_B954).description(list)).footer("Powered by the Spotify Web API.");
 
-    return Ok(());
+    Ok(Response::Paginated(paginator))
 }
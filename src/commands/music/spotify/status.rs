@@ -1,22 +1,30 @@
-use crate::data::ConfigContainer;
+use crate::commands::response::Response;
+use crate::listeners::checks::SPOTIFY_CHECK;
+use crate::utils::config::get_config;
 use crate::utils::parsing::parse_user;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
-    model::{gateway::Activity, prelude::Message}
+    model::{
+        gateway::Activity,
+        guild::Member,
+        id::GuildId,
+        prelude::{Message, User}
+    }
 };
 
 #[command]
+#[checks(Spotify)]
 #[description = "Shows yours or another user's Spotify status."]
 #[aliases("np", "nowplaying")]
+#[usage = "[user]"]
+#[example = "@Taliyah"]
 async fn status(context: &Context, message: &Message, arguments: Args) -> CommandResult {
-    let cache = &context.cache;
     let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
-    let cached_guild = cache.guild(guild_id).ok_or("Unable to retrieve guild")?.clone();
     let member = if message.mentions.is_empty() {
         if arguments.is_empty() {
             message.member(&context).await.map_err(|_| "Could not find member.")?
@@ -30,26 +38,31 @@ async fn status(context: &Context, message: &Message, arguments: Args) -> Comman
         guild_id.member(&context, message.mentions.first().ok_or("Failed to get user mentioned.")?).await?
     };
 
+    status_response(context, guild_id, member, &message.author).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed containing the given member's current Spotify status.
+pub async fn status_response(context: &Context, guild_id: GuildId, member: Member, author: &User) -> CommandResult<Response> {
+    let guild = context.cache.guild(guild_id).ok_or("Unable to retrieve guild")?.clone();
     let user = member.user;
-    let guild = cached_guild;
 
-    let data = context.data.read().await;
-    let config = data.get::<ConfigContainer>().unwrap();
+    let config = get_config(context).await;
     let denied_ids = &config.bot.denylist.spotify.ids;
     if denied_ids.contains(&user.id.get()) {
-        message.reply(context, "This user's status cannot be viewed; they are in the deny list.").await?;
-        return Ok(());
+        return Ok("This user's status cannot be viewed; they are in the deny list.".into());
     }
 
     let name = &user.name;
 
-    if guild.presences.get(&user.id).is_some() {
+    let response: Response = if guild.presences.get(&user.id).is_some() {
         let presence = guild.presences.get(&user.id).unwrap();
         if presence.activities.first().is_none() {
-            if user == message.author {
-                message.reply(&context, "You do not currently have an active activity.").await?
+            if user == *author {
+                "You do not currently have an active activity.".into()
             } else {
-                message.reply(&context, format!("**{name}** does not have an active activity.")).await?
+                format!("**{name}** does not have an active activity.").into()
             }
         } else {
             let activities = presence.activities.iter().filter(|a| a.name == "Spotify").collect::<Vec<&Activity>>();
@@ -104,18 +117,18 @@ async fn status(context: &Context, message: &Message, arguments: Args) -> Comman
                     .thumbnail(artwork_url)
                     .footer(CreateEmbedFooter::new(format!("Length: {length}")));
 
-                message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await?
-            } else if user == message.author {
-                message.reply(&context, "You are not currently listening to anything on Spotify.").await?
+                Response::embed(embed)
+            } else if user == *author {
+                "You are not currently listening to anything on Spotify.".into()
             } else {
-                message.reply(&context, format!("**{name}** is not currently playing anything on Spotify.")).await?
+                format!("**{name}** is not currently playing anything on Spotify.").into()
             }
         }
-    } else if user == message.author {
-        message.reply(&context, "You are currently shown as offline or you don't have a visible presence.").await?
+    } else if user == *author {
+        "You are currently shown as offline or you don't have a visible presence.".into()
     } else {
-        message.reply(&context, format!("**{name}** is currently offline / doesn't have a presence.")).await?
+        format!("**{name}** is currently offline / doesn't have a presence.").into()
     };
 
-    Ok(())
+    Ok(response)
 }
@@ -3,7 +3,7 @@ use humantime::format_duration;
 use itertools::Itertools;
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
@@ -11,54 +11,97 @@ use serenity::{
 
 use std::time::Duration;
 
-use crate::data::SpotifyContainer;
+use super::spotify_link;
+use crate::{
+    commands::{
+        response::Response,
+        selection::{choose, describe, take_first_flag, Candidate, CANDIDATES}
+    },
+    config::Integration,
+    listeners::checks::SPOTIFY_CHECK,
+    utils::{
+        http::Service,
+        net::{get_spotify, reserve}
+    }
+};
 
 #[command]
+#[checks(Spotify)]
 #[aliases("song")]
 #[description("Displays information about a specified track on Spotify.")]
+#[usage = "<name> [--first]"]
+#[example = "Bohemian Rhapsody"]
 async fn track(context: &Context, message: &Message, args: Args) -> CommandResult {
     if args.rest().is_empty() {
         message.channel_id.say(context, "No track name provided. Please provide one & try again.").await?;
         return Ok(());
     }
 
-    let data = context.data.read().await;
-    let spotify = data.get::<SpotifyContainer>().unwrap();
+    track_response(context, args.rest()).await?.send(context, message).await?;
 
-    let track_search = spotify.search().search(args.rest(), [ItemType::Track].iter().copied(), false, 1, 0, None);
-    let track_result = &track_search.await.unwrap().data;
-    let tracks = &track_result.tracks;
-    let items = &tracks.as_ref().unwrap().items;
-    if items.is_empty() {
-        message.channel_id.say(context, format!("No track was found for `{}`. Try something else.", args.rest())).await?;
-        return Ok(());
+    Ok(())
+}
+
+/// Builds an embed containing information about the track matching the given
+/// query, asking which one was meant if there are several.
+pub async fn track_response(context: &Context, query: &str) -> CommandResult<Response> {
+    let (query, first) = take_first_flag(query);
+    let candidates: Vec<Candidate> = {
+        let spotify = get_spotify(context).await.ok_or_else(|| Integration::Spotify.unavailable_message())?;
+        let _permit = reserve(context, Service::Spotify).await;
+        let track_search = spotify.search().search(&query, [ItemType::Track].iter().copied(), false, CANDIDATES, 0, None).await?.data;
+
+        #[rustfmt::skip]
+        let candidates = track_search.tracks.map(|tracks| tracks.items).unwrap_or_default().into_iter().filter_map(|track| {
+            let artists = track.artists.iter().map(|artist| &artist.name).join(", ");
+            let year = track.album.release_date.map(|date| date.format("%Y").to_string()).unwrap_or_default();
+            Some(Candidate::new(&track.name, describe("Track", &[&year, &artists]), track.id?))
+        }).collect();
+
+        candidates
+    };
+
+    if candidates.is_empty() {
+        return Ok(format!("No track was found for `{query}`. Try something else.").into());
     }
 
-    let track = items.first().unwrap();
-    let track_id = track.id.as_ref().unwrap();
-    let track_album = &track.album;
-    let track_album_id = track_album.id.as_ref().unwrap();
+    // Spotify's search already narrows results down to the given year.
+    let first = first || query.contains("year:");
+    let prompt = format!("Several tracks match `{query}`. Which one did you mean?");
+    choose(context, "spotify", prompt, candidates, first, |context, id| async move { track_by_id(&context, &id).await }).await
+}
+
+/// Builds an embed containing information about the track with the given ID.
+pub async fn track_by_id(context: &Context, track_id: &str) -> CommandResult<Response> {
+    let spotify = get_spotify(context).await.ok_or_else(|| Integration::Spotify.unavailable_message())?;
+    let _permit = reserve(context, Service::Spotify).await;
+
+    let track = spotify.tracks().get_track(track_id, None).await?.data;
+    let track_album = match &track.album.id {
+        Some(album_id) => Some(spotify.albums().get_album(album_id, None).await?.data),
+        None => None
+    };
+
     let track_name = &track.name;
-    let track_album = spotify.albums().get_album(track_album_id, None).await.unwrap().data;
-    let track_album_name = track_album.name;
-    let track_album_url = track_album.external_urls.get("spotify").unwrap();
-    let track_markets = track_album.available_markets.unwrap().len();
-    let track_label = track_album.label;
+    let track_album_name = spotify_link(&track.album.name, &track.album.external_urls);
+    let track_markets = match track_album.as_ref().and_then(|album| album.available_markets.as_ref()) {
+        Some(markets) => markets.len().to_string(),
+        None => "Unknown".to_string()
+    };
     let track_length = format_duration(Duration::from_millis((track.duration.as_millis() as u64) / 1000 * 1000));
-    let track_url = track.external_urls.get("spotify").unwrap();
     let track_explicit = if track.explicit { "Yes" } else { "No" };
-    let track_image = &track_album.images.first().unwrap().url;
-    let track_artists = track.artists.iter().map(|a| format!("[{}]({})", &a.name, &a.external_urls["spotify"])).join(", ");
-    let track_date = track_album.release_date.to_string();
-    let track_copyright = match &track_album.copyrights.is_empty() {
-        true => track_label,
-        false => {
-            let copyright = &track_album.copyrights.first().unwrap().text;
-            format!("{copyright} ({track_label})")
-        }
+    let track_image = track.album.images.first().map(|image| image.url.clone());
+    let track_artists = track.artists.iter().map(|a| spotify_link(&a.name, &a.external_urls)).join(", ");
+    let track_date = match track.album.release_date {
+        Some(date) => date.to_string(),
+        None => "Unknown".to_string()
     };
+    let track_copyright = track_album.map(|album| match album.copyrights.first() {
+        Some(copyright) => format!("{} ({})", copyright.text, album.label),
+        None => album.label
+    });
 
-    let track_features = spotify.tracks().get_features_track(track_id).await.unwrap().data;
+    let track_features = spotify.tracks().get_features_track(track_id).await?.data;
     let track_key = match track_features.key {
         0 => "C".to_owned(),
         1 => "C♯, D♭".to_owned(),
@@ -82,26 +125,30 @@ async fn track(context: &Context, message: &Message, args: Args) -> CommandResul
         Mode::Minor => "Major".to_owned()
     };
 
-    let embed = CreateEmbed::new()
-        .title(track_name)
-        .thumbnail(track_image)
-        .url(track_url)
-        .color(0x001D_B954)
-        .fields(vec![
-            ("Artists", track_artists, true),
-            ("Album", format!("[{track_album_name}]({track_album_url})"), true),
-            ("Release Date", track_date, true),
-            ("Explicit", track_explicit.to_string(), true),
-            ("Markets", track_markets.to_string(), true),
-            ("Duration", track_length.to_string(), true),
-            ("Loudness", format!("{track_loudness} dB"), true),
-            ("Keys", track_key, true),
-            ("Mode", track_mode, true),
-            ("Tempo", track_tempo.to_string(), true),
-        ])
-        .footer(CreateEmbedFooter::new(track_copyright));
-
-    message.channel_id.send_message(&context, CreateMessage::new().add_embed(embed)).await?;
+    let mut embed = CreateEmbed::new().title(track_name).color(0x001D_B954).fields(vec![
+        ("Artists", track_artists, true),
+        ("Album", track_album_name, true),
+        ("Release Date", track_date, true),
+        ("Explicit", track_explicit.to_string(), true),
+        ("Markets", track_markets, true),
+        ("Duration", track_length.to_string(), true),
+        ("Loudness", format!("{track_loudness} dB"), true),
+        ("Keys", track_key, true),
+        ("Mode", track_mode, true),
+        ("Tempo", track_tempo.to_string(), true),
+    ]);
 
-    Ok(())
+    if let Some(url) = track.external_urls.get("spotify") {
+        embed = embed.url(url);
+    }
+
+    if let Some(image) = track_image {
+        embed = embed.thumbnail(image);
+    }
+
+    if let Some(copyright) = track_copyright {
+        embed = embed.footer(CreateEmbedFooter::new(copyright));
+    }
+
+    Ok(Response::embed(embed))
 }
@@ -0,0 +1,174 @@
+//! Paginated embeds
+//!
+//! Responses too long for a single embed are split into pages, which the
+//! person who used the command can turn through with buttons below the
+//! message. The buttons are removed once they haven't been used for a while,
+//! or when the stop button is pressed.
+
+use serenity::{
+    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage},
+    client::Context,
+    model::{application::ButtonStyle, channel::Message, id::UserId}
+};
+use std::time::Duration;
+use tracing::warn;
+
+use super::components::ComponentListener;
+
+/// How long the buttons stay usable after they were last pressed.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The most characters Discord allows in an embed's description.
+pub const DESCRIPTION_LIMIT: usize = 4096;
+
+/// The most fields Discord allows in a single embed.
+pub const FIELD_LIMIT: usize = 25;
+
+/// The most characters Discord allows across all of an embed's text. Some of
+/// it is left for the title and footer.
+pub const FIELD_TEXT_LIMIT: usize = 5000;
+
+const FIRST: &str = "paginator_first";
+const PREVIOUS: &str = "paginator_previous";
+const NEXT: &str = "paginator_next";
+const LAST: &str = "paginator_last";
+const STOP: &str = "paginator_stop";
+
+/// An embed field, as accepted by [`CreateEmbed::fields`].
+pub type Field = (String, String, bool);
+
+pub struct Paginator {
+    pages: Vec<CreateEmbed>,
+    footer: Option<String>
+}
+
+impl Paginator {
+    /// Creates a paginator showing the given embeds in order.
+    pub fn new(pages: Vec<CreateEmbed>) -> Paginator {
+        Paginator { pages, footer: None }
+    }
+
+    /// Splits the given lines into pages of at most `per_page` lines each, with
+    /// every page's description kept within Discord's limit, and builds each
+    /// page's embed from its description. There are no pages without lines.
+    pub fn from_lines(lines: Vec<String>, per_page: usize, page: impl Fn(String) -> CreateEmbed) -> Paginator {
+        let chunks = chunk(lines, per_page, DESCRIPTION_LIMIT, |line| line.chars().count() + 1);
+        Paginator::new(chunks.into_iter().map(|lines| page(lines.join("\n"))).collect())
+    }
+
+    /// Splits the given fields into pages of at most `per_page` fields each,
+    /// keeping every page within Discord's limits, and builds each page's embed
+    /// from its fields. There are no pages without fields.
+    pub fn from_fields(fields: Vec<Field>, per_page: usize, page: impl Fn(Vec<Field>) -> CreateEmbed) -> Paginator {
+        let chunks = chunk(fields, per_page.min(FIELD_LIMIT), FIELD_TEXT_LIMIT, |(name, value, _)| {
+            name.chars().count() + value.chars().count()
+        });
+        Paginator::new(chunks.into_iter().map(page).collect())
+    }
+
+    /// Appends the pages of another paginator after this one's.
+    pub fn chain(mut self, other: Paginator) -> Paginator {
+        self.pages.extend(other.pages);
+        self
+    }
+
+    /// Sets the text shown after the page number in every page's footer,
+    /// replacing any footer the pages were built with.
+    pub fn footer(mut self, footer: impl Into<String>) -> Paginator {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// Whether there is more than one page, and so buttons are needed.
+    pub fn is_paginated(&self) -> bool {
+        self.pages.len() > 1
+    }
+
+    /// The embed for the given page, with the page number in its footer.
+    pub fn page(&self, index: usize) -> CreateEmbed {
+        let embed = self.pages.get(index).cloned().unwrap_or_default();
+        let footer = match (&self.footer, self.is_paginated()) {
+            (Some(footer), true) => format!("Page {} of {} | {footer}", index + 1, self.pages.len()),
+            (Some(footer), false) => footer.clone(),
+            (None, true) => format!("Page {} of {}", index + 1, self.pages.len()),
+            (None, false) => return embed
+        };
+
+        embed.footer(CreateEmbedFooter::new(footer))
+    }
+
+    /// The navigation buttons for the given page, or none if there is only one
+    /// page.
+    pub fn components(&self, index: usize) -> Vec<CreateActionRow> {
+        if !self.is_paginated() {
+            return Vec::new();
+        }
+
+        let last = self.pages.len() - 1;
+        let button = |id: &str, label: &str, disabled: bool| CreateButton::new(id).label(label).style(ButtonStyle::Secondary).disabled(disabled);
+
+        vec![CreateActionRow::Buttons(vec![
+            button(FIRST, "⏮", index == 0),
+            button(PREVIOUS, "◀", index == 0),
+            button(NEXT, "▶", index == last),
+            button(LAST, "⏭", index == last),
+            CreateButton::new(STOP).label("⏹").style(ButtonStyle::Danger),
+        ])]
+    }
+
+    /// Turns pages as the given user presses the buttons on the given message,
+    /// until they stop or the buttons go unused for too long. Presses by
+    /// anyone else are turned away.
+    pub async fn listen(self, context: Context, message: Message, author: UserId) {
+        let last = self.pages.len() - 1;
+        let mut index = 0;
+        let mut listener = ComponentListener::new(message, author, TIMEOUT, "Only the person who used this command can turn its pages.");
+
+        while let Some(interaction) = listener.next(&context).await {
+            index = match interaction.data.custom_id.as_str() {
+                FIRST => 0,
+                PREVIOUS => index.saturating_sub(1),
+                NEXT => (index + 1).min(last),
+                LAST => last,
+                _ => {
+                    let update = CreateInteractionResponseMessage::new().components(Vec::new());
+                    if let Err(why) = interaction.create_response(&context.http, CreateInteractionResponse::UpdateMessage(update)).await {
+                        warn!("Unable to remove the page buttons: {why}");
+                    }
+                    return;
+                }
+            };
+
+            let update = CreateInteractionResponseMessage::new().embed(self.page(index)).components(self.components(index));
+            if let Err(why) = interaction.create_response(&context.http, CreateInteractionResponse::UpdateMessage(update)).await {
+                warn!("Unable to turn to page {}: {why}", index + 1);
+            }
+        }
+    }
+}
+
+/// Splits items into chunks of at most `per_page` items whose total size is
+/// within `budget`. An item larger than the budget gets a chunk of its own.
+fn chunk<T>(items: Vec<T>, per_page: usize, budget: usize, size: impl Fn(&T) -> usize) -> Vec<Vec<T>> {
+    let per_page = per_page.max(1);
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut used = 0;
+
+    for item in items {
+        let item_size = size(&item);
+        if !current.is_empty() && (current.len() == per_page || used + item_size > budget) {
+            chunks.push(std::mem::take(&mut current));
+            used = 0;
+        }
+
+        used += item_size;
+        current.push(item);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
@@ -0,0 +1,155 @@
+//! Command responses
+//!
+//! A transport-agnostic representation of what a command replies with, so
+//! the same command logic can answer both prefix commands and application
+//! (slash) commands.
+
+use serenity::{
+    builder::{CreateActionRow, CreateEmbed, CreateMessage, EditInteractionResponse, EditMessage},
+    client::Context,
+    model::{application::CommandInteraction, channel::Message, id::UserId}
+};
+
+use super::{confirmation::Confirmation, paginator::Paginator, selection::Selection};
+
+pub enum Response {
+    Text(String),
+    Embed(CreateEmbed, Vec<CreateActionRow>),
+    Paginated(Paginator),
+    /// A prompt asking which of several search results was meant.
+    Selection(Selection),
+    /// A prompt asking to confirm something that can't be undone.
+    Confirmation(Confirmation)
+}
+
+/// The contents of a response's message, along with whatever keeps listening
+/// to the message once it is sent.
+struct Parts {
+    content: Option<String>,
+    embeds: Vec<CreateEmbed>,
+    components: Vec<CreateActionRow>,
+    listener: Option<Listener>
+}
+
+enum Listener {
+    Paginator(Paginator),
+    Selection(Selection),
+    Confirmation(Confirmation)
+}
+
+impl Response {
+    /// Creates a response consisting of a single embed without any components.
+    pub fn embed(embed: CreateEmbed) -> Response {
+        Response::Embed(embed, Vec::new())
+    }
+
+    /// Sends the response to the channel the given message was sent in.
+    ///
+    /// Paginated responses and prompts keep listening to the message's author
+    /// in the background.
+    pub async fn send(self, context: &Context, message: &Message) -> serenity::Result<Message> {
+        let parts = self.into_parts();
+        let mut builder = CreateMessage::new().embeds(parts.embeds).components(parts.components);
+        if let Some(content) = parts.content {
+            builder = builder.content(content);
+        }
+
+        let sent = message.channel_id.send_message(context, builder).await?;
+        Listener::spawn(parts.listener, context, &sent, message.author.id);
+
+        Ok(sent)
+    }
+
+    /// Edits the deferred response of the given application command.
+    ///
+    /// Paginated responses and prompts keep listening to the user of the
+    /// command in the background.
+    pub async fn respond(self, context: &Context, command: &CommandInteraction) -> serenity::Result<Message> {
+        let parts = self.into_parts();
+        let builder = EditInteractionResponse::new()
+            .content(parts.content.unwrap_or_default())
+            .embeds(parts.embeds)
+            .components(parts.components);
+
+        let sent = command.edit_response(&context.http, builder).await?;
+        Listener::spawn(parts.listener, context, &sent, command.user.id);
+
+        Ok(sent)
+    }
+
+    /// Replaces the contents of a message that was already sent, such as a
+    /// prompt that has been answered.
+    pub async fn edit(self, context: &Context, message: &mut Message, author: UserId) -> serenity::Result<()> {
+        let parts = self.into_parts();
+        let builder = EditMessage::new().content(parts.content.unwrap_or_default()).embeds(parts.embeds).components(parts.components);
+
+        message.edit(context, builder).await?;
+        Listener::spawn(parts.listener, context, message, author);
+
+        Ok(())
+    }
+
+    fn into_parts(self) -> Parts {
+        match self {
+            Response::Text(content) => Parts {
+                content: Some(content),
+                embeds: Vec::new(),
+                components: Vec::new(),
+                listener: None
+            },
+            Response::Embed(embed, components) => Parts {
+                content: None,
+                embeds: vec![embed],
+                components,
+                listener: None
+            },
+            Response::Paginated(paginator) => Parts {
+                content: None,
+                embeds: vec![paginator.page(0)],
+                components: paginator.components(0),
+                listener: paginator.is_paginated().then_some(Listener::Paginator(paginator))
+            },
+            Response::Selection(selection) => Parts {
+                content: Some(selection.prompt().to_string()),
+                embeds: Vec::new(),
+                components: selection.components(),
+                listener: Some(Listener::Selection(selection))
+            },
+            Response::Confirmation(confirmation) => Parts {
+                content: Some(confirmation.prompt().to_string()),
+                embeds: Vec::new(),
+                components: confirmation.components(),
+                listener: Some(Listener::Confirmation(confirmation))
+            }
+        }
+    }
+}
+
+impl Listener {
+    fn spawn(listener: Option<Listener>, context: &Context, message: &Message, author: UserId) {
+        match listener {
+            Some(Listener::Paginator(paginator)) => {
+                tokio::spawn(paginator.listen(context.clone(), message.clone(), author));
+            }
+            Some(Listener::Selection(selection)) => {
+                tokio::spawn(selection.listen(context.clone(), message.clone(), author));
+            }
+            Some(Listener::Confirmation(confirmation)) => {
+                tokio::spawn(confirmation.listen(context.clone(), message.clone(), author));
+            }
+            None => {}
+        }
+    }
+}
+
+impl From<String> for Response {
+    fn from(content: String) -> Response {
+        Response::Text(content)
+    }
+}
+
+impl From<&str> for Response {
+    fn from(content: &str) -> Response {
+        Response::Text(content.to_string())
+    }
+}
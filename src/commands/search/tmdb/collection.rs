@@ -1,78 +1,74 @@
-use chrono::prelude::NaiveDate;
-use serde::Deserialize;
-
 use serenity::{
-    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
 };
 
-use crate::{data::ReqwestContainer, utils::read_config};
-
-#[derive(Deserialize, Debug)]
-pub struct SearchResponse {
-    pub results: Vec<SearchCollection>
-}
-
-#[derive(Deserialize, Debug)]
-pub struct SearchCollection {
-    pub id: u64
-}
-
-#[derive(Deserialize, Debug)]
-pub struct Collection {
-    pub id: u64,                     // The TMDb ID belonging to the collection.
-    pub name: String,                // The name of the collection.
-    pub overview: String,            // The overview of the collection.
-    pub poster_path: String,         // The poster belonging to the collection.
-    pub backdrop_path: String,       // The backdrop path of the collection.
-    pub parts: Vec<SimplifiedMovie>  // The movies part of the collection.
-}
-
-#[derive(Deserialize, Debug)]
-pub struct SimplifiedMovie {
-    pub adult: bool,             // Whether or not the movie is marked as an adult film by TMDb.
-    pub id: u64,                 // The TMDb ID belonging to the movie.
-    pub overview: String,        // The overview of the movie.
-    pub release_date: NaiveDate, // The release date of the movie.
-    pub title: String            // The title of the movie.
-}
-
-#[derive(Deserialize, Debug)]
-pub struct Movie {
-    pub status: String
-}
+use crate::{
+    commands::{
+        response::Response,
+        selection::{choose, describe, take_first_flag, Candidate}
+    },
+    listeners::checks::TMDB_CHECK,
+    models::tmdb::collection::Collection,
+    services::get_services
+};
 
 #[command]
+#[checks(Tmdb)]
 #[aliases("collection")]
 #[description("Gets detailed information about a collection from The Movie Database.")]
+#[usage = "<name> [--first]"]
+#[example = "The Lord of the Rings"]
 async fn collection(context: &Context, message: &Message, arguments: Args) -> CommandResult {
     if arguments.rest().is_empty() {
         message.channel_id.say(context, "No collection name provided. Please provide one.").await?;
         return Ok(());
     }
 
-    let collection: String = arguments.rest().to_string();
+    collection_response(context, arguments.rest()).await?.send(context, message).await?;
+
+    Ok(())
+}
 
-    let config = read_config("config.toml");
-    let api_key = config.api.entertainment.tmdb;
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
+/// Builds an embed containing information about the collection matching the
+/// given query, asking which one was meant if there are several.
+pub async fn collection_response(context: &Context, query: &str) -> CommandResult<Response> {
+    let (query, first) = take_first_flag(query);
+    let collections = get_services(context).await.movies;
 
-    let search_endpoint = "https://api.themoviedb.org/3/search/collection";
-    let search_response = client.get(search_endpoint).query(&[("api_key", &api_key), ("query", &collection)]);
-    let search_result: SearchResponse = search_response.send().await?.json().await?;
-    let search_results = search_result.results;
+    let search_results = collections.search_collections(&query).await?;
     if search_results.is_empty() {
-        message.channel_id.say(context, format!("Nothing found for `{collection}`. Please try another name.")).await?;
-        return Ok(());
+        return Ok(format!("Nothing found for `{query}`. Please try another name.").into());
     }
 
-    let id = search_results.first().unwrap().id;
-    let endpoint = format!("https://api.themoviedb.org/3/collection/{id}");
-    let response = client.get(&endpoint).query(&[("api_key", &api_key)]).send().await?;
-    let result: Collection = response.json().await?;
+    let candidates = search_results.iter().map(|result| Candidate::new(&result.name, describe("Collection", &[]), result.id)).collect();
+
+    let prompt = format!("Several collections match `{query}`. Which one did you mean?");
+    choose(
+        context,
+        "tmdb",
+        prompt,
+        candidates,
+        first,
+        |context, id| async move { collection_by_id(&context, id.parse()?).await }
+    )
+    .await
+}
 
+/// Builds an embed containing information about the collection with the given ID.
+pub async fn collection_by_id(context: &Context, id: u64) -> CommandResult<Response> {
+    let result = get_services(context).await.movies.collection(id).await?;
+    let (embed, rows) = collection_embed(result);
+
+    Ok(Response::Embed(embed, rows))
+}
+
+/// Builds the embed describing the given collection, along with links to
+/// each of its movies.
+pub fn collection_embed(result: Collection) -> (CreateEmbed, Vec<CreateActionRow>) {
+    let id = result.id;
     let name = result.name;
     let poster = format!("https://image.tmdb.org/t/p/original{}", result.poster_path);
     let url = format!("https://www.themoviedb.org/collection/{id}");
@@ -101,7 +97,5 @@ async fn collection(context: &Context, message: &Message, arguments: Args) -> Co
         .fields(fields)
         .footer(CreateEmbedFooter::new("Powered by TMDb."));
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed).components(rows)).await?;
-
-    Ok(())
+    (embed, rows)
 }
@@ -3,11 +3,14 @@ pub mod collection;
 pub mod movie;
 pub mod show;
 
+use crate::listeners::checks::TMDB_CHECK;
+
 use self::cast::*;
 use self::collection::*;
 use self::movie::*;
 use self::show::*;
 
+use itertools::Itertools;
 use serenity::{
     client::Context,
     framework::standard::{macros::command, CommandResult},
@@ -17,7 +20,26 @@ use serenity::{
 /// Gets a variety of information from the API provided by The Movie Database.
 #[command]
 #[sub_commands(cast, collection, movie, show)]
+#[checks(Tmdb)]
 async fn tmdb(context: &Context, message: &Message) -> CommandResult {
     message.channel_id.say(context, "No valid subcommand entered. Do `help tmdb` to see the commands.").await?;
     Ok(())
 }
+
+/// Splits a year given with the `y:` or `year:` notation, as supported by The
+/// Movie Database's website, off the end of a search query.
+pub fn split_year(query: &str) -> (String, Option<String>) {
+    if !query.contains("y:") && !query.contains("year:") {
+        return (query.to_string(), None);
+    }
+
+    // This is a pretty hacky way of being able to search by year, but
+    // surprisingly enough it actually works from what I've tested, and
+    // while it might be a tad slow, it should compute fast enough to not
+    // make users wonder why its taking so long for the response to send.
+    let query = query.replace(" y:", "").replace(" year:", "");
+    let mut year_rev: Vec<char> = query.chars().rev().take(4).collect();
+    year_rev.reverse();
+    let year = year_rev.iter().join("");
+    (query.replace(&year, ""), Some(year))
+}
@@ -1,10 +1,8 @@
 use humantime::format_duration;
 use itertools::Itertools;
-use reqwest::RequestBuilder;
-use serde::Deserialize;
 
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
@@ -12,71 +10,69 @@ use serenity::{
 
 use std::time::Duration;
 
+use super::split_year;
 use crate::{
-    data::ReqwestContainer,
+    commands::{
+        response::Response,
+        selection::{choose, describe, take_first_flag, year_of, Candidate}
+    },
+    listeners::checks::TMDB_CHECK,
     models::tmdb::movie::*,
-    utils::{format_int, locale, read_config}
+    services::get_services,
+    utils::{format_int, locale}
 };
 
-#[derive(Debug, Deserialize)]
-pub struct SearchResponse {
-    pub results: Vec<SearchMovie>
-}
-
-#[derive(Debug, Deserialize)]
-pub struct SearchMovie {
-    pub id: u64 // The movie's ID from the search result, which is all we need.
-}
-
 #[command]
+#[checks(Tmdb)]
 #[aliases("film")]
 #[description("Gets detailed information about a movie from The Movie Database.")]
+#[usage = "<name> [year:<year>] [--first]"]
+#[example = "Blade Runner"]
+#[example = "Dune year:2021"]
 async fn movie(context: &Context, message: &Message, arguments: Args) -> CommandResult {
     if arguments.rest().is_empty() {
         message.channel_id.say(context, "Invalid movie name provided. Please try again.").await?;
         return Ok(());
     }
 
-    let mut movie: String = arguments.rest().to_string();
-
-    let config = read_config("config.toml");
-    let api_key = config.api.entertainment.tmdb;
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-
-    let search_endpoint = "https://api.themoviedb.org/3/search/movie";
-    let search_response: RequestBuilder;
-
-    // This is a pretty hacky way of being able to search by year, but
-    // surprisingly enough it actually works from what I've tested, and
-    // while it might be a tad slow, it should compute fast enough to not
-    // make users wonder why its taking so long for the response to send.
-    //
-    // This code follows the y: notation syntax as available on the website
-    // for The Movie Database, with the additional ability to use year: in
-    // place of y:, depending on the user's preference.
-    if movie.contains("y:") || movie.contains("year:") {
-        movie = movie.replace(" y:", "").replace(" year:", "");
-        let mut year_rev: Vec<char> = movie.chars().rev().take(4).collect();
-        year_rev.reverse();
-        let year = year_rev.iter().join("");
-        movie = movie.replace(&year, "");
-        search_response = client.get(search_endpoint).query(&[("api_key", &api_key), ("query", &movie), ("year", &year)]);
-    } else {
-        search_response = client.get(search_endpoint).query(&[("api_key", &api_key), ("query", &movie)]);
-    }
+    movie_response(context, arguments.rest()).await?.send(context, message).await?;
 
-    let search_result: SearchResponse = search_response.send().await.unwrap().json().await.unwrap();
-    let search_results = search_result.results;
+    Ok(())
+}
+
+/// Builds an embed containing information about the movie matching the given
+/// query, asking which one was meant if there are several.
+pub async fn movie_response(context: &Context, query: &str) -> CommandResult<Response> {
+    let (query, first) = take_first_flag(query);
+    let (movie, year) = split_year(&query);
+    let movies = get_services(context).await.movies;
+
+    let search_results = movies.search_movies(&movie, year.as_deref()).await?;
     if search_results.is_empty() {
-        message.channel_id.say(context, format!("Nothing found for `{movie}`. Please try again.")).await?;
-        return Ok(());
+        return Ok(format!("Nothing found for `{movie}`. Please try again.").into());
     }
 
-    let id = search_results.first().unwrap().id;
-    let endpoint = format!("https://api.themoviedb.org/3/movie/{id}");
-    let response = client.get(&endpoint).query(&[("api_key", &api_key)]).send().await.unwrap();
-    let result: Movie = response.json().await.unwrap();
+    #[rustfmt::skip]
+    let candidates = search_results.iter().map(|result| {
+        let description = describe("Movie", &[year_of(result.release_date.as_deref())]);
+        Candidate::new(&result.title, description, result.id)
+    }).collect();
+
+    let prompt = format!("Several movies match `{}`. Which one did you mean?", movie.trim());
+    choose(context, "tmdb", prompt, candidates, first || year.is_some(), |context, id| async move {
+        movie_by_id(&context, id.parse()?).await
+    })
+    .await
+}
+
+/// Builds an embed containing information about the movie with the given ID.
+pub async fn movie_by_id(context: &Context, id: u64) -> CommandResult<Response> {
+    let result = get_services(context).await.movies.movie(id).await?;
+    Ok(Response::embed(movie_embed(result)))
+}
 
+/// Builds the embed describing the given movie.
+pub fn movie_embed(result: Movie) -> CreateEmbed {
     let tagline = match result.tagline {
         Some(tagline) => {
             if tagline.is_empty() {
@@ -125,44 +121,115 @@ async fn movie(context: &Context, message: &Message, arguments: Args) -> Command
     let title = result.title.as_str();
     let status = result.status;
     let language = locale::get_language_name_from_iso(&result.original_language).to_string();
-    let release_date = result.release_date.unwrap().format("%B %e, %Y").to_string();
+    let release_date = result.release_date.map(|date| date.format("%B %e, %Y").to_string());
     let budget = format_int(result.budget);
     let revenue = format_int(result.revenue);
-    let imdb = format!("[IMDb](https://www.imdb.com/title/{})", result.imdb_id.unwrap());
+    let imdb = result.imdb_id.map(|imdb_id| format!("[IMDb](https://www.imdb.com/title/{imdb_id})"));
     let url = format!("https://www.themoviedb.org/movie/{id}");
     let genres = result.genres.iter().map(|g| &g.name).join("\n");
     let popularity = format!("{}%", result.popularity);
-    let poster_uri = result.poster_path.unwrap();
-    let poster = format!("https://image.tmdb.org/t/p/original/{}", &poster_uri.replace('/', ""));
+    let poster = result.poster_path.map(|path| format!("https://image.tmdb.org/t/p/original/{}", path.replace('/', "")));
     let user_score = format!("{}/100", result.vote_average * 10.0);
     let user_score_count = result.vote_count;
-    let runtime = format_duration(Duration::from_secs(result.runtime.unwrap() * 60)).to_string();
-    let external_links = format!("{homepage} | {imdb}");
+    let runtime = result.runtime.map(|runtime| format_duration(Duration::from_secs(runtime * 60)).to_string());
+    let external_links = match imdb {
+        Some(imdb) => format!("{homepage} | {imdb}"),
+        None => homepage
+    };
 
-    let embed = CreateEmbed::new()
+    // Details TMDb doesn't know yet are left out rather than filled in.
+    #[rustfmt::skip]
+    let fields = [
+        ("Status", Some(status), true),
+        ("Film ID", Some(id), true),
+        ("Language", Some(language), true),
+        ("Runtime", runtime, true),
+        ("Release Date", release_date, true),
+        ("Collection", Some(collection), true),
+        ("Popularity", Some(popularity), true),
+        ("User Score", Some(format!("{user_score} ({user_score_count} votes)")), true),
+        ("Budget", Some(format!("${budget}")), true),
+        ("Box Office", Some(format!("${revenue}")), true),
+        ("Genres", Some(genres), true),
+        ("Studios", Some(studios), true),
+        ("External Links", Some(external_links), false),
+    ].into_iter().filter_map(|(name, value, inline)| Some((name, value?, inline)));
+
+    let mut embed = CreateEmbed::new()
         .title(title)
         .url(url)
         .color(0x01b4e4)
-        .thumbnail(poster)
         .description(format!("{tagline}{overview}"))
-        .fields(vec![
-            ("Status", status, true),
-            ("Film ID", id, true),
-            ("Language", language, true),
-            ("Runtime", runtime, true),
-            ("Release Date", release_date, true),
-            ("Collection", collection, true),
-            ("Popularity", popularity, true),
-            ("User Score", format!("{user_score} ({user_score_count} votes)"), true),
-            ("Budget", format!("${budget}"), true),
-            ("Box Office", format!("${revenue}"), true),
-            ("Genres", genres, true),
-            ("Studios", studios, true),
-            ("External Links", external_links, false),
-        ])
+        .fields(fields)
         .footer(CreateEmbedFooter::new("Powered by the The Movie Database API."));
 
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed)).await.unwrap();
+    if let Some(poster) = poster {
+        embed = embed.thumbnail(poster);
+    }
 
-    Ok(())
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{field, parse_fixture, to_json};
+
+    #[test]
+    fn movie_embed_lists_the_details() {
+        let embed = to_json(&movie_embed(parse_fixture("movie")));
+
+        assert_eq!(embed["title"], "The Matrix");
+        assert_eq!(embed["url"], "https://www.themoviedb.org/movie/603");
+        assert_eq!(embed["thumbnail"]["url"], "https://image.tmdb.org/t/p/original/f89U3ADr1oiB1s9GkdPOEpXUk5H.jpg");
+        assert!(embed["description"].as_str().unwrap().starts_with("*Welcome to the Real World.*\n\nSet in the 22nd century"));
+        assert_eq!(field(&embed, "Film ID"), "603");
+        assert_eq!(field(&embed, "Language"), "English");
+        assert_eq!(field(&embed, "Runtime"), "2h 16m");
+        assert_eq!(field(&embed, "Release Date"), "March 30, 1999");
+        assert_eq!(field(&embed, "Collection"), "The Matrix Collection");
+        assert_eq!(field(&embed, "Popularity"), "79.5%");
+        assert_eq!(field(&embed, "User Score"), "85/100 (23000 votes)");
+        assert_eq!(field(&embed, "Budget"), "$63,000,000");
+        assert_eq!(field(&embed, "Box Office"), "$463,517,383");
+        assert_eq!(field(&embed, "Genres"), "Action\nScience Fiction");
+        assert_eq!(field(&embed, "Studios"), "Village Roadshow Pictures\nGroucho II Film Partnership");
+        assert_eq!(
+            field(&embed, "External Links"),
+            "[Website](http://www.warnerbros.com/matrix) | [IMDb](https://www.imdb.com/title/tt0133093)"
+        );
+    }
+
+    #[test]
+    fn movie_embed_fills_in_missing_details() {
+        let mut movie: Movie = parse_fixture("movie");
+        movie.tagline = None;
+        movie.homepage = Some(String::new());
+        movie.belongs_to_collection = None;
+        movie.production_companies.clear();
+
+        let embed = to_json(&movie_embed(movie));
+
+        assert!(embed["description"].as_str().unwrap().starts_with("Set in the 22nd century"));
+        assert_eq!(field(&embed, "Collection"), "N/A");
+        assert_eq!(field(&embed, "Studios"), "No Known Studios");
+        assert_eq!(field(&embed, "External Links"), "No Website | [IMDb](https://www.imdb.com/title/tt0133093)");
+    }
+
+    #[test]
+    fn movie_embed_leaves_out_unknown_details() {
+        let mut movie: Movie = parse_fixture("movie");
+        movie.release_date = None;
+        movie.runtime = None;
+        movie.imdb_id = None;
+        movie.poster_path = None;
+
+        let embed = to_json(&movie_embed(movie));
+        let names: Vec<&str> = embed["fields"].as_array().unwrap().iter().map(|field| field["name"].as_str().unwrap()).collect();
+
+        assert!(!names.contains(&"Runtime"));
+        assert!(!names.contains(&"Release Date"));
+        assert!(embed.get("thumbnail").map_or(true, |thumbnail| thumbnail.is_null()));
+        assert_eq!(field(&embed, "External Links"), "[Website](http://www.warnerbros.com/matrix)");
+    }
 }
@@ -1,9 +1,8 @@
 use humantime::format_duration;
 use itertools::Itertools;
-use serde::Deserialize;
 
 use serenity::{
-    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
@@ -11,57 +10,76 @@ use serenity::{
 
 use std::time::Duration;
 
+use super::split_year;
 use crate::{
-    data::ReqwestContainer,
+    commands::{
+        response::Response,
+        selection::{choose, describe, take_first_flag, year_of, Candidate}
+    },
+    listeners::checks::TMDB_CHECK,
     models::tmdb::show::*,
-    utils::{calculate_average_sum, locale, read_config}
+    services::get_services,
+    utils::{calculate_average_sum, locale}
 };
 
-#[derive(Debug, Deserialize)]
-pub struct SearchResponse {
-    pub results: Vec<Result>
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Result {
-    pub id: i64
-}
-
 #[command]
+#[checks(Tmdb)]
 #[aliases("show", "series")]
 #[description("Gets detailed information about a TV series from The Movie Database.")]
+#[usage = "<name> [year:<year>] [--first]"]
+#[example = "The Office"]
+#[example = "Battlestar Galactica year:2004"]
 async fn show(context: &Context, message: &Message, arguments: Args) -> CommandResult {
     if arguments.rest().is_empty() {
         message.channel_id.say(context, "Invalid show name provided. Please try again.").await?;
         return Ok(());
     }
 
-    let show: String = arguments.rest().to_string();
+    show_response(context, arguments.rest()).await?.send(context, message).await?;
+
+    Ok(())
+}
 
-    let config = read_config("config.toml");
-    let api_key = config.api.entertainment.tmdb;
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
+/// Builds an embed containing information about the series matching the
+/// given query, asking which one was meant if there are several.
+pub async fn show_response(context: &Context, query: &str) -> CommandResult<Response> {
+    let (query, first) = take_first_flag(&query.replace(" --cast", "").replace(" -c", ""));
+    let (show, year) = split_year(&query);
+    let shows = get_services(context).await.movies;
 
-    let search_endpoint = "https://api.themoviedb.org/3/search/tv";
-    let search_query = ("query", &show.replace(" --cast", "").replace(" -c", ""));
-    let search_response = client.get(search_endpoint).query(&[("api_key", &api_key), search_query]);
-    let search_result: SearchResponse = search_response.send().await.unwrap().json().await.unwrap();
-    let search_results = search_result.results;
+    let search_results = shows.search_shows(&show, year.as_deref()).await?;
     if search_results.is_empty() {
-        message.channel_id.say(context, format!("Nothing found for `{show}`. Please try a different name.")).await?;
-        return Ok(());
+        return Ok(format!("Nothing found for `{show}`. Please try a different name.").into());
     }
 
-    let id = search_results.first().unwrap().id;
-    let endpoint = format!("https://api.themoviedb.org/3/tv/{id}");
-    let sub_requests = ("append_to_response", &"external_ids".to_string());
-    let response = client.get(&endpoint).query(&[("api_key", &api_key), sub_requests]).send().await.unwrap();
-    let result: Show = response.json().await.unwrap();
-    let poster_path = result.poster_path.unwrap();
-    let poster = format!("https://image.tmdb.org/t/p/original/{}", &poster_path.replace('/', ""));
+    #[rustfmt::skip]
+    let candidates = search_results.iter().map(|result| {
+        let description = describe("Series", &[year_of(result.first_air_date.as_deref())]);
+        Candidate::new(&result.name, description, result.id)
+    }).collect();
+
+    let prompt = format!("Several series match `{}`. Which one did you mean?", show.trim());
+    choose(context, "tmdb", prompt, candidates, first || year.is_some(), |context, id| async move {
+        show_by_id(&context, id.parse()?).await
+    })
+    .await
+}
+
+/// Builds an embed containing information about the series with the given ID.
+pub async fn show_by_id(context: &Context, id: i64) -> CommandResult<Response> {
+    let result = get_services(context).await.movies.show(id).await?;
+    let (embed, links) = show_embed(result);
+
+    Ok(Response::Embed(embed, links.into_iter().collect()))
+}
+
+/// Builds the embed describing the given series, along with a link to its
+/// IMDb page if TMDb knows it.
+pub fn show_embed(result: Show) -> (CreateEmbed, Option<CreateActionRow>) {
+    let poster = result.poster_path.map(|path| format!("https://image.tmdb.org/t/p/original/{}", path.replace('/', "")));
 
     let title = result.name;
-    let url = format!("https://themoviedb.org/tv/{}", &id);
+    let url = format!("https://themoviedb.org/tv/{}", result.id);
     let status = result.status;
     let format = result.format;
     let average_runtime = calculate_average_sum(&result.episode_run_time);
@@ -72,8 +90,7 @@ async fn show(context: &Context, message: &Message, arguments: Args) -> CommandR
     let networks = result.networks.iter().map(|n| &n.name).join("\n");
     let seasons = result.number_of_seasons.to_string();
     let episodes = result.number_of_episodes.to_string();
-    let imdb_id = result.external_ids.imdb_id.unwrap();
-    let imdb = format!("https://www.imdb.com/title/{imdb_id}");
+    let imdb = result.external_ids.imdb_id.map(|imdb_id| format!("https://www.imdb.com/title/{imdb_id}"));
     let genres = result.genres.iter().map(|genre| &genre.name).join("\n");
     let tagline = if !result.tagline.is_empty() { format!("*{}*", result.tagline) } else { String::new() };
 
@@ -119,17 +136,61 @@ async fn show(context: &Context, message: &Message, arguments: Args) -> CommandR
         ("Production Status", production_status.to_string(), true),
     ];
 
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .title(title)
         .url(url)
-        .thumbnail(poster)
         .color(0x01b4e4)
         .description(tagline)
         .fields(fields)
         .footer(CreateEmbedFooter::new("Powered by TMDb."));
 
-    let links = CreateActionRow::Buttons(vec![(CreateButton::new_link(imdb)).label("View IMDb Page")]);
-    message.channel_id.send_message(&context, CreateMessage::new().embed(embed).components(vec![links])).await?;
+    if let Some(poster) = poster {
+        embed = embed.thumbnail(poster);
+    }
 
-    Ok(())
+    let links = imdb.map(|imdb| CreateActionRow::Buttons(vec![CreateButton::new_link(imdb).label("View IMDb Page")]));
+
+    (embed, links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{field, parse_fixture, to_json};
+
+    #[test]
+    fn show_embed_lists_the_details() {
+        let (embed, links) = show_embed(parse_fixture("show"));
+        let (embed, links) = (to_json(&embed), to_json(&links.unwrap()));
+
+        assert_eq!(embed["title"], "Breaking Bad");
+        assert_eq!(embed["url"], "https://themoviedb.org/tv/1396");
+        assert_eq!(embed["description"], "*Remember my name*");
+        assert_eq!(field(&embed, "Created By"), "Vince Gilligan");
+        assert_eq!(field(&embed, "Runtime"), "46m");
+        assert_eq!(field(&embed, "First Air Date"), "January 20, 2008");
+        assert_eq!(field(&embed, "Last Air Date"), "September 29, 2013");
+        assert_eq!(field(&embed, "Main Language"), "English");
+        assert_eq!(field(&embed, "Origin Countries"), "United States");
+        assert_eq!(field(&embed, "Popularity"), "400.25%");
+        assert_eq!(field(&embed, "User Score"), "87.5/100 (11500 votes)");
+        assert_eq!(field(&embed, "Networks / Services"), "AMC");
+        assert_eq!(field(&embed, "Studios"), "Sony Pictures Television Studios\nHigh Bridge Productions");
+        assert_eq!(field(&embed, "Production Status"), "Finished Production");
+        assert_eq!(embed["footer"]["text"], "Powered by TMDb.");
+        assert_eq!(links["components"][0]["url"], "https://www.imdb.com/title/tt0903747");
+    }
+
+    #[test]
+    fn show_embed_leaves_out_unknown_links() {
+        let mut show: Show = parse_fixture("show");
+        show.poster_path = None;
+        show.external_ids.imdb_id = None;
+
+        let (embed, links) = show_embed(show);
+        let embed = to_json(&embed);
+
+        assert!(embed.get("thumbnail").map_or(true, |thumbnail| thumbnail.is_null()));
+        assert!(links.is_none());
+    }
 }
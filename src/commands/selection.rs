@@ -0,0 +1,171 @@
+//! Search result selection
+//!
+//! Searches that match several items ask which one was meant with a select
+//! menu listing the top candidates, rather than silently taking the first.
+//! Adding `--first` to a query skips the prompt. The prompt is withdrawn if
+//! nothing is chosen for a while.
+
+use serenity::{
+    builder::{CreateActionRow, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::{application::ComponentInteractionDataKind, channel::Message, id::UserId}
+};
+use std::{future::Future, pin::Pin, time::Duration};
+use tracing::warn;
+
+use super::{components::ComponentListener, response::Response};
+use crate::error::report;
+
+/// How long the prompt waits for a choice.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How many candidates are offered at most.
+pub const CANDIDATES: usize = 10;
+
+/// The flag that skips the prompt and takes the first result.
+const FIRST_FLAG: &str = "--first";
+
+const MENU: &str = "selection";
+
+/// The longest label or description Discord allows for a menu option.
+const OPTION_LIMIT: usize = 100;
+
+type Resolver = Box<dyn FnOnce(Context, String) -> Pin<Box<dyn Future<Output = CommandResult<Response>> + Send>> + Send + Sync>;
+
+/// One of the items a search matched.
+pub struct Candidate {
+    label: String,
+    description: String,
+    value: String
+}
+
+impl Candidate {
+    /// Creates a candidate, where `value` identifies the item to look up if
+    /// it is chosen and `description` tells it apart from the others, e.g. by
+    /// its year and type.
+    pub fn new(label: impl Into<String>, description: impl Into<String>, value: impl ToString) -> Candidate {
+        Candidate {
+            label: truncate(label.into()),
+            description: truncate(description.into()),
+            value: value.to_string()
+        }
+    }
+}
+
+pub struct Selection {
+    command: &'static str,
+    prompt: String,
+    candidates: Vec<Candidate>,
+    resolve: Resolver
+}
+
+impl Selection {
+    /// The text asking which candidate was meant.
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    /// The select menu listing the candidates.
+    pub fn components(&self) -> Vec<CreateActionRow> {
+        let options = self
+            .candidates
+            .iter()
+            .map(|candidate| CreateSelectMenuOption::new(&candidate.label, &candidate.value).description(&candidate.description))
+            .collect();
+
+        vec![CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(MENU, CreateSelectMenuKind::String { options }).placeholder("Choose a result")
+        )]
+    }
+
+    /// Waits for the given user to choose a candidate on the given message,
+    /// then replaces the prompt with the response for their choice. Choices
+    /// by anyone else are turned away.
+    pub fn listen(self, context: Context, message: Message, author: UserId) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            let mut listener = ComponentListener::new(message, author, TIMEOUT, "Only the person who used this command can choose a result.")
+                .expiry("No result was chosen in time, so nothing was looked up.");
+
+            while let Some(interaction) = listener.next(&context).await {
+                let value = match &interaction.data.kind {
+                    ComponentInteractionDataKind::StringSelect { values } if !values.is_empty() => values[0].clone(),
+                    _ => continue
+                };
+
+                // Looking up the choice can take longer than Discord waits for
+                // a reply, so the interaction is acknowledged first.
+                if let Err(why) = interaction.defer(&context.http).await {
+                    warn!("Unable to acknowledge a choice: {why}");
+                }
+
+                let response = match (self.resolve)(context.clone(), value).await {
+                    Ok(response) => response,
+                    Err(why) => report(self.command, why.as_ref()).into()
+                };
+
+                if let Err(why) = response.edit(&context, listener.message(), author).await {
+                    warn!("Unable to show the chosen result: {why}");
+                }
+
+                return;
+            }
+        })
+    }
+}
+
+/// Removes the `--first` flag from a query, returning whether it was given.
+pub fn take_first_flag(query: &str) -> (String, bool) {
+    if !query.split_whitespace().any(|word| word == FIRST_FLAG) {
+        return (query.to_string(), false);
+    }
+
+    (query.split_whitespace().filter(|word| *word != FIRST_FLAG).collect::<Vec<_>>().join(" "), true)
+}
+
+/// Looks up the first candidate right away if it is the only one or `first`
+/// is set, and otherwise asks which candidate was meant. `resolve` is given
+/// the value of the candidate to look up.
+pub async fn choose<F, Fut>(context: &Context, command: &'static str, prompt: String, mut candidates: Vec<Candidate>, first: bool, resolve: F) -> CommandResult<Response>
+where
+    F: FnOnce(Context, String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = CommandResult<Response>> + Send + 'static
+{
+    if first || candidates.len() == 1 {
+        let candidate = candidates.into_iter().next().ok_or("There is nothing to choose from.")?;
+        return resolve(context.clone(), candidate.value).await;
+    }
+
+    candidates.truncate(CANDIDATES);
+
+    Ok(Response::Selection(Selection {
+        command,
+        prompt,
+        candidates,
+        resolve: Box::new(move |context, value| Box::pin(resolve(context, value)))
+    }))
+}
+
+/// Describes a candidate by its type followed by any details that aren't
+/// empty, such as its year.
+pub fn describe(kind: &str, details: &[&str]) -> String {
+    let mut parts = vec![kind];
+    parts.extend(details.iter().copied().filter(|detail| !detail.is_empty()));
+    parts.join(" · ")
+}
+
+/// The year of a date given as `YYYY-MM-DD`, or nothing if it is unknown.
+pub fn year_of(date: Option<&str>) -> &str {
+    date.and_then(|date| date.get(..4)).unwrap_or_default()
+}
+
+/// Shortens text to fit in a menu option.
+fn truncate(text: String) -> String {
+    if text.chars().count() <= OPTION_LIMIT {
+        return text;
+    }
+
+    let mut truncated: String = text.chars().take(OPTION_LIMIT - 1).collect();
+    truncated.push('…');
+    truncated
+}
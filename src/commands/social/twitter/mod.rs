@@ -1,5 +1,7 @@
 pub mod user;
 
+use crate::listeners::checks::TWITTER_CHECK;
+
 use self::user::*;
 
 use serenity::{
@@ -11,6 +13,7 @@ use serenity::{
 /// Gets a variety of information from the API provided by The Movie Database.
 #[command]
 #[sub_commands(user)]
+#[checks(Twitter)]
 #[aliases("tw")]
 async fn twitter(context: &Context, message: &Message) -> CommandResult {
     message.channel_id.say(context, "No valid subcommand entered. Do `help twitter` to see the commands.").await?;
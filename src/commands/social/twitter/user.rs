@@ -1,68 +1,44 @@
-use crate::data::ReqwestContainer;
-use crate::read_config;
+use crate::commands::response::Response;
+use crate::listeners::checks::TWITTER_CHECK;
+use crate::models::twitter::UserData;
+use crate::services::get_services;
 use crate::utils::format_int;
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
 use serenity::{
-    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    builder::{CreateEmbed, CreateEmbedFooter},
     client::Context,
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message
 };
 
-#[derive(Deserialize)]
-struct User {
-    pub data: UserData
-}
-
-#[derive(Deserialize)]
-struct UserData {
-    pub id: String,                        // The user's Twitter identifier.
-    pub name: String,                      // The user's display name.
-    pub username: String,                  // The user's username / handle.
-    pub created_at: DateTime<Utc>,         // The user's date of when they joined Twitter, in UTC.
-    pub protected: bool,                   // The user's protected account status, e.g. whether or not tweets are private.
-    pub location: Option<String>,          // The user's provided location, if available.
-    pub description: String,               // The user's description / bio.
-    pub verified: bool,                    // The user's verified status.
-    pub profile_image_url: String,         // The user's profile image.
-    pub public_metrics: UserPublicMetrics  // The user's publicly available metrics, such as followers / following.
-}
-
-#[derive(Deserialize)]
-struct UserPublicMetrics {
-    pub followers_count: u64, // The amount of people that follow the given user.
-    pub following_count: u64, // The amount of people that the given user is following.
-    pub tweet_count: u64      // The total amount of times the given user has Tweeted.
-}
-
-#[derive(Deserialize)]
-struct UserTweets {
-    pub data: Option<Vec<UserTweet>>
-}
-
-#[derive(Deserialize)]
-struct UserTweet {
-    pub text: String // the text
-}
-
 #[command]
+#[checks(Twitter)]
 #[min_args(1)]
 #[max_args(1)]
+#[usage = "<username>"]
+#[example = "rustlang"]
 /// Displays information about a given user on Twitter.
 async fn user(context: &Context, message: &Message, mut args: Args) -> CommandResult {
-    let user_fields = [("user.fields", "created_at,protected,location,public_metrics,description,verified,profile_image_url")];
-    let tweet_fields = [("max_results", "5"), ("exclude", "retweets,replies")];
-    let user: String = args.single().unwrap();
+    let user: String = args.single()?;
+    twitter_user_response(context, &user).await?.send(context, message).await?;
 
-    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-    let config = read_config("config.toml");
-    let bearer = config.api.social.twitter.core.bearer_token;
+    Ok(())
+}
 
-    let mut endpoint = format!("https://api.twitter.com/2/users/by/username/{user}");
-    let mut request = client.get(&endpoint).bearer_auth(&bearer).query(&user_fields).send().await?;
+/// Builds an embed containing information about the given Twitter user.
+pub async fn twitter_user_response(context: &Context, user: &str) -> CommandResult<Response> {
+    let microblog = get_services(context).await.microblog;
+    let user = microblog.user(user).await?;
+    let tweets = microblog.tweets(&user.id).await?;
+    let latest_tweet = match tweets.first() {
+        Some(tweet) => tweet.text.clone(),
+        None => "Tweet not available.".to_string()
+    };
+
+    Ok(Response::embed(user_embed(user, latest_tweet)))
+}
 
-    let user = request.json::<User>().await?.data;
+/// Builds the embed describing the given user.
+pub fn user_embed(user: UserData, latest_tweet: String) -> CreateEmbed {
     let id = &user.id;
     let name = &user.name;
     let handle = user.username;
@@ -76,36 +52,21 @@ async fn user(context: &Context, message: &Message, mut args: Args) -> CommandRe
     let followers = format_int(user.public_metrics.followers_count);
     let tweets = format_int(user.public_metrics.tweet_count);
 
-    endpoint = format!("https://api.twitter.com/2/users/{id}/tweets");
-    request = client.get(&endpoint).bearer_auth(&bearer).query(&tweet_fields).send().await?;
-
-    let tweets_response: UserTweets = request.json().await?;
-    let latest_tweet = match tweets_response.data {
-        Some(tweets) => tweets.first().unwrap().text.clone(),
-        None => "Tweet not available.".to_string()
-    };
-
-    let builder = CreateMessage::new().embed(
-        CreateEmbed::new()
-            .title(format!("{name}{verified}", verified = if user.verified { " \\✔️" } else { "" }))
-            .url(url)
-            .thumbnail(avatar)
-            .color(0x00acee)
-            .description(description)
-            .fields(vec![
-                ("Username", handle, true),
-                ("Join Date", joined, true),
-                ("Protected", protected, true),
-                ("Location", if location.is_some() { location.unwrap() } else { "None".to_string() }, true),
-                ("Following", following, true),
-                ("Followers", followers, true),
-                ("Tweets", tweets, true),
-                ("Latest Tweet", latest_tweet, false),
-            ])
-            .footer(CreateEmbedFooter::new(format!("User ID: {id} | Powered by Twitter.")))
-    );
-
-    message.channel_id.send_message(context, builder).await?;
-
-    Ok(())
+    CreateEmbed::new()
+        .title(format!("{name}{verified}", verified = if user.verified { " \\✔️" } else { "" }))
+        .url(url)
+        .thumbnail(avatar)
+        .color(0x00acee)
+        .description(description)
+        .fields(vec![
+            ("Username", handle, true),
+            ("Join Date", joined, true),
+            ("Protected", protected, true),
+            ("Location", if location.is_some() { location.unwrap() } else { "None".to_string() }, true),
+            ("Following", following, true),
+            ("Followers", followers, true),
+            ("Tweets", tweets, true),
+            ("Latest Tweet", latest_tweet, false),
+        ])
+        .footer(CreateEmbedFooter::new(format!("User ID: {id} | Powered by Twitter.")))
 }
@@ -0,0 +1,38 @@
+use serenity::{
+    client::Context,
+    framework::standard::{macros::command, CommandResult},
+    model::channel::Message
+};
+
+use crate::utils::cache::get_cache;
+
+#[command]
+#[owners_only]
+#[sub_commands(cache_flush)]
+/// Shows how many external API lookups the response cache has served.
+///
+/// Use the `flush` subcommand to empty it.
+async fn cache(context: &Context, message: &Message) -> CommandResult {
+    let stats = get_cache(context).await.stats();
+    let lookups = stats.hits + stats.misses;
+    let hit_rate = if lookups == 0 { 0.0 } else { stats.hits as f64 / lookups as f64 * 100.0 };
+
+    let response = format!(
+        "**Entries**: {}\n**Hits**: {}\n**Misses**: {}\n**Hit Rate**: {hit_rate:.1}%",
+        stats.entries, stats.hits, stats.misses
+    );
+
+    message.channel_id.say(context, response).await?;
+
+    Ok(())
+}
+
+#[command("flush")]
+#[owners_only]
+/// Removes every entry from the response cache and resets its statistics.
+async fn cache_flush(context: &Context, message: &Message) -> CommandResult {
+    let removed = get_cache(context).await.flush().await?;
+    message.channel_id.say(context, format!("Flushed {removed} entries from the response cache.")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! Help command
+//!
+//! Lists the commands of every group on the pages of an embed, and describes
+//! a single command or subcommand in detail with its usage and examples.
+//! Commands the member can't run are left out, whether they lack permissions,
+//! the command is limited to the owners or to guilds, or one of its checks
+//! fails, e.g. because its integration isn't configured.
+
+use itertools::Itertools;
+use serenity::{
+    builder::{CreateEmbed, CreateEmbedFooter},
+    client::Context,
+    framework::standard::{macros::help, Args, Command, CommandGroup, CommandOptions, CommandResult, HelpOptions, OnlyIn},
+    model::{
+        prelude::{Message, UserId},
+        Permissions
+    }
+};
+use std::collections::HashSet;
+
+use crate::{
+    commands::{paginator::Paginator, response::Response},
+    listeners::hooks::dynamic_prefix,
+    utils::prefixes::default_prefix
+};
+
+/// How many lines of a group's command list are shown on each page.
+const PAGE_SIZE: usize = 20;
+
+#[help]
+async fn help(context: &Context, message: &Message, args: Args, _: &'static HelpOptions, groups: &[&'static CommandGroup], owners: HashSet<UserId>) -> CommandResult {
+    let prefix = match dynamic_prefix(context, message).await {
+        Some(prefix) => prefix,
+        None => default_prefix(context).await
+    };
+
+    let words: Vec<&str> = args.rest().split_whitespace().collect();
+    help_response(context, message, groups, &owners, &prefix, &words).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds the help for the given words, which may name a group, a command or
+/// a command followed by its subcommands, optionally after its group. Without
+/// any words, every group is listed.
+pub async fn help_response(context: &Context, message: &Message, groups: &[&'static CommandGroup], owners: &HashSet<UserId>, prefix: &str, words: &[&str]) -> CommandResult<Response> {
+    let mut visible = Vec::new();
+    for group in groups {
+        let mut commands = Vec::new();
+        for command in group.options.commands {
+            if can_run(context, message, group, command.options, owners).await {
+                commands.push(*command);
+            }
+        }
+
+        if !commands.is_empty() {
+            visible.push((*group, commands));
+        }
+    }
+
+    let footer = format!("Use {prefix}help <command> [subcommand] for details on a command.");
+
+    let (name, path) = match words.split_first() {
+        Some((name, path)) => (name, path),
+        None => {
+            let mut paginator = Paginator::new(Vec::new());
+            for (group, commands) in &visible {
+                paginator = paginator.chain(group_pages(context, message, group, commands, owners, prefix).await);
+            }
+
+            return Ok(Response::Paginated(paginator.footer(footer)));
+        }
+    };
+
+    // A group can be named before one of its commands, as in `help search tmdb`.
+    let (name, path, searched) = match visible.iter().position(|(group, _)| group.name.eq_ignore_ascii_case(name)) {
+        Some(index) if path.is_empty() => {
+            let (group, commands) = &visible[index];
+            return Ok(Response::Paginated(group_pages(context, message, group, commands, owners, prefix).await.footer(footer)));
+        }
+        Some(index) => (&path[0], &path[1..], &visible[index..=index]),
+        None => (name, path, &visible[..])
+    };
+
+    let found = searched.iter().find_map(|(group, commands)| find(commands, name).map(|command| (*group, command)));
+    let (group, mut command) = match found {
+        Some(found) => found,
+        None => return Ok(format!("There is no command or group named `{name}` that you can use.").into())
+    };
+
+    let mut names = vec![command.options.names[0]];
+    for name in path {
+        let subcommands = runnable(context, message, group, command.options.sub_commands, owners).await;
+        command = match find(&subcommands, name) {
+            Some(subcommand) => subcommand,
+            None => return Ok(format!("`{prefix}{}` has no subcommand named `{name}` that you can use.", names.join(" ")).into())
+        };
+        names.push(command.options.names[0]);
+    }
+
+    let subcommands = runnable(context, message, group, command.options.sub_commands, owners).await;
+    let embed = command_embed(group, command, &subcommands, &format!("{prefix}{}", names.join(" "))).footer(CreateEmbedFooter::new(footer));
+
+    Ok(Response::embed(embed))
+}
+
+/// Lists the commands of a group along with their subcommands.
+async fn group_pages(context: &Context, message: &Message, group: &CommandGroup, commands: &[&'static Command], owners: &HashSet<UserId>, prefix: &str) -> Paginator {
+    let mut lines = Vec::new();
+    for command in commands {
+        let name = command.options.names[0];
+        lines.push(format!("`{prefix}{name}` — {}", command.options.desc.unwrap_or("No description available.")));
+
+        for subcommand in runnable(context, message, group, command.options.sub_commands, owners).await {
+            let subname = subcommand.options.names[0];
+            lines.push(format!("\u{2003}`{prefix}{name} {subname}` — {}", subcommand.options.desc.unwrap_or("No description available.")));
+        }
+    }
+
+    let title = format!("{} Commands", group.name);
+    let description = group.options.description.unwrap_or_default();
+
+    Paginator::from_lines(lines, PAGE_SIZE, |list| CreateEmbed::new().title(&title).description(format!("{description}\n\n{list}")))
+}
+
+/// Describes a single command, where `path` is how it is invoked.
+fn command_embed(group: &CommandGroup, command: &Command, subcommands: &[&'static Command], path: &str) -> CreateEmbed {
+    let options = command.options;
+    let usage = match options.usage {
+        Some(usage) => format!("`{path} {usage}`"),
+        None => format!("`{path}`")
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(path)
+        .description(options.desc.unwrap_or("No description available."))
+        .field("Usage", usage, false);
+
+    if !options.examples.is_empty() {
+        embed = embed.field("Examples", options.examples.iter().map(|example| format!("`{path} {example}`")).join("\n"), false);
+    }
+
+    if options.names.len() > 1 {
+        embed = embed.field("Aliases", options.names[1..].iter().map(|alias| format!("`{alias}`")).join(", "), true);
+    }
+
+    embed = embed.field("Group", group.name, true);
+
+    if !subcommands.is_empty() {
+        let list = subcommands
+            .iter()
+            .map(|subcommand| format!("`{}` — {}", subcommand.options.names[0], subcommand.options.desc.unwrap_or("No description available.")))
+            .join("\n");
+        embed = embed.field("Subcommands", list, false);
+    }
+
+    embed
+}
+
+/// Finds the command going by the given name or alias.
+fn find(commands: &[&'static Command], name: &str) -> Option<&'static Command> {
+    commands
+        .iter()
+        .copied()
+        .find(|command| command.options.names.iter().any(|alias| alias.eq_ignore_ascii_case(name)))
+}
+
+/// The given commands the author of the message can run.
+async fn runnable(context: &Context, message: &Message, group: &CommandGroup, commands: &[&'static Command], owners: &HashSet<UserId>) -> Vec<&'static Command> {
+    let mut runnable = Vec::new();
+    for command in commands {
+        if can_run(context, message, group, command.options, owners).await {
+            runnable.push(*command);
+        }
+    }
+
+    runnable
+}
+
+/// Whether the author of the message can run a command, as far as can be
+/// told before it is invoked.
+async fn can_run(context: &Context, message: &Message, group: &CommandGroup, options: &CommandOptions, owners: &HashSet<UserId>) -> bool {
+    if !options.help_available || !group.options.help_available {
+        return false;
+    }
+
+    let is_owner = owners.contains(&message.author.id);
+    if (options.owners_only || group.options.owners_only) && !is_owner {
+        return false;
+    }
+
+    let in_guild = message.guild_id.is_some();
+    for only_in in [&options.only_in, &group.options.only_in] {
+        match only_in {
+            OnlyIn::Guild if !in_guild => return false,
+            OnlyIn::Dm if in_guild => return false,
+            _ => {}
+        }
+    }
+
+    let required = options.required_permissions | group.options.required_permissions;
+    if in_guild && !required.is_empty() && !(is_owner && options.owner_privilege) {
+        let permissions = match message.member(context).await {
+            Ok(member) => member.permissions(context).unwrap_or_else(|_| Permissions::empty()),
+            Err(_) => Permissions::empty()
+        };
+
+        if !permissions.contains(required) {
+            return false;
+        }
+    }
+
+    for check in group.options.checks.iter().chain(options.checks) {
+        if check.check_in_help && (check.function)(context, message, &mut Args::new("", &[]), options).await.is_err() {
+            return false;
+        }
+    }
+
+    true
+}
@@ -1,40 +1,38 @@
-use crate::data::ShardManagerContainer;
+pub mod cache;
+pub mod help;
+pub mod prefix;
+pub mod remind;
+pub mod time;
+
+use crate::{commands::response::Response, data::ShardManagerContainer};
 use chrono::{Duration, Utc};
 use serenity::{
-    builder::{CreateEmbed, CreateMessage, EditMessage},
+    builder::{CreateEmbed, EditMessage},
     client::{bridge::gateway::ShardId, Context},
-    framework::standard::{
-        help_commands,
-        macros::{command, help},
-        Args, CommandGroup, CommandResult, HelpOptions
-    },
-    model::{
-        prelude::{Message, UserId},
-        Permissions
-    }
+    framework::standard::{macros::command, CommandResult},
+    model::{prelude::Message, Permissions}
 };
 
-use std::collections::HashSet;
 use tracing::log::error;
 
-#[help]
-#[max_levenshtein_distance(3)]
-#[no_help_available_text("No help information available.")]
-async fn help(ctx: &Context, msg: &Message, args: Args, opts: &'static HelpOptions, groups: &[&'static CommandGroup], owners: HashSet<UserId>) -> CommandResult {
-    drop(help_commands::plain(ctx, msg, args, opts, groups, owners).await);
-    Ok(())
-}
+pub const SOURCE_RESPONSE: &str = "GitHub repository: <https://github.com/evelynmarie/Taliyah>";
 
 #[command]
 #[description = "Generates an invite link for the bot."]
 async fn invite(context: &Context, message: &Message) -> CommandResult {
+    invite_response(context).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Builds an embed containing the bot's invite link.
+pub async fn invite_response(context: &Context) -> CommandResult<Response> {
     let current_user = &context.cache.current_user().clone();
     let url = match current_user.invite_url(&context.http, Permissions::empty()).await {
         Ok(invite) => invite,
         Err(why) => {
             error!("Encountered an error while trying to generate an invite: {}", why);
-            message.reply(context, "Couldn't generate invite.").await?;
-            return Ok(());
+            return Ok("Couldn't generate invite.".into());
         }
     };
 
@@ -46,9 +44,7 @@ async fn invite(context: &Context, message: &Message) -> CommandResult {
         .thumbnail(avatar)
         .description(format!("Click [here]({url}) to add {name} to your Discord server."));
 
-    message.channel_id.send_message(&context.http, CreateMessage::new().embed(embed)).await?;
-
-    Ok(())
+    Ok(Response::embed(embed))
 }
 
 #[command]
@@ -62,26 +58,36 @@ async fn ping(context: &Context, message: &Message) -> CommandResult {
     let end_ts = end.timestamp();
     let end_ts_ss = end.timestamp_subsec_millis() as i64;
     let api_response = ((end_ts - start_ts) * 1000) + (end_ts_ss - start_ts_ss);
+    let shard_response = shard_latency(context).await;
+
+    let response = format!(
+        "Pong! Succesfully retrieved the message and shard latencies. :ping_pong:\n\n\
+        **API Response Time**: `{api_response}ms`\n\
+        **Shard Response Time**: {shard_response}"
+    );
+
+    let embed = CreateEmbed::new().color(0x008b_0000).title("Discord Latency Information").description(response);
+    ping.edit(context, EditMessage::new().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Retrieves the latency of the shard the given context belongs to.
+pub async fn shard_latency(context: &Context) -> String {
     let ctx_data = context.data.read().await;
     let shard_manager = match ctx_data.get::<ShardManagerContainer>() {
         Some(shard) => shard,
-        None => {
-            message.reply(context, "I encountered a problem while getting the shard manager.").await?;
-            return Ok(());
-        }
+        None => return "I encountered a problem while getting the shard manager.".to_string()
     };
 
     let manager = shard_manager.lock().await;
     let runners = manager.runners.lock().await;
     let runner = match runners.get(&ShardId(context.shard_id)) {
         Some(runner) => runner,
-        None => {
-            message.reply(context, "Could not find a shard").await?;
-            return Ok(());
-        }
+        None => return "Could not find a shard".to_string()
     };
 
-    let shard_response = match runner.latency {
+    match runner.latency {
         Some(latency) => {
             if let Ok(time) = Duration::from_std(latency) {
                 let time_ms = time.num_milliseconds();
@@ -91,23 +97,12 @@ async fn ping(context: &Context, message: &Message) -> CommandResult {
             }
         }
         None => "No data available at the moment.".to_string()
-    };
-
-    let response = format!(
-        "Pong! Succesfully retrieved the message and shard latencies. :ping_pong:\n\n\
-        **API Response Time**: `{api_response}ms`\n\
-        **Shard Response Time**: {shard_response}"
-    );
-
-    let embed = CreateEmbed::new().color(0x008b_0000).title("Discord Latency Information").description(response);
-    ping.edit(context, EditMessage::new().embed(embed)).await?;
-
-    Ok(())
+    }
 }
 
 #[command]
 #[description = "Sends a link containing the bot's source code."]
 async fn source(context: &Context, message: &Message) -> CommandResult {
-    message.reply(context, "GitHub repository: <https://github.com/evelynmarie/Taliyah>").await?;
+    message.reply(context, SOURCE_RESPONSE).await?;
     Ok(())
 }
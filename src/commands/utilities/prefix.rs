@@ -0,0 +1,158 @@
+use serenity::{
+    client::Context,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{channel::Message, id::GuildId}
+};
+
+use itertools::Itertools;
+
+use crate::utils::prefixes::*;
+
+#[command]
+#[only_in(guilds)]
+#[sub_commands(prefix_set, prefix_add, prefix_remove, prefix_reset)]
+/// Shows the prefixes the bot responds to in this server.
+///
+/// Members with the Manage Server permission can change them through the
+/// `set`, `add`, `remove` and `reset` subcommands.
+async fn prefix(context: &Context, message: &Message) -> CommandResult {
+    let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
+    message.channel_id.say(context, list_prefixes(context, guild_id).await?).await?;
+
+    Ok(())
+}
+
+#[command("set")]
+#[only_in(guilds)]
+#[usage = "<prefix>"]
+#[example = "!"]
+#[num_args(1)]
+#[required_permissions(MANAGE_GUILD)]
+/// Replaces this server's own prefixes with the given prefix. The default
+/// prefix keeps working alongside it.
+async fn prefix_set(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
+    let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
+    let prefix = arguments.single::<String>()?;
+    message.channel_id.say(context, set_prefix(context, guild_id, &prefix).await?).await?;
+
+    Ok(())
+}
+
+#[command("add")]
+#[only_in(guilds)]
+#[usage = "<prefix> [prefix...]"]
+#[example = "! ?"]
+#[min_args(1)]
+#[required_permissions(MANAGE_GUILD)]
+/// Adds one or more prefixes to the ones this server already uses.
+async fn prefix_add(context: &Context, message: &Message, arguments: Args) -> CommandResult {
+    let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
+    let prefixes = arguments.raw().collect::<Vec<&str>>();
+    message.channel_id.say(context, add_prefixes(context, guild_id, &prefixes).await?).await?;
+
+    Ok(())
+}
+
+#[command("remove")]
+#[only_in(guilds)]
+#[usage = "<prefix>"]
+#[example = "?"]
+#[num_args(1)]
+#[required_permissions(MANAGE_GUILD)]
+/// Removes a prefix from this server.
+async fn prefix_remove(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
+    let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
+    let prefix = arguments.single::<String>()?;
+    message.channel_id.say(context, remove_prefix(context, guild_id, &prefix).await?).await?;
+
+    Ok(())
+}
+
+#[command("reset")]
+#[only_in(guilds)]
+#[required_permissions(MANAGE_GUILD)]
+/// Removes this server's own prefixes, leaving only the default prefix.
+async fn prefix_reset(context: &Context, message: &Message) -> CommandResult {
+    let guild_id = message.guild_id.ok_or("Failed to get GuildID from Message.")?;
+    message.channel_id.say(context, reset_prefixes(context, guild_id).await?).await?;
+
+    Ok(())
+}
+
+fn format_prefixes(prefixes: &[String]) -> String {
+    prefixes.iter().map(|prefix| format!("`{prefix}`")).join(", ")
+}
+
+/// Describes the prefixes the bot responds to in a guild with the given
+/// prefixes of its own.
+async fn describe_prefixes(context: &Context, custom: Vec<String>) -> String {
+    let prefixes = with_default(default_prefix(context).await, custom);
+    format!("The prefixes for this server are now: {}", format_prefixes(&prefixes))
+}
+
+/// Describes the prefixes the bot responds to in the given guild.
+pub async fn list_prefixes(context: &Context, guild_id: GuildId) -> CommandResult<String> {
+    let prefixes = get_guild_prefixes(context, guild_id).await?;
+    Ok(format!("The prefixes for this server are: {}", format_prefixes(&prefixes)))
+}
+
+/// Replaces the prefixes the given guild has set of its own with a single
+/// prefix. The default prefix keeps working alongside it.
+pub async fn set_prefix(context: &Context, guild_id: GuildId, prefix: &str) -> CommandResult<String> {
+    validate_prefix(prefix)?;
+    let default = default_prefix(context).await;
+    if prefix == default {
+        set_guild_prefixes(context, guild_id, Vec::new()).await?;
+        return Ok(format!("`{prefix}` is the default prefix, so this server now only uses it."));
+    }
+
+    set_guild_prefixes(context, guild_id, vec![prefix.to_string()]).await?;
+    Ok(format!("The prefix for this server has been set to `{prefix}`, alongside the default prefix `{default}`."))
+}
+
+/// Adds the given prefixes to the prefixes of the given guild. The default
+/// prefix always works, so it isn't stored with them.
+pub async fn add_prefixes(context: &Context, guild_id: GuildId, new_prefixes: &[&str]) -> CommandResult<String> {
+    let default = default_prefix(context).await;
+    let mut prefixes = get_custom_prefixes(context, guild_id).await?;
+    for prefix in new_prefixes {
+        validate_prefix(prefix)?;
+        if *prefix != default && !prefixes.iter().any(|p| p == prefix) {
+            prefixes.push(prefix.to_string());
+        }
+    }
+
+    if prefixes.len() > MAX_PREFIXES {
+        return Ok(format!("A server can have at most {MAX_PREFIXES} prefixes besides the default prefix."));
+    }
+
+    set_guild_prefixes(context, guild_id, prefixes.clone()).await?;
+
+    Ok(describe_prefixes(context, prefixes).await)
+}
+
+/// Removes the given prefix from the prefixes of the given guild. The default
+/// prefix can't be removed.
+pub async fn remove_prefix(context: &Context, guild_id: GuildId, prefix: &str) -> CommandResult<String> {
+    if prefix == default_prefix(context).await {
+        return Ok(format!("`{prefix}` is the default prefix, which works in every server."));
+    }
+
+    let mut prefixes = get_custom_prefixes(context, guild_id).await?;
+    if !prefixes.iter().any(|p| p == prefix) {
+        return Ok(format!("`{prefix}` is not a prefix in this server."));
+    }
+
+    prefixes.retain(|p| p != prefix);
+    set_guild_prefixes(context, guild_id, prefixes.clone()).await?;
+
+    Ok(describe_prefixes(context, prefixes).await)
+}
+
+/// Removes the prefixes the given guild has set of its own, leaving only the
+/// default prefix.
+pub async fn reset_prefixes(context: &Context, guild_id: GuildId) -> CommandResult<String> {
+    set_guild_prefixes(context, guild_id, Vec::new()).await?;
+    let prefix = default_prefix(context).await;
+    Ok(format!("The prefix for this server has been reset to `{prefix}`."))
+}
@@ -0,0 +1,159 @@
+//! Reminder commands
+//!
+//! Reminders are read in the time zone on the user's profile, which falls back
+//! to UTC. They are delivered by the scheduler in [`crate::utils::reminders`].
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use serenity::{
+    builder::{CreateEmbed, CreateEmbedFooter},
+    client::Context,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        id::{ChannelId, UserId},
+        prelude::Message
+    }
+};
+
+use crate::{
+    commands::response::Response,
+    models::reminder::Recurrence,
+    utils::{
+        reminders::{add_reminder, cancel_reminder, get_reminders},
+        time::{parse_time, user_timezone}
+    }
+};
+
+/// The most reminders a user can have pending at once.
+const MAX_REMINDERS: usize = 25;
+
+/// The longest message a reminder can have, in characters.
+const MESSAGE_LIMIT: usize = 1000;
+
+/// How much of a reminder's message is shown when listing reminders.
+const PREVIEW_LENGTH: usize = 100;
+
+const USAGE: &str = "Tell me when and what to remind you of, e.g. `remind me in 2h30m to check the oven` or `remind me every monday 9am to water the plants`.";
+
+#[command]
+#[usage = "me <when> to <message>"]
+#[example = "me in 2h30m to check the oven"]
+#[example = "me tomorrow 5pm to call home"]
+#[example = "me every monday 9am to water the plants"]
+#[sub_commands(remind_list, remind_cancel)]
+/// Sets a reminder, which is sent to the channel it was set in when it comes
+/// due. Times are read in the time zone on your profile.
+async fn remind(context: &Context, message: &Message, arguments: Args) -> CommandResult {
+    let input = arguments.rest();
+    let input = input.strip_prefix("me ").unwrap_or(input);
+    let response = match input.split_once(" to ") {
+        Some((when, text)) => remind_response(context, message.author.id, message.channel_id, when, text).await?,
+        None => USAGE.into()
+    };
+
+    response.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Sets a reminder for the given user in the given channel, which is a
+/// one-off if `when` is a point in time, or recurring if it is something like
+/// `every monday 9am`.
+pub async fn remind_response(context: &Context, user_id: UserId, channel_id: ChannelId, when: &str, text: &str) -> CommandResult<Response> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(USAGE.into());
+    }
+
+    if text.chars().count() > MESSAGE_LIMIT {
+        return Ok(format!("Reminders can't be longer than {MESSAGE_LIMIT} characters.").into());
+    }
+
+    if get_reminders(context, user_id).await?.len() >= MAX_REMINDERS {
+        return Ok(format!("You can't have more than {MAX_REMINDERS} reminders at once. Cancel one with `remind cancel <id>` first.").into());
+    }
+
+    let timezone = user_timezone(context, user_id).await?.unwrap_or(Tz::UTC);
+    let now = Utc::now().with_timezone(&timezone);
+    let recurrence = Recurrence::parse(when);
+    let due = match recurrence {
+        Some(recurrence) => recurrence.next_after(now),
+        None => parse_time(when, now)
+    };
+
+    let due = match due {
+        Some(due) if due > now => due.timestamp(),
+        Some(_) => return Ok("That time has already passed.".into()),
+        None => return Ok(format!("I couldn't understand `{}` as a time. {USAGE}", when.trim()).into())
+    };
+
+    let id = add_reminder(context, user_id, channel_id, text, due, recurrence).await?;
+    let response = match recurrence {
+        Some(recurrence) => format!("Reminder #{id} set for <t:{due}:F> (<t:{due}:R>), repeating {} in {}.", recurrence.describe(), timezone.name()),
+        None => format!("Reminder #{id} set for <t:{due}:F> (<t:{due}:R>).")
+    };
+
+    Ok(response.into())
+}
+
+#[command("list")]
+/// Lists your pending reminders.
+async fn remind_list(context: &Context, message: &Message) -> CommandResult {
+    list_reminders_response(context, message.author.id).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Lists the pending reminders of the given user, soonest first.
+pub async fn list_reminders_response(context: &Context, user_id: UserId) -> CommandResult<Response> {
+    let reminders = get_reminders(context, user_id).await?;
+    if reminders.is_empty() {
+        return Ok("You don't have any reminders.".into());
+    }
+
+    let lines = reminders.iter().map(|reminder| {
+        let mut preview: String = reminder.message.chars().take(PREVIEW_LENGTH).collect();
+        if reminder.message.chars().count() > PREVIEW_LENGTH {
+            preview.push('…');
+        }
+
+        let repeats = reminder.recurrence().map(|recurrence| format!(" (repeats {})", recurrence.describe())).unwrap_or_default();
+        format!("**#{}** <t:{}:R>{repeats}: {preview}", reminder.id, reminder.due_at)
+    });
+
+    let embed = CreateEmbed::new()
+        .title("Your Reminders")
+        .description(lines.collect::<Vec<_>>().join("\n"))
+        .footer(CreateEmbedFooter::new("Use remind cancel <id> to cancel a reminder."));
+
+    Ok(Response::embed(embed))
+}
+
+#[command("cancel")]
+#[usage = "<id>"]
+#[example = "12"]
+#[min_args(1)]
+/// Cancels one of your reminders.
+async fn remind_cancel(context: &Context, message: &Message, mut arguments: Args) -> CommandResult {
+    let id = arguments.single::<String>()?;
+    let id = id.trim_start_matches('#');
+    let response = match id.parse::<i64>() {
+        Ok(id) => cancel_reminder_response(context, message.author.id, id).await?,
+        Err(_) => "That is not a valid reminder ID.".into()
+    };
+
+    response.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Cancels one of the given user's reminders.
+pub async fn cancel_reminder_response(context: &Context, user_id: UserId, id: i64) -> CommandResult<Response> {
+    let response = if cancel_reminder(context, user_id, id).await? {
+        format!("Cancelled reminder #{id}.")
+    } else {
+        format!("You don't have a reminder #{id}.")
+    };
+
+    Ok(response.into())
+}
@@ -0,0 +1,127 @@
+//! Time commands
+//!
+//! Shows the local time of members from the time zone on their profile, and
+//! turns times written in plain language into Discord timestamps, which show
+//! in the time zone of whoever reads them.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serenity::{
+    builder::{CreateEmbed, CreateEmbedFooter},
+    client::Context,
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{prelude::Message, user::User}
+};
+
+use crate::{
+    commands::response::Response,
+    models::profile::ProfileField,
+    utils::{
+        parsing::parse_user,
+        profiles::{get_visible_profile_field, Viewer},
+        time::{parse_time, user_timezone}
+    }
+};
+
+/// The styles of Discord timestamps, along with what they look like.
+const TIMESTAMP_STYLES: [(&str, &str); 7] = [
+    ("t", "Short Time"),
+    ("T", "Long Time"),
+    ("d", "Short Date"),
+    ("D", "Long Date"),
+    ("f", "Short Date / Time"),
+    ("F", "Long Date / Time"),
+    ("R", "Relative")
+];
+
+const TIMEZONE_HINT: &str = "Set your time zone with `profile set timezone <zone>`, e.g. `Europe/London`.";
+
+#[command]
+#[usage = "[user]"]
+#[example = "@someone"]
+/// Shows the local time of a member, from the time zone on their profile.
+async fn time(context: &Context, message: &Message, arguments: Args) -> CommandResult {
+    let user = match (message.mentions.first(), message.guild_id) {
+        (Some(user), _) => user.clone(),
+        (None, Some(guild_id)) if !arguments.is_empty() => match parse_user(arguments.rest(), guild_id, context).await {
+            Some(user_id) => user_id.to_user(context).await?,
+            None => {
+                message.channel_id.say(context, "Could not find that member.").await?;
+                return Ok(());
+            }
+        },
+        (None, _) => message.author.clone()
+    };
+
+    let viewer = Viewer::new(message.author.id, message.guild_id);
+    time_response(context, viewer, &user).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Shows the local time of the given user, if the viewer can see their time
+/// zone.
+pub async fn time_response(context: &Context, viewer: Viewer, user: &User) -> CommandResult<Response> {
+    let timezone = get_visible_profile_field(context, user.id, ProfileField::Timezone, &viewer).await?;
+    let timezone = match timezone.and_then(|timezone| timezone.parse::<Tz>().ok()) {
+        Some(timezone) => timezone,
+        None if user.id == viewer.user_id => return Ok(format!("You haven't set a time zone. {TIMEZONE_HINT}").into()),
+        None => return Ok(format!("{} hasn't shared a time zone.", user.name).into())
+    };
+
+    let now = Utc::now().with_timezone(&timezone);
+    let embed = CreateEmbed::new()
+        .title(format!("Local time for {}", user.name))
+        .description(format!("**{}** ({})\n{}", now.format("%H:%M"), now.format("%-I:%M %p"), now.format("%A, %B %-d, %Y")))
+        .field("Time Zone", format!("{} (UTC{})", timezone.name(), now.format("%:z")), false);
+
+    Ok(Response::embed(embed))
+}
+
+#[command]
+#[usage = "<time>"]
+#[example = "tomorrow 5pm"]
+#[example = "in 3 hours"]
+#[example = "friday at 9:30"]
+/// Converts a time into Discord timestamps, which show in each reader's own
+/// time zone. Times are read in the time zone on your profile.
+async fn timestamp(context: &Context, message: &Message, arguments: Args) -> CommandResult {
+    timestamp_response(context, &message.author, arguments.rest()).await?.send(context, message).await?;
+
+    Ok(())
+}
+
+/// Lists every Discord timestamp for the given time, read in the time zone of
+/// the given user.
+pub async fn timestamp_response(context: &Context, user: &User, input: &str) -> CommandResult<Response> {
+    if input.trim().is_empty() {
+        return Ok("Please provide a time, e.g. `tomorrow 5pm` or `in 3 hours`.".into());
+    }
+
+    let timezone = user_timezone(context, user.id).await?;
+    let now = Utc::now().with_timezone(&timezone.unwrap_or(Tz::UTC));
+    let time = match parse_time(input, now) {
+        Some(time) => time,
+        None => return Ok("I couldn't understand that time. Try something like `tomorrow 5pm`, `in 3 hours` or `2026-12-31 23:59`.".into())
+    };
+
+    let footer = match timezone {
+        Some(timezone) => format!("Read in {}.", timezone.name()),
+        None => format!("Read in UTC. {TIMEZONE_HINT}")
+    };
+
+    let embed = CreateEmbed::new().title("Timestamps").description(timestamp_lines(time)).footer(CreateEmbedFooter::new(footer));
+
+    Ok(Response::embed(embed))
+}
+
+/// Shows each style of timestamp for the given time, next to the text that
+/// produces it.
+fn timestamp_lines(time: DateTime<Tz>) -> String {
+    let seconds = time.timestamp();
+    TIMESTAMP_STYLES
+        .iter()
+        .map(|(style, name)| format!("**{name}**: <t:{seconds}:{style}> — `<t:{seconds}:{style}>`"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,115 +1,376 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct ConfigurationData {
     pub bot: BotConfig,
+    #[serde(default)]
     pub api: ApiConfig
 }
 
-#[derive(Deserialize)]
+/// The keys that must be present in every configuration.
+pub const REQUIRED_KEYS: &[&str] = &[
+    "bot.general.codename",
+    "bot.general.prefix",
+    "bot.database.url",
+    "bot.discord.appid",
+    "bot.discord.token",
+    "bot.denylist.spotify.ids",
+    "bot.logging.enabled",
+    "bot.logging.level"
+];
+
+impl ConfigurationData {
+    /// Checks the configuration for values that would prevent the bot from
+    /// working, returning a description of every problem found.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let general = &self.bot.general;
+        if general.prefix.is_empty() || general.prefix.chars().any(char::is_whitespace) {
+            problems.push("bot.general.prefix must not be empty or contain whitespace.".to_string());
+        }
+
+        if self.bot.discord.token.is_empty() {
+            problems.push("bot.discord.token must not be empty.".to_string());
+        }
+
+        if self.bot.database.url.is_empty() {
+            problems.push("bot.database.url must not be empty.".to_string());
+        }
+
+        if !["error", "warn", "info", "debug", "trace"].contains(&self.bot.logging.level.as_str()) {
+            problems.push(format!("bot.logging.level must be one of error, warn, info, debug or trace, not {}.", self.bot.logging.level));
+        }
+
+        problems
+    }
+
+    /// Checks the configuration for values that would prevent the bot from
+    /// working, returning a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.problems().into_iter().next() {
+            Some(problem) => Err(problem),
+            None => Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
 pub struct BotConfig {
     pub general: GeneralConfig,
     pub database: DatabaseConfig,
     pub discord: DiscordConfig,
     pub denylist: DenylistConfig,
-    pub logging: LoggingConfig
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub cache: CacheConfig
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct GeneralConfig {
     pub codename: String,
     pub prefix: String
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct LoggingConfig {
     pub enabled: bool,
     pub level: String
 }
 
-#[derive(Deserialize)]
+/// Settings for requests to external APIs. Changes only take effect after a
+/// restart.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Seconds to wait for a response before giving up on an attempt.
+    pub timeout: u64,
+    /// How often a request is retried after a timeout, a rate limit or a
+    /// server error.
+    pub retries: u32,
+    /// Delay in milliseconds before the first retry, doubled with each one.
+    pub backoff: u64,
+    /// Longest delay in seconds to wait before a retry. Rate limits asking for
+    /// a longer wait fail right away.
+    pub max_backoff: u64,
+    /// How many requests may be in flight to a single service at once.
+    pub concurrency: usize
+}
+
+impl Default for HttpConfig {
+    fn default() -> HttpConfig {
+        HttpConfig {
+            timeout: 10,
+            retries: 2,
+            backoff: 500,
+            max_backoff: 30,
+            concurrency: 4
+        }
+    }
+}
+
+/// Settings for the cache of external API lookups. Changes only take effect
+/// after a restart.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    /// How many entries are kept in memory.
+    pub capacity: usize,
+    /// Whether entries are also stored in the database.
+    pub persist: bool,
+    /// Time to live in seconds, keyed by endpoint, overriding the defaults.
+    pub ttl: HashMap<String, u64>
+}
+
+impl Default for CacheConfig {
+    fn default() -> CacheConfig {
+        CacheConfig {
+            enabled: true,
+            capacity: 1000,
+            persist: false,
+            ttl: HashMap::new()
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
 pub struct DatabaseConfig {
-    pub url: String
+    pub url: String,
+    /// Whether pending migrations are applied on startup.
+    #[serde(default)]
+    pub migrate: bool
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct DiscordConfig {
     pub appid: u64,
     pub token: String
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct DenylistConfig {
     pub spotify: DenylistSpotifyConfig
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct DenylistSpotifyConfig {
     pub ids: Vec<u64>
 }
 
-#[derive(Deserialize)]
+/// Configuration for the third-party APIs used by commands.
+///
+/// Every integration is optional. Commands depending on an integration that
+/// isn't configured are disabled; see [`Integration`].
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct ApiConfig {
     pub entertainment: EntertainmentConfig,
     pub minecraft: MinecraftConfig,
     pub music: MusicConfig,
     pub services: ServicesConfig,
-    pub social: SocialConfig
+    pub social: SocialConfig,
+    pub urls: UrlsConfig
+}
+
+impl ApiConfig {
+    /// Returns the TMDb API key, if one is set.
+    #[cfg(feature = "tmdb")]
+    pub fn tmdb(&self) -> Option<&str> {
+        self.entertainment.tmdb.as_deref().filter(|key| !key.is_empty())
+    }
+
+    /// Returns the Spotify client credentials, if they are set.
+    #[cfg(feature = "music-spotify")]
+    pub fn spotify(&self) -> Option<&SpotifyConfig> {
+        self.music.spotify.as_ref().filter(|spotify| !spotify.client_id.is_empty() && !spotify.client_secret.is_empty())
+    }
+
+    /// Returns the Last.fm API key, if one is set.
+    #[cfg(feature = "music-lastfm")]
+    pub fn lastfm(&self) -> Option<&str> {
+        self.music.lastfm.as_ref().map(|lastfm| lastfm.api_key.as_str()).filter(|key| !key.is_empty())
+    }
+
+    /// Returns the Twitter bearer token, if one is set.
+    #[cfg(feature = "twitter")]
+    pub fn twitter(&self) -> Option<&str> {
+        self.social.twitter.as_ref().map(|twitter| twitter.core.bearer_token.as_str()).filter(|token| !token.is_empty())
+    }
+}
+
+/// A third-party API that one or more commands depend on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integration {
+    #[cfg(feature = "music-lastfm")]
+    LastFm,
+    #[cfg(feature = "music-spotify")]
+    Spotify,
+    #[cfg(feature = "tmdb")]
+    Tmdb,
+    #[cfg(feature = "twitter")]
+    Twitter
+}
+
+impl Integration {
+    /// Every integration compiled into this build.
+    pub const ALL: &'static [Integration] = &[
+        #[cfg(feature = "music-lastfm")]
+        Integration::LastFm,
+        #[cfg(feature = "music-spotify")]
+        Integration::Spotify,
+        #[cfg(feature = "tmdb")]
+        Integration::Tmdb,
+        #[cfg(feature = "twitter")]
+        Integration::Twitter
+    ];
+
+    /// The name of the service, as shown to users.
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "music-lastfm")]
+            Integration::LastFm => "Last.fm",
+            #[cfg(feature = "music-spotify")]
+            Integration::Spotify => "Spotify",
+            #[cfg(feature = "tmdb")]
+            Integration::Tmdb => "TMDb",
+            #[cfg(feature = "twitter")]
+            Integration::Twitter => "Twitter"
+        }
+    }
+
+    /// The top-level commands that depend on this integration.
+    pub fn commands(self) -> &'static [&'static str] {
+        match self {
+            #[cfg(feature = "music-lastfm")]
+            Integration::LastFm => &["lastfm"],
+            #[cfg(feature = "music-spotify")]
+            Integration::Spotify => &["spotify"],
+            #[cfg(feature = "tmdb")]
+            Integration::Tmdb => &["tmdb"],
+            #[cfg(feature = "twitter")]
+            Integration::Twitter => &["twitter"]
+        }
+    }
+
+    /// Returns the integration the given top-level command depends on.
+    pub fn for_command(command: &str) -> Option<Integration> {
+        Integration::ALL.iter().copied().find(|integration| integration.commands().contains(&command))
+    }
+
+    pub fn is_configured(self, api: &ApiConfig) -> bool {
+        match self {
+            #[cfg(feature = "music-lastfm")]
+            Integration::LastFm => api.lastfm().is_some(),
+            #[cfg(feature = "music-spotify")]
+            Integration::Spotify => api.spotify().is_some(),
+            #[cfg(feature = "tmdb")]
+            Integration::Tmdb => api.tmdb().is_some(),
+            #[cfg(feature = "twitter")]
+            Integration::Twitter => api.twitter().is_some()
+        }
+    }
+
+    /// The message shown when a command of this integration is used while it
+    /// isn't configured.
+    pub fn unavailable_message(self) -> String {
+        format!("{} is not configured for this bot, so this command is unavailable.", self.name())
+    }
+}
+
+/// The base URLs of the APIs requested over HTTP, without a trailing slash.
+/// These only need changing to point the bot at a mirror or a local stub.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct UrlsConfig {
+    pub tmdb: String,
+    pub tokei: String,
+    pub twitter: String,
+    pub urban_dictionary: String,
+    pub xkcd: String
+}
+
+impl Default for UrlsConfig {
+    fn default() -> UrlsConfig {
+        UrlsConfig {
+            tmdb: "https://api.themoviedb.org/3".to_string(),
+            tokei: "https://tokei.vercel.app".to_string(),
+            twitter: "https://api.twitter.com/2".to_string(),
+            urban_dictionary: "https://api.urbandictionary.com/v0".to_string(),
+            xkcd: "https://xkcd.com".to_string()
+        }
+    }
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct EntertainmentConfig {
-    pub tmdb: String
+    #[cfg(feature = "tmdb")]
+    pub tmdb: Option<String>
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct MinecraftConfig {
-    pub hypixel: String
+    pub hypixel: Option<String>
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct MusicConfig {
-    pub spotify: SpotifyConfig,
-    pub lastfm: LastFmConfig,
-    pub lavalink: LavalinkConfig
+    #[cfg(feature = "music-spotify")]
+    pub spotify: Option<SpotifyConfig>,
+    #[cfg(feature = "music-lastfm")]
+    pub lastfm: Option<LastFmConfig>,
+    pub lavalink: Option<LavalinkConfig>
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct ServicesConfig {
-    pub github: String,
-    pub google: String
+    pub github: Option<String>,
+    pub google: Option<String>
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct SocialConfig {
-    pub twitter: TwitterConfig
+    #[cfg(feature = "twitter")]
+    pub twitter: Option<TwitterConfig>
 }
 
-#[derive(Deserialize)]
+#[cfg(feature = "music-spotify")]
+#[derive(Clone, Deserialize)]
 pub struct SpotifyConfig {
     pub client_id: String,
     pub client_secret: String
 }
 
-#[derive(Deserialize)]
+#[cfg(feature = "music-lastfm")]
+#[derive(Clone, Deserialize)]
 pub struct LastFmConfig {
     pub api_key: String
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct LavalinkConfig {
     pub host: String,
     pub port: u16,
     pub password: String
 }
 
-#[derive(Deserialize)]
+#[cfg(feature = "twitter")]
+#[derive(Clone, Deserialize)]
 pub struct TwitterConfig {
     pub core: TwitterCore,
     pub client: TwitterClient
 }
 
-#[derive(Deserialize)]
+#[cfg(feature = "twitter")]
+#[derive(Clone, Deserialize)]
 pub struct TwitterCore {
     pub api_key: String,
     pub api_key_secret: String,
@@ -118,7 +379,8 @@ pub struct TwitterCore {
     pub access_token_secret: String
 }
 
-#[derive(Deserialize)]
+#[cfg(feature = "twitter")]
+#[derive(Clone, Deserialize)]
 pub struct TwitterClient {
     pub client_id: String,
     pub client_secret: String
@@ -1,2 +1,5 @@
+/// The configuration file read on startup and watched for changes.
+pub const CONFIG_FILE: &str = "config.toml";
+
 /// The user agent used for the reqwest client.
 pub const REQWEST_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
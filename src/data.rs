@@ -1,15 +1,23 @@
-use crate::config::ConfigurationData;
+use crate::{
+    config::ConfigurationData,
+    services::Services,
+    utils::{cache::ResponseCache, http::HttpClient}
+};
+#[cfg(feature = "music-spotify")]
 use aspotify::Client as SpotifyClient;
-use reqwest::Client as ReqwestClient;
-use serenity::{client::bridge::gateway::ShardManager, prelude::TypeMapKey};
+use serenity::{client::bridge::gateway::ShardManager, model::id::GuildId, prelude::TypeMapKey};
 use sqlx::PgPool;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{Mutex, RwLock};
 
 pub struct ShardManagerContainer;
 pub struct ConfigContainer;
 pub struct DatabasePool;
+pub struct PrefixCache;
 pub struct ReqwestContainer;
+pub struct ResponseCacheContainer;
+pub struct ServicesContainer;
+#[cfg(feature = "music-spotify")]
 pub struct SpotifyContainer;
 
 impl TypeMapKey for ShardManagerContainer {
@@ -17,17 +25,30 @@ impl TypeMapKey for ShardManagerContainer {
 }
 
 impl TypeMapKey for ConfigContainer {
-    type Value = ConfigurationData;
+    type Value = Arc<RwLock<ConfigurationData>>;
 }
 
 impl TypeMapKey for DatabasePool {
     type Value = PgPool;
 }
 
+impl TypeMapKey for PrefixCache {
+    type Value = Arc<RwLock<HashMap<GuildId, Vec<String>>>>;
+}
+
 impl TypeMapKey for ReqwestContainer {
-    type Value = ReqwestClient;
+    type Value = HttpClient;
+}
+
+impl TypeMapKey for ResponseCacheContainer {
+    type Value = Arc<ResponseCache>;
+}
+
+impl TypeMapKey for ServicesContainer {
+    type Value = Services;
 }
 
+#[cfg(feature = "music-spotify")]
 impl TypeMapKey for SpotifyContainer {
-    type Value = SpotifyClient;
+    type Value = Arc<SpotifyClient>;
 }
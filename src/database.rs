@@ -0,0 +1,135 @@
+//! Database
+//!
+//! The migrations in the `migrations` directory are embedded into the binary
+//! at compile time, so a database can be set up without having to install
+//! sqlx-cli. They are applied either on startup, if enabled in the
+//! configuration, or through the `migrate` subcommand.
+//!
+//! The first three migrations were changed after they were released, so that
+//! they run on an empty database. Databases that applied an earlier version
+//! of them have the checksum of that version recorded, which sqlx treats as
+//! a modified migration. Before migrating, such checksums are replaced with
+//! the ones of the current versions. Only checksums of known earlier versions
+//! are replaced, so migrations that were actually changed are still caught.
+
+use sqlx::{
+    migrate::{Migrate, Migrator},
+    postgres::{PgPool, PgPoolOptions}
+};
+use tracing::info;
+
+use crate::{config::DatabaseConfig, error::TaliyahError};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// The SHA-384 checksums of earlier versions of migrations, by version.
+const LEGACY_CHECKSUMS: &[(i64, &[&str])] = &[
+    (
+        20201121051913,
+        &["b1b68dd8eb07d4e5bacb48c9b4aedd6d95bf1ed29f1ab13ee860dcaa98f43b582153503c7b411dd70ba63ce0571e6c2f"]
+    ),
+    (
+        20201205023818,
+        &["856567ec8f309464cce1838c12261734a082517227d83b7f12eb7b02d4b645c480f7ff6ddd4023d25d22eb7b7ee77689"]
+    ),
+    (
+        20201205032238,
+        &["56894f1819f9c712e1228c492091c083804d51e98ac823b241f832e052178d5c41d3449c5a655339857372f39328e37c"]
+    )
+];
+
+/// The state of an embedded migration in the database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied from an earlier version of the migration, whose checksum is
+    /// replaced on the next migration run.
+    Outdated,
+    /// Applied, but the migration has been changed since.
+    Modified
+}
+
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState
+}
+
+/// Connects to the database described by the given configuration.
+pub async fn connect(config: &DatabaseConfig) -> Result<PgPool, TaliyahError> {
+    Ok(PgPoolOptions::new().max_connections(20).connect(&config.url).await?)
+}
+
+/// Applies every pending migration, after replacing the checksums of
+/// earlier versions of migrations.
+pub async fn run_migrations(pool: &PgPool) -> Result<(), TaliyahError> {
+    repair_checksums(pool).await?;
+    MIGRATOR.run(pool).await.map_err(|err| format!("Unable to apply migrations: {err}"))?;
+    Ok(())
+}
+
+/// Replaces the recorded checksums of migrations that were applied from an
+/// earlier version with the checksums of their current versions.
+async fn repair_checksums(pool: &PgPool) -> Result<(), TaliyahError> {
+    let mut connection = pool.acquire().await?;
+    connection
+        .ensure_migrations_table()
+        .await
+        .map_err(|err| format!("Unable to create the migrations table: {err}"))?;
+
+    for migration in MIGRATOR.iter() {
+        for checksum in legacy_checksums(migration.version) {
+            let result = sqlx::query("UPDATE _sqlx_migrations SET checksum = $1 WHERE version = $2 AND checksum = decode($3, 'hex')")
+                .bind(&*migration.checksum)
+                .bind(migration.version)
+                .bind(checksum)
+                .execute(&mut *connection)
+                .await?;
+
+            if result.rows_affected() > 0 {
+                info!("Updated the checksum of migration {} from an earlier version.", migration.version);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn legacy_checksums(version: i64) -> &'static [&'static str] {
+    LEGACY_CHECKSUMS.iter().find(|(legacy, _)| *legacy == version).map_or(&[], |(_, checksums)| checksums)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Compares the embedded migrations against the ones applied to the database.
+pub async fn migration_status(pool: &PgPool) -> Result<Vec<MigrationStatus>, TaliyahError> {
+    let mut connection = pool.acquire().await?;
+    connection
+        .ensure_migrations_table()
+        .await
+        .map_err(|err| format!("Unable to create the migrations table: {err}"))?;
+    let applied = connection.list_applied_migrations().await.map_err(|err| format!("Unable to list applied migrations: {err}"))?;
+
+    let status = MIGRATOR
+        .iter()
+        .map(|migration| {
+            let state = match applied.iter().find(|applied| applied.version == migration.version) {
+                Some(applied) if applied.checksum == migration.checksum => MigrationState::Applied,
+                Some(applied) if legacy_checksums(migration.version).contains(&hex(&applied.checksum).as_str()) => MigrationState::Outdated,
+                Some(_) => MigrationState::Modified,
+                None => MigrationState::Pending
+            };
+
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state
+            }
+        })
+        .collect();
+
+    Ok(status)
+}
@@ -1,30 +1,86 @@
 use std::{
     error::Error,
     fmt::{Display, Formatter, Result},
-    num::ParseIntError
+    num::ParseIntError,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{SystemTime, UNIX_EPOCH}
 };
 
 use tracing::error;
 
+#[cfg(any(feature = "music-lastfm", feature = "music-spotify", feature = "tmdb", feature = "twitter"))]
+use crate::config::Integration;
+
+/// An error raised while running a command or loading the bot.
+///
+/// Its [`Display`] implementation describes the error in full for operators.
+/// Use [`TaliyahError::user_message`] for anything shown in Discord, as the
+/// underlying errors may contain request URLs, API keys or query details.
 #[derive(Debug)]
 pub enum TaliyahError {
     Database(sqlx::Error),
+    Http(reqwest::Error),
+    #[cfg(feature = "music-lastfm")]
+    LastFm(lastfm_rs::error::Error),
+    #[cfg(feature = "music-spotify")]
+    Spotify(aspotify::Error),
+    #[cfg(feature = "tmdb")]
+    Tmdb(reqwest::Error),
+    #[cfg(feature = "twitter")]
+    Twitter(reqwest::Error),
     Parsing(ParseIntError),
     Serenity(serenity::Error),
     Custom(String)
 }
 
+const DATABASE_MESSAGE: &str = "Something went wrong while accessing the database.";
+const DISCORD_MESSAGE: &str = "Something went wrong while talking to Discord.";
+const HTTP_MESSAGE: &str = "Something went wrong while contacting an external service.";
+const PARSING_MESSAGE: &str = "That is not a valid number.";
+
+impl TaliyahError {
+    /// A description of the error that is safe to show to users.
+    pub fn user_message(&self) -> String {
+        match self {
+            TaliyahError::Database(_) => DATABASE_MESSAGE.to_string(),
+            TaliyahError::Http(_) => HTTP_MESSAGE.to_string(),
+            #[cfg(feature = "music-lastfm")]
+            TaliyahError::LastFm(_) => integration_message(Integration::LastFm),
+            #[cfg(feature = "music-spotify")]
+            TaliyahError::Spotify(_) => integration_message(Integration::Spotify),
+            #[cfg(feature = "tmdb")]
+            TaliyahError::Tmdb(_) => integration_message(Integration::Tmdb),
+            #[cfg(feature = "twitter")]
+            TaliyahError::Twitter(_) => integration_message(Integration::Twitter),
+            TaliyahError::Parsing(_) => PARSING_MESSAGE.to_string(),
+            TaliyahError::Serenity(_) => DISCORD_MESSAGE.to_string(),
+            TaliyahError::Custom(message) => message.clone()
+        }
+    }
+}
+
+#[cfg(any(feature = "music-lastfm", feature = "music-spotify", feature = "tmdb", feature = "twitter"))]
+fn integration_message(integration: Integration) -> String {
+    format!("{} could not be reached or returned an error.", integration.name())
+}
+
 impl Display for TaliyahError {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        let error = match self {
-            TaliyahError::Database(e) => e.to_string(),
-            TaliyahError::Parsing(e) => e.to_string(),
-            TaliyahError::Serenity(e) => e.to_string(),
-            TaliyahError::Custom(e) => e.to_string()
-        };
-        f.write_str(&error)?;
-        error!("Encountered an error: {}", &error);
-        Ok(())
+        match self {
+            TaliyahError::Database(e) => write!(f, "Database error: {e}"),
+            TaliyahError::Http(e) => write!(f, "HTTP error: {e}"),
+            #[cfg(feature = "music-lastfm")]
+            TaliyahError::LastFm(e) => write!(f, "Last.fm error: {e:?}"),
+            #[cfg(feature = "music-spotify")]
+            TaliyahError::Spotify(e) => write!(f, "Spotify error: {e}"),
+            #[cfg(feature = "tmdb")]
+            TaliyahError::Tmdb(e) => write!(f, "TMDb error: {e}"),
+            #[cfg(feature = "twitter")]
+            TaliyahError::Twitter(e) => write!(f, "Twitter error: {e}"),
+            TaliyahError::Parsing(e) => write!(f, "Parsing error: {e}"),
+            TaliyahError::Serenity(e) => write!(f, "Serenity error: {e}"),
+            TaliyahError::Custom(e) => f.write_str(e)
+        }
     }
 }
 
@@ -36,6 +92,26 @@ impl From<sqlx::Error> for TaliyahError {
     }
 }
 
+impl From<reqwest::Error> for TaliyahError {
+    fn from(err: reqwest::Error) -> TaliyahError {
+        TaliyahError::Http(err)
+    }
+}
+
+#[cfg(feature = "music-lastfm")]
+impl From<lastfm_rs::error::Error> for TaliyahError {
+    fn from(err: lastfm_rs::error::Error) -> TaliyahError {
+        TaliyahError::LastFm(err)
+    }
+}
+
+#[cfg(feature = "music-spotify")]
+impl From<aspotify::Error> for TaliyahError {
+    fn from(err: aspotify::Error) -> TaliyahError {
+        TaliyahError::Spotify(err)
+    }
+}
+
 impl From<String> for TaliyahError {
     fn from(err: String) -> TaliyahError {
         TaliyahError::Custom(err)
@@ -53,3 +129,61 @@ impl From<serenity::Error> for TaliyahError {
         TaliyahError::Serenity(err)
     }
 }
+
+/// An identifier shown to users alongside an error and written to the log
+/// with its details, so bug reports can be matched to their traces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CorrelationId(u32);
+
+impl CorrelationId {
+    pub fn generate() -> CorrelationId {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.subsec_nanos() ^ time.as_secs() as u32)
+            .unwrap_or_default();
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        CorrelationId(nanos ^ count.wrapping_mul(0x9E37_79B9))
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{:08X}", self.0)
+    }
+}
+
+/// Logs the error of a failed command under a new correlation ID, returning
+/// the message to show the user.
+///
+/// Errors from the database, Discord and external APIs are replaced by a
+/// generic description. Any other error is one of the messages the commands
+/// return themselves, and is shown as-is.
+pub fn report(command: &str, error: &(dyn Error + 'static)) -> String {
+    let id = CorrelationId::generate();
+    error!(correlation_id = %id, "Error while running command {command}: {error}");
+    format!("{} (Error ID: `{id}`)", user_message(error))
+}
+
+fn user_message(error: &(dyn Error + 'static)) -> String {
+    if let Some(error) = error.downcast_ref::<TaliyahError>() {
+        return error.user_message();
+    }
+
+    #[cfg(feature = "music-spotify")]
+    if error.is::<aspotify::Error>() {
+        return integration_message(Integration::Spotify);
+    }
+
+    if error.is::<sqlx::Error>() {
+        DATABASE_MESSAGE.to_string()
+    } else if error.is::<reqwest::Error>() || error.is::<serde_json::Error>() {
+        HTTP_MESSAGE.to_string()
+    } else if error.is::<serenity::Error>() {
+        DISCORD_MESSAGE.to_string()
+    } else if error.is::<ParseIntError>() {
+        PARSING_MESSAGE.to_string()
+    } else {
+        error.to_string()
+    }
+}
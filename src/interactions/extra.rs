@@ -0,0 +1,23 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::{CommandInteraction, CommandOptionType}
+};
+
+use super::string_option;
+use crate::commands::{extra::sloc::get_statistics, response::Response};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![CreateCommand::new("sloc")
+        .description("Fetches the source lines of code for a GitHub repository.")
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "owner", "The owner of the repository.").required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "repository", "The name of the repository.").required(true))]
+}
+
+pub async fn sloc(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let owner = string_option(&options, "owner").ok_or("No repository owner provided.")?;
+    let name = string_option(&options, "repository").ok_or("No repository name provided.")?;
+    Ok(get_statistics(context, owner, name).await?.into())
+}
@@ -0,0 +1,40 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::{CommandInteraction, CommandOptionType}
+};
+
+use super::{integer_option, string_option};
+use crate::commands::{
+    fun::{urban::*, xkcd::*},
+    response::Response
+};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("urban")
+            .description("Looks up a definition from the Urban Dictionary.")
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "term", "The word to look up.").required(true)),
+        CreateCommand::new("randefine").description("Gets a random definition from the Urban Dictionary."),
+        CreateCommand::new("xkcd")
+            .description("Retrieves the latest or a given comic from xkcd.")
+            .add_option(CreateCommandOption::new(CommandOptionType::Integer, "number", "The number of the comic.").min_int_value(1)),
+    ]
+}
+
+pub async fn urban(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let term = string_option(&options, "term").ok_or("You did not provide a word to look up. Please provide one.")?;
+    urban_response(context, term).await
+}
+
+pub async fn randefine(context: &Context, _command: &CommandInteraction) -> CommandResult<Response> {
+    randefine_response(context).await
+}
+
+pub async fn xkcd(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let comic_num = integer_option(&options, "number").map_or(Ok(0), u16::try_from)?;
+    xkcd_response(context, comic_num).await
+}
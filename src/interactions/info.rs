@@ -0,0 +1,149 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::{CommandInteraction, CommandOptionType}
+};
+
+use super::{string_option, subcommand, user_option};
+use crate::{
+    commands::{
+        info::{about::*, guild::*, profile::*, user::*},
+        response::Response
+    },
+    utils::profiles::Viewer
+};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("about").description("Shows information about the bot."),
+        CreateCommand::new("guild").description("Shows various information about the current guild.").dm_permission(false),
+        CreateCommand::new("profile")
+            .description("Shows or modifies user profiles.")
+            .dm_permission(false)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "view", "Shows the profile of a given user.").add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "The user to show the profile of."
+                ))
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Sets one of your profile parameters.")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "property", "The profile parameter to set.")
+                            .required(true)
+                            .add_string_choice("Name", "name")
+                            .add_string_choice("Location", "location")
+                            .add_string_choice("Gender", "gender")
+                            .add_string_choice("Pronouns", "pronouns")
+                            .add_string_choice("Last.fm", "lastfm")
+                            .add_string_choice("Bio", "bio")
+                            .add_string_choice("Time Zone", "timezone")
+                            .add_string_choice("Birthday", "birthday")
+                            .add_string_choice("Linked Account", "account")
+                    )
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "value", "The new value of the parameter.").required(true))
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "service", "The service of a linked account, e.g. steam."))
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "unset", "Clears one of your profile parameters.")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "property", "The profile parameter to clear.")
+                            .required(true)
+                            .add_string_choice("Name", "name")
+                            .add_string_choice("Location", "location")
+                            .add_string_choice("Gender", "gender")
+                            .add_string_choice("Pronouns", "pronouns")
+                            .add_string_choice("Last.fm", "lastfm")
+                            .add_string_choice("Bio", "bio")
+                            .add_string_choice("Time Zone", "timezone")
+                            .add_string_choice("Birthday", "birthday")
+                            .add_string_choice("Linked Account", "account")
+                    )
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "service", "The service of the linked account to unlink."))
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "privacy", "Shows or changes who can see each part of your profile.")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "property", "The part of your profile to change.")
+                            .add_string_choice("Name", "name")
+                            .add_string_choice("Location", "location")
+                            .add_string_choice("Gender", "gender")
+                            .add_string_choice("Pronouns", "pronouns")
+                            .add_string_choice("Last.fm", "lastfm")
+                            .add_string_choice("Bio", "bio")
+                            .add_string_choice("Time Zone", "timezone")
+                            .add_string_choice("Birthday", "birthday")
+                            .add_string_choice("Linked Accounts", "accounts")
+                    )
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "visibility", "Who can see it.")
+                            .add_string_choice("Everyone", "public")
+                            .add_string_choice("Members of servers you're in", "guild")
+                            .add_string_choice("Only you", "private")
+                    )
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "export",
+                "Sends you a file with all of the data stored about you."
+            ))
+            .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "delete", "Deletes all of the data stored about you.")),
+        CreateCommand::new("user")
+            .description("Shows various information about a user.")
+            .dm_permission(false)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "The user to show information about.")),
+    ]
+}
+
+pub async fn about(context: &Context, _command: &CommandInteraction) -> CommandResult<Response> {
+    about_response(context).await
+}
+
+pub async fn guild(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    guild_response(context, guild_id).await
+}
+
+pub async fn profile(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    match subcommand(&options)? {
+        ("set", options) => {
+            let property = string_option(options, "property").ok_or("You did not provide a profile property.")?;
+            let value = string_option(options, "value").unwrap_or_default();
+            if property == "account" {
+                let service = string_option(options, "service").unwrap_or_default();
+                link_response(context, &command.user, service, value).await
+            } else {
+                set_response(context, &command.user, property, value).await
+            }
+        }
+        ("privacy", options) => {
+            let property = string_option(options, "property");
+            let visibility = string_option(options, "visibility");
+            privacy_response(context, &command.user, property, visibility).await
+        }
+        ("export", _) => export_response(context, &command.user).await,
+        ("delete", _) => Ok(delete_response(&command.user)),
+        ("unset", options) => {
+            let property = string_option(options, "property").ok_or("You did not provide a profile property.")?;
+            let service = string_option(options, "service").unwrap_or_default();
+            unset_response(context, &command.user, property, service).await
+        }
+        (_, options) => {
+            let user = user_option(options, "user").unwrap_or(&command.user);
+            let member = guild_id.member(&context, user.id).await?;
+            profile_response(context, member, Viewer::new(command.user.id, command.guild_id)).await
+        }
+    }
+}
+
+pub async fn user(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "user").unwrap_or(&command.user);
+    let member = guild_id.member(&context, user.id).await?;
+    user_response(context, guild_id, member).await
+}
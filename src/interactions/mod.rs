@@ -0,0 +1,205 @@
+//! Application Commands
+//!
+//! Every command group is also exposed as a set of Discord application (slash)
+//! commands. The prefix commands registered with the standard framework keep
+//! working alongside them; both paths share the same command logic and only
+//! differ in how arguments are parsed and how the response is delivered.
+
+#[cfg(feature = "extra")]
+pub mod extra;
+#[cfg(feature = "fun")]
+pub mod fun;
+#[cfg(feature = "info")]
+pub mod info;
+#[cfg(feature = "moderation")]
+pub mod moderation;
+#[cfg(any(feature = "music-lastfm", feature = "music-spotify"))]
+pub mod music;
+#[cfg(feature = "tmdb")]
+pub mod search;
+#[cfg(feature = "twitter")]
+pub mod social;
+pub mod utilities;
+
+use serenity::{
+    builder::CreateCommand,
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        application::{CommandInteraction, ResolvedOption, ResolvedValue},
+        user::User
+    }
+};
+
+use tracing::error;
+
+use crate::{
+    commands::response::Response,
+    config::{ApiConfig, Integration},
+    error::report,
+    utils::config::get_config
+};
+
+/// Builds the application commands of every command group, leaving out the
+/// commands of integrations that aren't configured.
+pub fn commands(api: &ApiConfig) -> Vec<CreateCommand> {
+    let mut commands = Vec::new();
+
+    #[cfg(feature = "extra")]
+    commands.extend(extra::commands());
+    #[cfg(feature = "fun")]
+    commands.extend(fun::commands());
+    #[cfg(feature = "info")]
+    commands.extend(info::commands());
+    #[cfg(feature = "moderation")]
+    commands.extend(moderation::commands());
+    #[cfg(any(feature = "music-lastfm", feature = "music-spotify"))]
+    commands.extend(music::commands(api));
+
+    #[cfg(feature = "tmdb")]
+    if Integration::Tmdb.is_configured(api) {
+        commands.extend(search::commands());
+    }
+
+    #[cfg(feature = "twitter")]
+    if Integration::Twitter.is_configured(api) {
+        commands.extend(social::commands());
+    }
+
+    commands.extend(utilities::commands());
+    commands
+}
+
+/// Runs the given application command and responds with its result.
+///
+/// The interaction is deferred before the command runs, as most commands
+/// query an external API and would otherwise risk exceeding the three second
+/// window Discord gives for an initial response.
+pub async fn handle(context: &Context, command: &CommandInteraction) {
+    let name = command.data.name.as_str();
+    if let Err(why) = command.defer(&context.http).await {
+        error!("Unable to defer application command {name}: {why}");
+        return;
+    }
+
+    let response = match run(context, command, name).await {
+        Ok(response) => response,
+        Err(why) => report(name, why.as_ref()).into()
+    };
+
+    if let Err(why) = response.respond(context, command).await {
+        error!("Unable to respond to application command {name}: {why}");
+    }
+}
+
+/// Routes the given application command to its group, unless it depends on
+/// an integration that isn't configured.
+async fn run(context: &Context, command: &CommandInteraction, name: &str) -> CommandResult<Response> {
+    let api = get_config(context).await.api;
+    if let Some(integration) = Integration::for_command(name).filter(|integration| !integration.is_configured(&api)) {
+        return Ok(integration.unavailable_message().into());
+    }
+
+    match name {
+        #[cfg(feature = "extra")]
+        "sloc" => extra::sloc(context, command).await,
+        #[cfg(feature = "fun")]
+        "urban" => fun::urban(context, command).await,
+        #[cfg(feature = "fun")]
+        "randefine" => fun::randefine(context, command).await,
+        #[cfg(feature = "fun")]
+        "xkcd" => fun::xkcd(context, command).await,
+        #[cfg(feature = "info")]
+        "about" => info::about(context, command).await,
+        #[cfg(feature = "info")]
+        "guild" => info::guild(context, command).await,
+        #[cfg(feature = "info")]
+        "profile" => info::profile(context, command).await,
+        #[cfg(feature = "info")]
+        "user" => info::user(context, command).await,
+        #[cfg(feature = "moderation")]
+        "ban" => moderation::ban(context, command).await,
+        #[cfg(feature = "moderation")]
+        "bandays" => moderation::bandays(context, command).await,
+        #[cfg(feature = "moderation")]
+        "bans" => moderation::bans(context, command).await,
+        #[cfg(feature = "moderation")]
+        "case" => moderation::case(context, command).await,
+        #[cfg(feature = "moderation")]
+        "cases" => moderation::cases(context, command).await,
+        #[cfg(feature = "moderation")]
+        "escalation" => moderation::escalation(context, command).await,
+        #[cfg(feature = "moderation")]
+        "kick" => moderation::kick(context, command).await,
+        #[cfg(feature = "moderation")]
+        "massban" => moderation::massban(context, command).await,
+        #[cfg(feature = "moderation")]
+        "pardon" => moderation::pardon(context, command).await,
+        #[cfg(feature = "moderation")]
+        "reason" => moderation::reason(context, command).await,
+        #[cfg(feature = "moderation")]
+        "slowmode" => moderation::slowmode(context, command).await,
+        #[cfg(feature = "moderation")]
+        "tempban" => moderation::tempban(context, command).await,
+        #[cfg(feature = "moderation")]
+        "timeout" => moderation::timeout(context, command).await,
+        #[cfg(feature = "moderation")]
+        "unban" => moderation::unban(context, command).await,
+        #[cfg(feature = "moderation")]
+        "warn" => moderation::warn(context, command).await,
+        #[cfg(feature = "moderation")]
+        "warnings" => moderation::warnings(context, command).await,
+        #[cfg(feature = "music-lastfm")]
+        "lastfm" => music::lastfm(context, command).await,
+        #[cfg(feature = "music-spotify")]
+        "spotify" => music::spotify(context, command).await,
+        #[cfg(feature = "tmdb")]
+        "tmdb" => search::tmdb(context, command).await,
+        #[cfg(feature = "twitter")]
+        "twitter" => social::twitter(context, command).await,
+        "invite" => utilities::invite(context, command).await,
+        "ping" => utilities::ping(context, command).await,
+        "prefix" => utilities::prefix(context, command).await,
+        "remind" => utilities::remind(context, command).await,
+        "source" => utilities::source(context, command).await,
+        "time" => utilities::time(context, command).await,
+        "timestamp" => utilities::timestamp(context, command).await,
+        _ => Ok(format!("The `{name}` command is not recognized.").into())
+    }
+}
+
+/// Retrieves the subcommand that was invoked along with its options.
+pub fn subcommand<'a>(options: &'a [ResolvedOption<'a>]) -> CommandResult<(&'a str, &'a [ResolvedOption<'a>])> {
+    match options.first() {
+        Some(ResolvedOption {
+            name,
+            value: ResolvedValue::SubCommand(options),
+            ..
+        }) => Ok((name, options.as_slice())),
+        _ => Err("No valid subcommand provided.".into())
+    }
+}
+
+/// Retrieves the value of the given string option, if it was provided.
+pub fn string_option<'a>(options: &[ResolvedOption<'a>], name: &str) -> Option<&'a str> {
+    options.iter().find(|option| option.name == name).and_then(|option| match option.value {
+        ResolvedValue::String(value) => Some(value),
+        _ => None
+    })
+}
+
+/// Retrieves the value of the given integer option, if it was provided.
+pub fn integer_option(options: &[ResolvedOption], name: &str) -> Option<i64> {
+    options.iter().find(|option| option.name == name).and_then(|option| match option.value {
+        ResolvedValue::Integer(value) => Some(value),
+        _ => None
+    })
+}
+
+/// Retrieves the value of the given user option, if it was provided.
+pub fn user_option<'a>(options: &[ResolvedOption<'a>], name: &str) -> Option<&'a User> {
+    options.iter().find(|option| option.name == name).and_then(|option| match option.value {
+        ResolvedValue::User(user, _) => Some(user),
+        _ => None
+    })
+}
@@ -0,0 +1,292 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        application::{CommandInteraction, CommandOptionType},
+        Permissions
+    }
+};
+
+use super::{integer_option, string_option, subcommand, user_option};
+use crate::{
+    commands::{
+        moderation::{ban::*, cases::*, kick::*, slowmode::*, timeout::*, warn::*},
+        response::Response
+    },
+    utils::{infractions::MAX_DELETE_MESSAGE_DAYS, parsing::parse_user_id, time::parse_duration}
+};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("ban")
+            .description("Bans the given user from the server, even if they aren't a member.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::BAN_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "The user to ban, or their ID.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the ban.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("bandays")
+            .description("Shows or sets how many days of messages are deleted when someone is banned.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "days", "The number of days of messages to delete.")
+                    .min_int_value(0)
+                    .max_int_value(MAX_DELETE_MESSAGE_DAYS.into())
+            ),
+        CreateCommand::new("bans")
+            .description("Lists everyone who is banned from this server.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::BAN_MEMBERS),
+        CreateCommand::new("case")
+            .description("Shows a moderation case of this server.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::Integer, "case", "The case number.").required(true).min_int_value(1)),
+        CreateCommand::new("cases")
+            .description("Lists the moderation cases of a user in this server.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "The user to list the cases of.").required(true)),
+        CreateCommand::new("escalation")
+            .description("Shows or changes what happens once members reach a number of warnings.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "view",
+                "Shows the escalation rules and warning decay of this server."
+            ))
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Sets what happens once a member reaches a number of active warnings.")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::Integer, "warnings", "The number of active warnings.")
+                            .required(true)
+                            .min_int_value(1)
+                    )
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "action", "What to do.")
+                            .required(true)
+                            .add_string_choice("Timeout", "timeout")
+                            .add_string_choice("Kick", "kick")
+                            .add_string_choice("Ban", "ban")
+                    )
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "duration", "How long a timeout lasts, e.g. 1h or 1d12h."))
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Removes the escalation rule for a number of warnings.").add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "warnings", "The number of active warnings.")
+                        .required(true)
+                        .min_int_value(1)
+                )
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "decay", "Sets how long new warnings stay active.")
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "duration", "How long warnings stay active, e.g. 30d, or off.").required(true))
+            ),
+        CreateCommand::new("kick")
+            .description("Kicks the given member from the server.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::KICK_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "member", "The member to kick.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the kick.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("massban")
+            .description("Bans every user with the given IDs from the server, after asking you to confirm.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::BAN_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "users", "The IDs of the users to ban, separated by spaces.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the bans.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("pardon")
+            .description("Pardons a warning, so that it no longer counts towards escalation.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "case", "The case number of the warning.")
+                    .required(true)
+                    .min_int_value(1)
+            ),
+        CreateCommand::new("reason")
+            .description("Changes the reason of a moderation case.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::Integer, "case", "The case number.").required(true).min_int_value(1))
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "reason", "The new reason.")
+                    .required(true)
+                    .max_length(REASON_LIMIT as u16)
+            ),
+        CreateCommand::new("slowmode")
+            .description("Shows or sets the slowmode rate for the current channel.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_CHANNELS)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "seconds", "The slowmode rate, in seconds. Use 0 to clear it.")
+                    .min_int_value(0)
+                    .max_int_value(21600)
+            ),
+        CreateCommand::new("tempban")
+            .description("Bans the given user from the server for a while.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::BAN_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "The user to ban, or their ID.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "duration", "How long the ban lasts, e.g. 1d12h.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the ban.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("timeout")
+            .description("Times out the given member, so that they can't talk or react for a while.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "member", "The member to time out.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "duration", "How long the timeout lasts, e.g. 1h or 1d12h.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the timeout.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("unban")
+            .description("Lifts the ban of the given user.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::BAN_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "The user to unban, or their ID.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the unban.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("warn")
+            .description("Warns the given member.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "member", "The member to warn.").required(true))
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "reason", "The reason for the warning.").max_length(REASON_LIMIT as u16)),
+        CreateCommand::new("warnings")
+            .description("Lists the warnings of a user in this server.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "The user to list the warnings of.").required(true)),
+    ]
+}
+
+pub async fn ban(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "user").ok_or("You did not provide a user to ban.")?;
+    let reason = string_option(&options, "reason");
+    Ok(ban_member(context, guild_id, user.id, command.user.id, reason).await?.into())
+}
+
+pub async fn bandays(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    Ok(ban_days_response(context, guild_id, integer_option(&options, "days")).await?.into())
+}
+
+pub async fn bans(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    bans_response(context, guild_id).await
+}
+
+pub async fn case(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let case_id = integer_option(&options, "case").ok_or("You did not provide a case number.")?;
+    case_response(context, guild_id, case_id as i32).await
+}
+
+pub async fn cases(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "user").ok_or("You did not provide a user.")?;
+    cases_response(context, guild_id, user.id).await
+}
+
+pub async fn escalation(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let response = match subcommand(&options)? {
+        ("set", options) => {
+            let warnings = integer_option(options, "warnings").unwrap_or_default() as i32;
+            let action = string_option(options, "action").unwrap_or_default();
+            set_escalation_response(context, guild_id, warnings, action, string_option(options, "duration")).await?
+        }
+        ("remove", options) => remove_escalation_response(context, guild_id, integer_option(options, "warnings").unwrap_or_default() as i32).await?,
+        ("decay", options) => decay_response(context, guild_id, string_option(options, "duration").unwrap_or_default()).await?,
+        _ => return escalation_response(context, guild_id).await
+    };
+
+    Ok(response.into())
+}
+
+pub async fn kick(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "member").ok_or("You did not provide a member to kick.")?;
+    let reason = string_option(&options, "reason");
+    Ok(kick_member(context, guild_id, user.id, command.user.id, reason).await?.into())
+}
+
+pub async fn massban(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let ids = string_option(&options, "users").unwrap_or_default();
+    let users = match ids.split_whitespace().map(|id| parse_user_id(id).ok_or(id)).collect::<Result<Vec<_>, _>>() {
+        Ok(users) => users,
+        Err(id) => return Ok(format!("`{id}` is not a valid user ID.").into())
+    };
+
+    let reason = string_option(&options, "reason").map(str::to_string);
+    Ok(massban_response(guild_id, users, command.user.id, reason))
+}
+
+pub async fn pardon(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let case_id = integer_option(&options, "case").ok_or("You did not provide a case number.")?;
+    Ok(pardon_response(context, guild_id, case_id as i32, command.user.id).await?.into())
+}
+
+pub async fn reason(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let case_id = integer_option(&options, "case").ok_or("You did not provide a case number.")?;
+    let reason = string_option(&options, "reason").unwrap_or_default();
+    Ok(reason_response(context, guild_id, case_id as i32, reason).await?.into())
+}
+
+pub async fn slowmode(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let rate = integer_option(&options, "seconds").map(|rate| rate as u64);
+    Ok(apply_slowmode(context, command.channel_id, rate).await.into())
+}
+
+pub async fn tempban(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "user").ok_or("You did not provide a user to ban.")?;
+    let duration = parse_duration(string_option(&options, "duration").unwrap_or_default()).ok_or("Please give a valid duration, e.g. `1h` or `1d12h`.")?;
+    let reason = string_option(&options, "reason");
+    Ok(tempban_member(context, guild_id, user.id, command.user.id, duration.num_seconds(), reason).await?.into())
+}
+
+pub async fn timeout(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "member").ok_or("You did not provide a member to time out.")?;
+    let duration = parse_duration(string_option(&options, "duration").unwrap_or_default()).ok_or("Please give a valid duration, e.g. `1h` or `1d12h`.")?;
+    let reason = string_option(&options, "reason");
+    Ok(timeout_member(context, guild_id, user.id, command.user.id, duration.num_seconds(), reason).await?.into())
+}
+
+pub async fn unban(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "user").ok_or("You did not provide a user to unban.")?;
+    let reason = string_option(&options, "reason");
+    Ok(unban_member(context, guild_id, user.id, command.user.id, reason).await?.into())
+}
+
+pub async fn warn(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "member").ok_or("You did not provide a member to warn.")?;
+    let reason = string_option(&options, "reason");
+    Ok(warn_member(context, guild_id, user.id, command.user.id, reason).await?.into())
+}
+
+pub async fn warnings(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let user = user_option(&options, "user").ok_or("You did not provide a user.")?;
+    warnings_response(context, guild_id, user.id).await
+}
@@ -0,0 +1,116 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::{CommandInteraction, CommandOptionType}
+};
+
+use super::{string_option, subcommand, user_option};
+use crate::{
+    commands::response::Response,
+    config::{ApiConfig, Integration}
+};
+
+#[cfg(feature = "music-spotify")]
+use crate::commands::music::spotify::{album::*, newreleases::*, status::*, track::*};
+#[cfg(feature = "music-lastfm")]
+use crate::{
+    commands::music::lastfm::{nowplaying::*, profile::*, resolve_username, scrobbles::*},
+    utils::profiles::Viewer
+};
+
+pub fn commands(api: &ApiConfig) -> Vec<CreateCommand> {
+    let mut commands = Vec::new();
+
+    #[cfg(feature = "music-lastfm")]
+    if Integration::LastFm.is_configured(api) {
+        commands.push(lastfm_command());
+    }
+
+    #[cfg(feature = "music-spotify")]
+    if Integration::Spotify.is_configured(api) {
+        commands.push(spotify_command());
+    }
+
+    commands
+}
+
+#[cfg(feature = "music-lastfm")]
+fn lastfm_command() -> CreateCommand {
+    CreateCommand::new("lastfm")
+        .description("Shows a bunch of information from Last.fm.")
+        .add_option(lastfm_subcommand("profile", "Retrieves various Last.fm user stats."))
+        .add_option(lastfm_subcommand("nowplaying", "Retrieves the Last.fm now playing state of a given user."))
+        .add_option(lastfm_subcommand("scrobbles", "Retrieves a given Last.fm user's scrobble count."))
+}
+
+#[cfg(feature = "music-spotify")]
+fn spotify_command() -> CreateCommand {
+    CreateCommand::new("spotify")
+        .description("Retrieves information about media on Spotify.")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "album", "Displays information about a specified album on Spotify.")
+                .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "query", "The album to search for.").required(true))
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "newreleases", "Displays the new releases for a given market.").add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "market", "The two-letter country code of the market.")
+                    .required(true)
+                    .min_length(2)
+                    .max_length(2)
+            )
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "status", "Shows yours or another user's Spotify status.").add_sub_option(CreateCommandOption::new(
+                CommandOptionType::User,
+                "member",
+                "The member to show the status of."
+            ))
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "track", "Displays information about a specified track on Spotify.")
+                .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "query", "The track to search for.").required(true))
+        )
+}
+
+#[cfg(feature = "music-lastfm")]
+fn lastfm_subcommand(name: &str, description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::SubCommand, name, description)
+        .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "username", "The Last.fm username to look up."))
+        .add_sub_option(CreateCommandOption::new(CommandOptionType::User, "member", "The member whose Last.fm username to look up."))
+}
+
+#[cfg(feature = "music-lastfm")]
+pub async fn lastfm(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let (name, options) = subcommand(&options)?;
+    let mention = user_option(options, "member").map(|user| user.id);
+    let username = string_option(options, "username").map(str::to_string);
+    let viewer = Viewer::new(command.user.id, command.guild_id);
+    let user = match resolve_username(context, &viewer, mention, username).await? {
+        Some(user) => user,
+        None => return Ok("No username found. Please set one via `profile set` or provide one.".into())
+    };
+
+    match name {
+        "nowplaying" => nowplaying_response(context, &command.user, &user).await,
+        "scrobbles" => scrobbles_response(context, &command.user, &user).await,
+        _ => lastfm_profile_response(context, &command.user, &user).await
+    }
+}
+
+#[cfg(feature = "music-spotify")]
+pub async fn spotify(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    match subcommand(&options)? {
+        ("album", options) => album_response(context, string_option(options, "query").unwrap_or_default()).await,
+        ("newreleases", options) => newreleases_response(context, string_option(options, "market").unwrap_or_default()).await,
+        ("track", options) => track_response(context, string_option(options, "query").unwrap_or_default()).await,
+        (_, options) => {
+            let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+            let user = user_option(options, "member").unwrap_or(&command.user);
+            let member = guild_id.member(&context, user.id).await?;
+            status_response(context, guild_id, member, &command.user).await
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::{CommandInteraction, CommandOptionType}
+};
+
+use super::{string_option, subcommand};
+use crate::commands::{
+    response::Response,
+    search::tmdb::{cast::*, collection::*, movie::*, show::*}
+};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![CreateCommand::new("tmdb")
+        .description("Gets a variety of information from The Movie Database.")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "cast", "Gets the cast and crew of a show or movie.")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "type", "The type of media to look up.")
+                        .required(true)
+                        .add_string_choice("Show", "show")
+                        .add_string_choice("Movie", "movie")
+                )
+                .add_sub_option(query_option("The show or movie to search for. Append `y:<year>` to filter by year."))
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "collection", "Gets detailed information about a collection.")
+                .add_sub_option(query_option("The collection to search for."))
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "movie", "Gets detailed information about a movie.")
+                .add_sub_option(query_option("The movie to search for. Append `y:<year>` to filter by year."))
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "show", "Gets detailed information about a TV series.").add_sub_option(query_option("The series to search for."))
+        )]
+}
+
+fn query_option(description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::String, "query", description).required(true)
+}
+
+pub async fn tmdb(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let (name, options) = subcommand(&options)?;
+    let query = string_option(options, "query").ok_or("No search query provided. Provide one & try again.")?;
+    match name {
+        "cast" => cast_response(context, string_option(options, "type").unwrap_or("movie"), query).await,
+        "collection" => collection_response(context, query).await,
+        "show" => show_response(context, query).await,
+        _ => movie_response(context, query).await
+    }
+}
@@ -0,0 +1,23 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption},
+    client::Context,
+    framework::standard::CommandResult,
+    model::application::{CommandInteraction, CommandOptionType}
+};
+
+use super::{string_option, subcommand};
+use crate::commands::{response::Response, social::twitter::user::twitter_user_response};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![CreateCommand::new("twitter").description("Gets a variety of information from Twitter.").add_option(
+        CreateCommandOption::new(CommandOptionType::SubCommand, "user", "Displays information about a given user on Twitter.")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "username", "The user's Twitter handle.").required(true))
+    )]
+}
+
+pub async fn twitter(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let (_, options) = subcommand(&options)?;
+    let username = string_option(options, "username").ok_or("No username provided.")?;
+    twitter_user_response(context, username.trim_start_matches('@')).await
+}
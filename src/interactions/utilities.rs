@@ -0,0 +1,126 @@
+use serenity::{
+    builder::{CreateCommand, CreateCommandOption, CreateEmbed},
+    client::Context,
+    framework::standard::CommandResult,
+    model::{
+        application::{CommandInteraction, CommandOptionType},
+        Permissions
+    }
+};
+
+use super::{integer_option, string_option, subcommand, user_option};
+use crate::{
+    commands::{
+        response::Response,
+        utilities::{invite_response, prefix::*, remind::*, shard_latency, time::*, SOURCE_RESPONSE}
+    },
+    utils::profiles::Viewer
+};
+
+pub fn commands() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("invite").description("Generates an invite link for the bot."),
+        CreateCommand::new("ping").description("Checks Discord's API / message latency."),
+        CreateCommand::new("prefix")
+            .description("Shows or changes the prefixes of this server.")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "view", "Shows the prefixes of this server."))
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Replaces this server's own prefixes with the given prefix.")
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "prefix", "The new prefix.").required(true))
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "add", "Adds one or more prefixes to this server.")
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "prefixes", "The prefixes to add, separated by spaces.").required(true))
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Removes a prefix from this server.")
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "prefix", "The prefix to remove.").required(true))
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "reset",
+                "Removes this server's own prefixes, leaving only the default prefix."
+            )),
+        CreateCommand::new("remind")
+            .description("Sets, lists or cancels reminders.")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "me", "Sets a reminder, read in the time zone on your profile.")
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "when", "When to remind you, e.g. in 2h30m or every monday 9am.").required(true))
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "message", "What to remind you of.")
+                            .required(true)
+                            .max_length(1000)
+                    )
+            )
+            .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "list", "Lists your pending reminders."))
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::SubCommand, "cancel", "Cancels one of your reminders.")
+                    .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "id", "The ID of the reminder.").required(true))
+            ),
+        CreateCommand::new("source").description("Sends a link containing the bot's source code."),
+        CreateCommand::new("time")
+            .description("Shows the local time of a member, from the time zone on their profile.")
+            .add_option(CreateCommandOption::new(CommandOptionType::User, "member", "The member to show the local time of.")),
+        CreateCommand::new("timestamp")
+            .description("Converts a time into Discord timestamps, read in the time zone on your profile.")
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "when", "The time, e.g. tomorrow 5pm or in 3 hours.").required(true)),
+    ]
+}
+
+pub async fn invite(context: &Context, _command: &CommandInteraction) -> CommandResult<Response> {
+    invite_response(context).await
+}
+
+pub async fn ping(context: &Context, _command: &CommandInteraction) -> CommandResult<Response> {
+    let shard_response = shard_latency(context).await;
+    let response = format!("Pong! Succesfully retrieved the shard latency. :ping_pong:\n\n**Shard Response Time**: {shard_response}");
+    let embed = CreateEmbed::new().color(0x008b_0000).title("Discord Latency Information").description(response);
+    Ok(Response::embed(embed))
+}
+
+pub async fn prefix(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let guild_id = command.guild_id.ok_or("This command is only available in guilds.")?;
+    let options = command.data.options();
+    let response = match subcommand(&options)? {
+        ("set", options) => set_prefix(context, guild_id, string_option(options, "prefix").unwrap_or_default()).await?,
+        ("add", options) => {
+            let prefixes = string_option(options, "prefixes").unwrap_or_default().split_whitespace().collect::<Vec<&str>>();
+            add_prefixes(context, guild_id, &prefixes).await?
+        }
+        ("remove", options) => remove_prefix(context, guild_id, string_option(options, "prefix").unwrap_or_default()).await?,
+        ("reset", _) => reset_prefixes(context, guild_id).await?,
+        _ => list_prefixes(context, guild_id).await?
+    };
+
+    Ok(response.into())
+}
+
+pub async fn remind(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    match subcommand(&options)? {
+        ("list", _) => list_reminders_response(context, command.user.id).await,
+        ("cancel", options) => cancel_reminder_response(context, command.user.id, integer_option(options, "id").unwrap_or_default()).await,
+        (_, options) => {
+            let when = string_option(options, "when").unwrap_or_default();
+            let message = string_option(options, "message").unwrap_or_default();
+            remind_response(context, command.user.id, command.channel_id, when, message).await
+        }
+    }
+}
+
+pub async fn source(_context: &Context, _command: &CommandInteraction) -> CommandResult<Response> {
+    Ok(SOURCE_RESPONSE.into())
+}
+
+pub async fn time(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    let user = user_option(&options, "member").unwrap_or(&command.user);
+    time_response(context, Viewer::new(command.user.id, command.guild_id), user).await
+}
+
+pub async fn timestamp(context: &Context, command: &CommandInteraction) -> CommandResult<Response> {
+    let options = command.data.options();
+    timestamp_response(context, &command.user, string_option(&options, "when").unwrap_or_default()).await
+}
@@ -0,0 +1,49 @@
+//! Command checks
+//!
+//! Commands backed by a third-party API are guarded by a check for their
+//! integration, so they are unavailable (and hidden from the help command)
+//! while the integration isn't configured.
+
+use serenity::{
+    client::Context,
+    framework::standard::{macros::check, Args, CommandOptions, Reason},
+    model::channel::Message
+};
+
+use crate::{config::Integration, utils::config::get_config};
+
+async fn integration_check(context: &Context, integration: Integration) -> Result<(), Reason> {
+    if integration.is_configured(&get_config(context).await.api) {
+        Ok(())
+    } else {
+        Err(Reason::User(integration.unavailable_message()))
+    }
+}
+
+#[cfg(feature = "music-lastfm")]
+#[check]
+#[name = "LastFm"]
+async fn lastfm_check(context: &Context, _: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    integration_check(context, Integration::LastFm).await
+}
+
+#[cfg(feature = "music-spotify")]
+#[check]
+#[name = "Spotify"]
+async fn spotify_check(context: &Context, _: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    integration_check(context, Integration::Spotify).await
+}
+
+#[cfg(feature = "tmdb")]
+#[check]
+#[name = "Tmdb"]
+async fn tmdb_check(context: &Context, _: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    integration_check(context, Integration::Tmdb).await
+}
+
+#[cfg(feature = "twitter")]
+#[check]
+#[name = "Twitter"]
+async fn twitter_check(context: &Context, _: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    integration_check(context, Integration::Twitter).await
+}
@@ -1,16 +1,21 @@
-use crate::{data::DatabasePool, utils::read_config};
+use crate::{
+    data::DatabasePool,
+    interactions,
+    utils::{config::get_config, profiles::record_user}
+};
 use serenity::{
     async_trait,
     client::{Context, EventHandler},
     gateway::ActivityData,
     model::{
+        application::{Command, Interaction},
         channel::{GuildChannel, Message},
         prelude::OnlineStatus
     },
     model::{gateway::Ready, guild::Guild}
 };
 use sqlx::Row;
-use tracing::info;
+use tracing::{error, info};
 
 pub struct Handler;
 
@@ -41,20 +46,28 @@ impl EventHandler for Handler {
 
         let presence = format!("on {guild_count} guilds | e.help");
         context.set_presence(Some(ActivityData::playing(presence)), OnlineStatus::Online);
+
+        match Command::set_global_commands(&context.http, interactions::commands(&get_config(&context).await.api)).await {
+            Ok(commands) => info!("Registered {} application command(s).", commands.len()),
+            Err(why) => error!("Failed to register application commands: {why:?}")
+        }
+    }
+
+    async fn interaction_create(&self, context: Context, interaction: Interaction) {
+        if let Interaction::Command(command) = interaction {
+            interactions::handle(&context, &command).await;
+        }
     }
 
     async fn guild_create(&self, context: Context, guild: Guild, _is_new: std::option::Option<bool>) {
-        let config = read_config("config.toml");
         let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
 
         let guild_name = guild.name;
         let guild_id = guild.id.get() as i64;
-        let guild_prefix = config.bot.general.prefix;
 
-        sqlx::query("INSERT INTO guild_info (guild_id, guild_name, guild_prefix) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING")
+        sqlx::query("INSERT INTO guild_info (guild_id, guild_name) VALUES ($1, $2) ON CONFLICT DO NOTHING")
             .bind(guild_id)
             .bind(&guild_name)
-            .bind(&guild_prefix)
             .execute(&pool)
             .await
             .unwrap();
@@ -77,23 +90,20 @@ impl EventHandler for Handler {
     /// Message handler
     ///
     /// Upon message receive events, Taliyah will automatically add the
-    /// author's user id to the profiles table in the database. This
-    /// event might get expanded later on to handle other things, too.
+    /// author's user id to the profiles table in the database, unless they
+    /// have deleted their data. This event might get expanded later on to
+    /// handle other things, too.
     ///
     /// Bots are blacklisted from being added to the database, due to them
     /// not being actual users, so bots having their own profile sort of
     /// holds no value.
     async fn message(&self, context: Context, message: Message) {
-        let id = message.author.id.get() as i64;
-        let tag = message.author.tag();
-        let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
-        if !message.author.bot {
-            sqlx::query("INSERT INTO profile_data (user_id, user_tag) VALUES ($1, $2) ON CONFLICT DO NOTHING")
-                .bind(id)
-                .bind(tag)
-                .execute(&pool)
-                .await
-                .unwrap();
+        if message.author.bot {
+            return;
+        }
+
+        if let Err(why) = record_user(&context, &message.author).await {
+            error!("Unable to record {} in the database: {why}", message.author.id);
         }
     }
 }
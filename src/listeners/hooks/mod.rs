@@ -1,16 +1,19 @@
 use serenity::{
     client::Context,
-    framework::standard::{macros::hook, CommandResult, DispatchError},
+    framework::standard::{macros::hook, CommandResult, DispatchError, Reason},
     model::channel::Message
 };
 use tracing::error;
 
+use crate::{
+    error::report,
+    utils::prefixes::{default_prefix, get_guild_prefixes}
+};
+
 #[hook]
 pub async fn after(context: &Context, message: &Message, command: &str, error: CommandResult) {
     if let Err(why) = &error {
-        error!("Error while running command {}", &command);
-        error!("{:?}", &error);
-        if message.channel_id.say(context, why.to_string()).await.is_err() {
+        if message.channel_id.say(context, report(command, why.as_ref())).await.is_err() {
             let channel = &message.channel_id.name(&context).await.unwrap();
             error!("Unable to send messages to channel {}", &channel);
         };
@@ -57,6 +60,9 @@ pub async fn dispatch_error(context: &Context, message: &Message, error: Dispatc
             error_response = format!("Max arguments allowed is {max}, but got {given}.");
             drop(message.channel_id.say(context, error_response).await);
         }
+        DispatchError::CheckFailed(_, Reason::User(reason) | Reason::UserAndLog { user: reason, .. }) => {
+            drop(message.channel_id.say(context, reason).await);
+        }
         _ => tracing::warn!("Unhandled Dispatch error: {:?}", error)
     }
 }
@@ -65,3 +71,23 @@ pub async fn dispatch_error(context: &Context, message: &Message, error: Dispatc
 pub async fn prefix_only(context: &Context, message: &Message) {
     drop(message.channel_id.say(&context, "For info on my features, run the help command.").await);
 }
+
+/// Resolves the prefix of a message.
+///
+/// Guilds can set several prefixes, so the longest of them that the message
+/// starts with is returned for the framework to strip.
+#[hook]
+pub async fn dynamic_prefix(context: &Context, message: &Message) -> Option<String> {
+    let prefixes = match message.guild_id {
+        Some(guild_id) => match get_guild_prefixes(context, guild_id).await {
+            Ok(prefixes) => prefixes,
+            Err(why) => {
+                error!("Unable to retrieve prefixes for guild {guild_id}: {why}");
+                vec![default_prefix(context).await]
+            }
+        },
+        None => vec![default_prefix(context).await]
+    };
+
+    prefixes.into_iter().filter(|prefix| message.content.starts_with(prefix.as_str())).max_by_key(String::len)
+}
@@ -1,2 +1,4 @@
+#[cfg(any(feature = "music-lastfm", feature = "music-spotify", feature = "tmdb", feature = "twitter"))]
+pub mod checks;
 pub mod handler;
 pub mod hooks;
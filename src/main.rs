@@ -3,77 +3,118 @@
 //! Taliyah is a bot for the Discord chat platform focused on giving users
 //! a powerful set of features, while remaining quick to respond.
 
+mod cli;
 mod commands;
 mod config;
 mod constants;
 mod data;
+mod database;
 mod error;
+mod interactions;
 mod listeners;
 mod models;
+mod services;
+#[cfg(test)]
+mod testing;
 mod utils;
 
-use aspotify::{Client as SpotifyClient, ClientCredentials};
-use commands::{
-    extra::sloc::*,
-    fun::{urban::*, xkcd::*},
-    info::{about::*, guild::*, profile::*, user::*},
-    moderation::{ban::*, kick::*, slowmode::*},
-    music::{lastfm::*, spotify::*},
-    search::tmdb::*,
-    social::twitter::*,
-    utilities::*
-};
+#[cfg(feature = "extra")]
+use commands::extra::sloc::*;
+#[cfg(feature = "fun")]
+use commands::fun::{urban::*, xkcd::*};
+#[cfg(feature = "info")]
+use commands::info::{about::*, guild::*, profile::*, user::*};
+#[cfg(feature = "moderation")]
+use commands::moderation::{ban::*, cases::*, kick::*, slowmode::*, timeout::*, warn::*};
+#[cfg(feature = "music-lastfm")]
+use commands::music::lastfm::*;
+#[cfg(feature = "music-spotify")]
+use commands::music::spotify::*;
+#[cfg(feature = "tmdb")]
+use commands::search::tmdb::*;
+#[cfg(feature = "twitter")]
+use commands::social::twitter::*;
+use commands::utilities::{cache::*, help::*, prefix::*, remind::*, time::*, *};
+#[cfg(feature = "music-spotify")]
+use utils::config::refresh_spotify;
+#[cfg(feature = "moderation")]
+use utils::infractions::run_unban_scheduler;
 
 use listeners::{handler::Handler, hooks::*};
 
-use reqwest::{redirect::Policy, Client};
 use serenity::{
     client::ClientBuilder,
     framework::{standard::macros::group, StandardFramework},
     http::Http,
-    model::gateway::GatewayIntents
+    model::{gateway::GatewayIntents, id::ApplicationId}
 };
 
-use sqlx::postgres::PgPoolOptions;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    error::Error,
+    sync::Arc
+};
 
-use std::{collections::HashSet, error::Error, sync::Arc};
+use tokio::sync::RwLock;
 
-use tracing::{info, instrument, Level};
+use tracing::{info, instrument, warn, Level};
 use tracing_log::LogTracer;
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
-use crate::{constants::*, data::*, utils::read_config};
+use crate::{
+    cli::Command,
+    config::Integration,
+    data::*,
+    services::{Backend, Services},
+    utils::{
+        cache::ResponseCache,
+        config::{config_path, watch_config},
+        http::HttpClient,
+        read_config,
+        reminders::run_scheduler
+    }
+};
 
+#[cfg(feature = "extra")]
 #[group("Extra")]
 #[description = "Commands that don't really fit in the other command groups."]
 #[commands(sloc)]
 struct Extra;
 
+#[cfg(feature = "fun")]
 #[group("Fun")]
 #[description = "Commands that could be considered fun / silly."]
 #[commands(urban, randefine, xkcd)]
 struct Fun;
 
+#[cfg(feature = "info")]
 #[group("Info")]
 #[description = "Informational commands that provide useful information."]
 #[commands(about, guild, profile, user)]
 struct Info;
 
+#[cfg(feature = "moderation")]
 #[group("Moderation")]
 #[description = "Commands that help with the moderation of servers."]
-#[commands(ban, kick, slowmode)]
+#[commands(ban, bandays, bans, case, cases, escalation, kick, massban, pardon, reason, slowmode, tempban, timeout, unban, warn, warnings)]
 struct Moderation;
 
+#[cfg(any(feature = "music-lastfm", feature = "music-spotify"))]
 #[group("Music")]
 #[description = "Music-focused commands."]
-#[commands(lastfm, spotify)]
+#[cfg_attr(all(feature = "music-lastfm", feature = "music-spotify"), commands(lastfm, spotify))]
+#[cfg_attr(all(feature = "music-lastfm", not(feature = "music-spotify")), commands(lastfm))]
+#[cfg_attr(all(not(feature = "music-lastfm"), feature = "music-spotify"), commands(spotify))]
 struct Music;
 
+#[cfg(feature = "tmdb")]
 #[group("Search")]
 #[description = "Various commands that search various web services."]
 #[commands(tmdb)]
 struct Search;
 
+#[cfg(feature = "twitter")]
 #[group("Social")]
 #[description = "Commands that integrate with various services, e.g. Twitter."]
 #[commands(twitter)]
@@ -81,13 +122,29 @@ struct Social;
 
 #[group("Utilities")]
 #[description = "Miscellaneous commands that don't really fit into a more-specific category."]
-#[commands(invite, ping, source)]
+#[commands(cache, invite, ping, prefix, remind, source, time, timestamp)]
 struct Utilities;
 
 #[tokio::main(worker_threads = 16)]
 #[instrument]
 async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
-    let configuration = read_config("config.toml");
+    let command = Command::parse(env::args().skip(1))?;
+    let path = config_path();
+    if let Command::CheckConfig = command {
+        return Ok(cli::check_config(&path)?);
+    }
+
+    let configuration = read_config(&path)?;
+
+    match command {
+        Command::RegisterCommands => return Ok(cli::register_commands(&configuration).await?),
+        Command::Doctor => return Ok(cli::doctor(&configuration).await?),
+        Command::Migrate(command) => return Ok(cli::migrate(command, &configuration).await?),
+        Command::Run | Command::CheckConfig => {}
+    }
+
+    let config = Arc::new(RwLock::new(configuration.clone()));
+
     if configuration.bot.logging.enabled {
         LogTracer::init()?;
 
@@ -110,53 +167,91 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         info!("Tracing initialized with logging level set to {}.", level);
     }
 
+    for integration in Integration::ALL {
+        if !integration.is_configured(&configuration.api) {
+            let commands = integration.commands().join(", ");
+            warn!("{} is not configured; the following commands are disabled: {commands}", integration.name());
+        }
+    }
+
     let token = configuration.bot.discord.token;
-    let prefix = configuration.bot.general.prefix.as_str();
+    let appid = ApplicationId::new(configuration.bot.discord.appid);
 
-    let http = Http::new(&token);
+    let http = Arc::new(Http::new(&token));
     let id = http.get_current_user().await?.id;
     let owner = http.get_current_application_info().await?.owner.id;
 
     let mut owners = HashSet::new();
     owners.insert(owner);
 
-    let framework = StandardFramework::new()
-        .prefix_only(prefix_only)
-        .after(after)
-        .on_dispatch_error(dispatch_error)
-        .group(&EXTRA_GROUP)
-        .group(&FUN_GROUP)
-        .group(&INFO_GROUP)
-        .group(&MODERATION_GROUP)
-        .group(&MUSIC_GROUP)
-        .group(&SEARCH_GROUP)
-        .group(&SOCIAL_GROUP)
-        .group(&UTILITIES_GROUP)
-        .help(&HELP);
-
-    framework.configure(|c| c.on_mention(Some(id)).prefix(prefix).ignore_webhooks(false).no_dm_prefix(true).owners(owners));
-
-    let mut client = ClientBuilder::new(&token, GatewayIntents::all()).event_handler(Handler).framework(framework).await?;
+    let mut framework = StandardFramework::new().prefix_only(prefix_only).after(after).on_dispatch_error(dispatch_error).help(&HELP);
+
+    #[cfg(feature = "extra")]
+    framework.group_add(&EXTRA_GROUP);
+    #[cfg(feature = "fun")]
+    framework.group_add(&FUN_GROUP);
+    #[cfg(feature = "info")]
+    framework.group_add(&INFO_GROUP);
+    #[cfg(feature = "moderation")]
+    framework.group_add(&MODERATION_GROUP);
+    #[cfg(any(feature = "music-lastfm", feature = "music-spotify"))]
+    framework.group_add(&MUSIC_GROUP);
+    #[cfg(feature = "tmdb")]
+    framework.group_add(&SEARCH_GROUP);
+    #[cfg(feature = "twitter")]
+    framework.group_add(&SOCIAL_GROUP);
+    framework.group_add(&UTILITIES_GROUP);
+
+    framework.configure(|c| {
+        c.on_mention(Some(id))
+            .prefix("")
+            .dynamic_prefix(dynamic_prefix)
+            .ignore_webhooks(false)
+            .no_dm_prefix(true)
+            .owners(owners)
+    });
+
+    let mut client = ClientBuilder::new(&token, GatewayIntents::all())
+        .application_id(appid)
+        .event_handler(Handler)
+        .framework(framework)
+        .await?;
 
     {
         let mut data = client.data.write().await;
-        let url = configuration.bot.database.url;
-        let pool = PgPoolOptions::new().max_connections(20).connect(&url).await?;
-        let http = Client::builder().user_agent(REQWEST_USER_AGENT).redirect(Policy::none()).build()?;
+        let pool = database::connect(&configuration.bot.database).await?;
+        if configuration.bot.database.migrate {
+            database::run_migrations(&pool).await?;
+            info!("Applied any pending database migrations.");
+        }
 
-        data.insert::<ConfigContainer>(read_config("config.toml"));
+        tokio::spawn(run_scheduler(Arc::clone(&http), pool.clone()));
+        #[cfg(feature = "moderation")]
+        tokio::spawn(run_unban_scheduler(Arc::clone(&http), pool.clone()));
+
+        let http = HttpClient::new(&configuration.bot.http)?;
+
+        let cache = Arc::new(ResponseCache::new(&configuration.bot.cache, &pool));
+        let services = Services::new(Backend {
+            http: http.clone(),
+            cache: Arc::clone(&cache),
+            config: Arc::clone(&config)
+        });
+
+        data.insert::<ConfigContainer>(Arc::clone(&config));
         data.insert::<DatabasePool>(pool);
+        data.insert::<PrefixCache>(Arc::new(RwLock::new(HashMap::new())));
         data.insert::<ShardManagerContainer>(Arc::clone(&client.shard_manager));
         data.insert::<ReqwestContainer>(http);
+        data.insert::<ResponseCacheContainer>(cache);
+        data.insert::<ServicesContainer>(services);
 
-        {
-            let id = configuration.api.music.spotify.client_id;
-            let secret = configuration.api.music.spotify.client_secret;
-            let client = SpotifyClient::new(ClientCredentials { id, secret });
-            data.insert::<SpotifyContainer>(client);
-        }
+        #[cfg(feature = "music-spotify")]
+        refresh_spotify(&mut data, &configuration);
     }
 
+    tokio::spawn(watch_config(Arc::clone(&client.data), path));
+
     if let Err(why) = client.start_autosharded().await {
         eprintln!("An error occurred while running the client: {why:?}");
     }
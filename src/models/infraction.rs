@@ -0,0 +1,97 @@
+use sqlx::FromRow;
+
+/// A moderation action, as stored in the `infractions` table. Times are Unix
+/// timestamps.
+#[derive(Debug, FromRow)]
+pub struct Infraction {
+    /// The number of the case within its guild, starting at 1.
+    pub case_id: i32,
+    pub guild_id: i64,
+    /// The user the action was taken against.
+    pub user_id: i64,
+    pub moderator_id: i64,
+    /// The action, in the form given by [`Action::key`].
+    pub action: String,
+    pub reason: Option<String>,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub pardoned_at: Option<i64>,
+    pub pardoned_by: Option<i64>
+}
+
+impl Infraction {
+    pub fn action(&self) -> Option<Action> {
+        Action::parse(&self.action)
+    }
+
+    /// The name of the action, falling back to how it is stored if it isn't
+    /// known.
+    pub fn action_label(&self) -> &str {
+        self.action().map(Action::label).unwrap_or(&self.action)
+    }
+
+    /// Whether the infraction still counts against the user, which is until
+    /// it expires or is pardoned.
+    pub fn is_active(&self, now: i64) -> bool {
+        self.pardoned_at.is_none() && self.expires_at.map_or(true, |expires_at| expires_at > now)
+    }
+}
+
+/// A kind of moderation action that is recorded as an infraction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Ban,
+    Kick,
+    Tempban,
+    Timeout,
+    Unban,
+    Warn
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [Action::Ban, Action::Kick, Action::Tempban, Action::Timeout, Action::Unban, Action::Warn];
+
+    pub fn parse(key: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.key().eq_ignore_ascii_case(key))
+    }
+
+    /// The name the action is stored under.
+    pub fn key(self) -> &'static str {
+        match self {
+            Action::Ban => "ban",
+            Action::Kick => "kick",
+            Action::Tempban => "tempban",
+            Action::Timeout => "timeout",
+            Action::Unban => "unban",
+            Action::Warn => "warn"
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Ban => "Ban",
+            Action::Kick => "Kick",
+            Action::Tempban => "Temporary Ban",
+            Action::Timeout => "Timeout",
+            Action::Unban => "Unban",
+            Action::Warn => "Warning"
+        }
+    }
+}
+
+/// An action taken automatically once a user reaches a number of active
+/// warnings, as stored in the `escalation_rules` table.
+#[derive(Debug, FromRow)]
+pub struct EscalationRule {
+    pub warnings: i32,
+    /// The action, in the form given by [`Action::key`].
+    pub action: String,
+    /// How long, in seconds, a timeout lasts.
+    pub duration: Option<i64>
+}
+
+impl EscalationRule {
+    pub fn action(&self) -> Option<Action> {
+        Action::parse(&self.action)
+    }
+}
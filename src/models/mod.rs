@@ -1 +1,14 @@
+#[cfg(feature = "moderation")]
+pub mod infraction;
+pub mod profile;
+pub mod reminder;
+#[cfg(feature = "tmdb")]
 pub mod tmdb;
+#[cfg(feature = "extra")]
+pub mod tokei;
+#[cfg(feature = "twitter")]
+pub mod twitter;
+#[cfg(feature = "fun")]
+pub mod urban;
+#[cfg(feature = "fun")]
+pub mod xkcd;
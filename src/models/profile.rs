@@ -0,0 +1,347 @@
+use reqwest::Url;
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// A user's profile, as stored in the `profile_data` table. Fields the user
+/// hasn't set are `None`.
+#[derive(Debug, Default, FromRow)]
+pub struct Profile {
+    #[sqlx(rename = "user_name")]
+    pub name: Option<String>,
+    #[sqlx(rename = "user_location")]
+    pub location: Option<String>,
+    #[sqlx(rename = "user_gender")]
+    pub gender: Option<String>,
+    #[sqlx(rename = "user_pronouns")]
+    pub pronouns: Option<String>,
+    #[sqlx(rename = "user_lastfm_id")]
+    pub lastfm: Option<String>,
+    #[sqlx(rename = "user_bio")]
+    pub bio: Option<String>,
+    /// An IANA time zone, e.g. `Europe/London`.
+    #[sqlx(rename = "user_timezone")]
+    pub timezone: Option<String>,
+    /// Either `YYYY-MM-DD`, or `MM-DD` if the user left out the year.
+    #[sqlx(rename = "user_birthday")]
+    pub birthday: Option<String>
+}
+
+/// An account a user has linked from another service.
+#[derive(Debug, FromRow)]
+pub struct LinkedAccount {
+    /// The lowercase name of the service, e.g. `steam`.
+    pub service: String,
+    pub account: String
+}
+
+/// A service that linked accounts are shown with a proper name and, where the
+/// service has public profiles, a link for.
+pub struct KnownService {
+    pub key: &'static str,
+    pub name: &'static str,
+    /// The URL profiles are found under, with the account as the last path
+    /// segment.
+    pub profile_url: Option<&'static str>,
+    /// The longest account name the service allows.
+    pub max_length: usize,
+    /// The characters the service allows in account names besides ASCII
+    /// letters and numbers.
+    pub extra_chars: &'static str,
+    /// The characters allowed in account names, as shown to users.
+    pub allowed: &'static str
+}
+
+pub const KNOWN_SERVICES: &[KnownService] = &[
+    KnownService {
+        key: "github",
+        name: "GitHub",
+        profile_url: Some("https://github.com/"),
+        max_length: 39,
+        extra_chars: "-",
+        allowed: "letters, numbers and dashes"
+    },
+    KnownService {
+        key: "playstation",
+        name: "PlayStation",
+        profile_url: None,
+        max_length: 16,
+        extra_chars: "-_",
+        allowed: "letters, numbers, dashes and underscores"
+    },
+    KnownService {
+        key: "steam",
+        name: "Steam",
+        profile_url: Some("https://steamcommunity.com/id/"),
+        max_length: 32,
+        extra_chars: "-_",
+        allowed: "letters, numbers, dashes and underscores"
+    },
+    KnownService {
+        key: "twitch",
+        name: "Twitch",
+        profile_url: Some("https://www.twitch.tv/"),
+        max_length: 25,
+        extra_chars: "_",
+        allowed: "letters, numbers and underscores"
+    },
+    KnownService {
+        key: "twitter",
+        name: "Twitter",
+        profile_url: Some("https://twitter.com/"),
+        max_length: 15,
+        extra_chars: "_",
+        allowed: "letters, numbers and underscores"
+    },
+    KnownService {
+        key: "xbox",
+        name: "Xbox",
+        profile_url: None,
+        max_length: 15,
+        extra_chars: " ",
+        allowed: "letters, numbers and spaces"
+    }
+];
+
+/// A single field of a profile that users can set themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileField {
+    Name,
+    Location,
+    Gender,
+    Pronouns,
+    LastFm,
+    Bio,
+    Timezone,
+    Birthday
+}
+
+impl ProfileField {
+    pub const ALL: [ProfileField; 8] = [
+        ProfileField::Name,
+        ProfileField::Location,
+        ProfileField::Gender,
+        ProfileField::Pronouns,
+        ProfileField::Timezone,
+        ProfileField::Birthday,
+        ProfileField::LastFm,
+        ProfileField::Bio
+    ];
+
+    /// Parses the name users refer to a field by, e.g. in `profile set`.
+    pub fn parse(key: &str) -> Option<ProfileField> {
+        ProfileField::ALL.into_iter().find(|field| field.key().eq_ignore_ascii_case(key))
+    }
+
+    /// The name users refer to the field by.
+    pub fn key(self) -> &'static str {
+        match self {
+            ProfileField::Name => "name",
+            ProfileField::Location => "location",
+            ProfileField::Gender => "gender",
+            ProfileField::Pronouns => "pronouns",
+            ProfileField::LastFm => "lastfm",
+            ProfileField::Bio => "bio",
+            ProfileField::Timezone => "timezone",
+            ProfileField::Birthday => "birthday"
+        }
+    }
+
+    /// The name of the field as shown on a profile.
+    pub fn label(self) -> &'static str {
+        match self {
+            ProfileField::Name => "Name",
+            ProfileField::Location => "Location",
+            ProfileField::Gender => "Gender",
+            ProfileField::Pronouns => "Pronouns",
+            ProfileField::LastFm => "Last.fm",
+            ProfileField::Bio => "Bio",
+            ProfileField::Timezone => "Time Zone",
+            ProfileField::Birthday => "Birthday"
+        }
+    }
+}
+
+impl Profile {
+    /// Removes the value of the given field.
+    pub fn clear(&mut self, field: ProfileField) {
+        let value = match field {
+            ProfileField::Name => &mut self.name,
+            ProfileField::Location => &mut self.location,
+            ProfileField::Gender => &mut self.gender,
+            ProfileField::Pronouns => &mut self.pronouns,
+            ProfileField::LastFm => &mut self.lastfm,
+            ProfileField::Bio => &mut self.bio,
+            ProfileField::Timezone => &mut self.timezone,
+            ProfileField::Birthday => &mut self.birthday
+        };
+
+        *value = None;
+    }
+
+    /// The value of the given field, if it is set.
+    pub fn get(&self, field: ProfileField) -> Option<&str> {
+        let value = match field {
+            ProfileField::Name => &self.name,
+            ProfileField::Location => &self.location,
+            ProfileField::Gender => &self.gender,
+            ProfileField::Pronouns => &self.pronouns,
+            ProfileField::LastFm => &self.lastfm,
+            ProfileField::Bio => &self.bio,
+            ProfileField::Timezone => &self.timezone,
+            ProfileField::Birthday => &self.birthday
+        };
+
+        value.as_deref()
+    }
+}
+
+impl KnownService {
+    /// Whether the given account name is one the service would allow.
+    pub fn is_valid_account(&self, account: &str) -> bool {
+        account.chars().count() <= self.max_length && account.chars().all(|c| c.is_ascii_alphanumeric() || self.extra_chars.contains(c))
+    }
+}
+
+impl LinkedAccount {
+    /// The known service the account is from, if any.
+    pub fn known_service(&self) -> Option<&'static KnownService> {
+        KNOWN_SERVICES.iter().find(|service| service.key == self.service)
+    }
+
+    /// The name of the service the account is from.
+    pub fn service_name(&self) -> &str {
+        self.known_service().map_or(&self.service, |service| service.name)
+    }
+
+    /// A link to the account's profile, if the service has public profiles.
+    /// The account is percent-encoded, so it can't change where the link goes.
+    pub fn url(&self) -> Option<String> {
+        let mut url = Url::parse(self.known_service()?.profile_url?).ok()?;
+        url.path_segments_mut().ok()?.pop_if_empty().push(&self.account);
+        Some(url.into())
+    }
+}
+
+/// Who can see a profile field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Visibility {
+    /// Anyone.
+    #[default]
+    Public,
+    /// Members of a guild the user is in, from within that guild.
+    Guild,
+    /// The user alone.
+    Private
+}
+
+impl Visibility {
+    pub const ALL: [Visibility; 3] = [Visibility::Public, Visibility::Guild, Visibility::Private];
+
+    /// Parses the name users refer to a visibility by, which is also how it
+    /// is stored.
+    pub fn parse(key: &str) -> Option<Visibility> {
+        Visibility::ALL.into_iter().find(|visibility| visibility.key().eq_ignore_ascii_case(key))
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Guild => "guild",
+            Visibility::Private => "private"
+        }
+    }
+
+    /// Describes who can see a field with this visibility.
+    pub fn description(self) -> &'static str {
+        match self {
+            Visibility::Public => "Everyone",
+            Visibility::Guild => "Members of servers you're in",
+            Visibility::Private => "Only you"
+        }
+    }
+}
+
+/// Something on a profile that has its own visibility.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivacyTarget {
+    Field(ProfileField),
+    LinkedAccounts
+}
+
+impl PrivacyTarget {
+    /// Parses the name users refer to a target by, which is also how it is
+    /// stored.
+    pub fn parse(key: &str) -> Option<PrivacyTarget> {
+        if key.eq_ignore_ascii_case(PrivacyTarget::LinkedAccounts.key()) {
+            return Some(PrivacyTarget::LinkedAccounts);
+        }
+
+        ProfileField::parse(key).map(PrivacyTarget::Field)
+    }
+
+    /// Every target, in the order they are shown on a profile.
+    pub fn all() -> impl Iterator<Item = PrivacyTarget> {
+        ProfileField::ALL.into_iter().map(PrivacyTarget::Field).chain([PrivacyTarget::LinkedAccounts])
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            PrivacyTarget::Field(field) => field.key(),
+            PrivacyTarget::LinkedAccounts => "accounts"
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PrivacyTarget::Field(field) => field.label(),
+            PrivacyTarget::LinkedAccounts => "Linked Accounts"
+        }
+    }
+}
+
+/// The visibility a user has chosen for each part of their profile. Anything
+/// they haven't chosen one for is public.
+#[derive(Debug, Default)]
+pub struct PrivacySettings {
+    pub visibilities: HashMap<String, Visibility>
+}
+
+impl PrivacySettings {
+    pub fn visibility(&self, target: PrivacyTarget) -> Visibility {
+        self.visibilities.get(target.key()).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(service: &str, account: &str) -> LinkedAccount {
+        LinkedAccount {
+            service: service.to_string(),
+            account: account.to_string()
+        }
+    }
+
+    #[test]
+    fn url_links_to_the_profile() {
+        assert_eq!(account("steam", "gaben").url().as_deref(), Some("https://steamcommunity.com/id/gaben"));
+        assert_eq!(account("github", "octocat").url().as_deref(), Some("https://github.com/octocat"));
+        assert_eq!(account("xbox", "Major Nelson").url(), None);
+        assert_eq!(account("mastodon", "someone").url(), None);
+    }
+
+    #[test]
+    fn url_encodes_the_account() {
+        let url = account("twitter", "../evil?x=1#)").url().unwrap();
+        assert_eq!(url, "https://twitter.com/..%2Fevil%3Fx=1%23)");
+    }
+
+    #[test]
+    fn is_valid_account_follows_the_service_rules() {
+        let twitch = KNOWN_SERVICES.iter().find(|service| service.key == "twitch").unwrap();
+        assert!(twitch.is_valid_account("some_streamer"));
+        assert!(!twitch.is_valid_account("some-streamer"));
+        assert!(!twitch.is_valid_account(&"a".repeat(26)));
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use sqlx::FromRow;
+
+use crate::utils::time::{localize, next_weekday, parse_clock, parse_weekday, DEFAULT_HOUR};
+
+/// A reminder, as stored in the `reminders` table. Times are Unix timestamps.
+#[derive(Debug, FromRow)]
+pub struct Reminder {
+    pub id: i64,
+    pub user_id: i64,
+    pub channel_id: i64,
+    pub message: String,
+    pub due_at: i64,
+    pub created_at: i64,
+    /// How the reminder repeats, in the form given by [`Recurrence::key`].
+    pub recurrence: Option<String>,
+    pub attempts: i32
+}
+
+impl Reminder {
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        self.recurrence.as_deref().and_then(Recurrence::from_key)
+    }
+}
+
+/// How a recurring reminder repeats. Times are local to the user, so a
+/// reminder follows them if they change their time zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily(NaiveTime),
+    Weekly(Weekday, NaiveTime)
+}
+
+impl Recurrence {
+    /// Parses a recurrence such as `every monday 9am`, `every day at 17:30`
+    /// or `daily`. Reminders without a time of day go off at
+    /// [`DEFAULT_HOUR`].
+    pub fn parse(input: &str) -> Option<Recurrence> {
+        let input = input.trim().to_lowercase();
+        let words: Vec<&str> = input.split_whitespace().filter(|word| *word != "at").collect();
+        let (day, rest) = match words.as_slice() {
+            ["daily", rest @ ..] => (None, rest),
+            ["every", "day", rest @ ..] => (None, rest),
+            ["every", day, rest @ ..] => (Some(parse_weekday(day)?), rest),
+            _ => return None
+        };
+
+        let time = if rest.is_empty() {
+            NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0)?
+        } else {
+            parse_clock(&rest.join(" "))?
+        };
+
+        Some(match day {
+            Some(day) => Recurrence::Weekly(day, time),
+            None => Recurrence::Daily(time)
+        })
+    }
+
+    /// Reads a recurrence stored with [`Recurrence::key`].
+    pub fn from_key(key: &str) -> Option<Recurrence> {
+        match key.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["daily", time] => Some(Recurrence::Daily(NaiveTime::parse_from_str(time, "%H:%M").ok()?)),
+            ["weekly", day, time] => Some(Recurrence::Weekly(parse_weekday(day)?, NaiveTime::parse_from_str(time, "%H:%M").ok()?)),
+            _ => None
+        }
+    }
+
+    /// The form the recurrence is stored in, e.g. `weekly mon 09:00`.
+    pub fn key(self) -> String {
+        match self {
+            Recurrence::Daily(time) => format!("daily {}", time.format("%H:%M")),
+            Recurrence::Weekly(day, time) => format!("weekly {} {}", weekday_name(day)[..3].to_lowercase(), time.format("%H:%M"))
+        }
+    }
+
+    /// Describes the recurrence for users, e.g. `every Monday at 09:00`.
+    pub fn describe(self) -> String {
+        match self {
+            Recurrence::Daily(time) => format!("every day at {}", time.format("%H:%M")),
+            Recurrence::Weekly(day, time) => format!("every {} at {}", weekday_name(day), time.format("%H:%M"))
+        }
+    }
+
+    /// The first occurrence after the given time, in its time zone.
+    pub fn next_after(self, after: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let today = after.naive_local().date();
+        let (day, time) = match self {
+            Recurrence::Daily(time) => (today, time),
+            Recurrence::Weekly(day, time) if today.weekday() == day => (today, time),
+            Recurrence::Weekly(day, time) => (next_weekday(today, day), time)
+        };
+
+        let next = localize(after.timezone(), day.and_time(time))?;
+        if next > after {
+            return Some(next);
+        }
+
+        let day = match self {
+            Recurrence::Daily(_) => today + Duration::days(1),
+            Recurrence::Weekly(day, _) => next_weekday(today, day)
+        };
+
+        localize(after.timezone(), day.and_time(time))
+    }
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDateTime, TimeZone};
+    use chrono_tz::America::New_York;
+
+    fn local(datetime: &str) -> DateTime<Tz> {
+        New_York.from_local_datetime(&NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M").unwrap()).unwrap()
+    }
+
+    fn clock(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_and_stores_recurrences() {
+        assert_eq!(Recurrence::parse("every monday 9am"), Some(Recurrence::Weekly(Weekday::Mon, clock(9, 0))));
+        assert_eq!(Recurrence::parse("every day at 17:30"), Some(Recurrence::Daily(clock(17, 30))));
+        assert_eq!(Recurrence::parse("daily"), Some(Recurrence::Daily(clock(DEFAULT_HOUR, 0))));
+        assert_eq!(Recurrence::parse("every fortnight"), None);
+
+        let weekly = Recurrence::Weekly(Weekday::Thu, clock(12, 0));
+        assert_eq!(weekly.key(), "weekly thu 12:00");
+        assert_eq!(Recurrence::from_key(&weekly.key()), Some(weekly));
+    }
+
+    #[test]
+    fn daily_reminders_go_off_later_today_or_tomorrow() {
+        let daily = Recurrence::Daily(clock(9, 0));
+        assert_eq!(daily.next_after(local("2026-10-18 08:00")), Some(local("2026-10-18 09:00")));
+        assert_eq!(daily.next_after(local("2026-10-18 09:00")), Some(local("2026-10-19 09:00")));
+        assert_eq!(daily.next_after(local("2026-10-31 23:00")), Some(local("2026-11-01 09:00")));
+    }
+
+    #[test]
+    fn weekly_reminders_on_the_weekday_that_is_today() {
+        // 2026-10-18 is a Sunday.
+        let weekly = Recurrence::Weekly(Weekday::Sun, clock(9, 0));
+        assert_eq!(weekly.next_after(local("2026-10-18 08:00")), Some(local("2026-10-18 09:00")));
+        assert_eq!(weekly.next_after(local("2026-10-18 10:00")), Some(local("2026-10-25 09:00")));
+    }
+
+    #[test]
+    fn weekly_reminders_on_a_later_weekday() {
+        let weekly = Recurrence::Weekly(Weekday::Wed, clock(9, 0));
+        assert_eq!(weekly.next_after(local("2026-10-18 10:00")), Some(local("2026-10-21 09:00")));
+        assert_eq!(weekly.next_after(local("2026-10-29 10:00")), Some(local("2026-11-04 09:00")));
+    }
+
+    #[test]
+    fn reminders_skipped_by_daylight_saving_go_off_an_hour_later() {
+        let daily = Recurrence::Daily(clock(2, 30));
+        assert_eq!(daily.next_after(local("2026-03-07 03:00")), Some(local("2026-03-08 03:30")));
+        assert_eq!(daily.next_after(local("2026-03-08 03:30")), Some(local("2026-03-09 02:30")));
+    }
+}
@@ -0,0 +1,21 @@
+use chrono::prelude::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Collection {
+    pub id: u64,                     // The TMDb ID belonging to the collection.
+    pub name: String,                // The name of the collection.
+    pub overview: String,            // The overview of the collection.
+    pub poster_path: String,         // The poster belonging to the collection.
+    pub backdrop_path: String,       // The backdrop path of the collection.
+    pub parts: Vec<SimplifiedMovie>  // The movies part of the collection.
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SimplifiedMovie {
+    pub adult: bool,             // Whether or not the movie is marked as an adult film by TMDb.
+    pub id: u64,                 // The TMDb ID belonging to the movie.
+    pub overview: String,        // The overview of the movie.
+    pub release_date: NaiveDate, // The release date of the movie.
+    pub title: String            // The title of the movie.
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct Credits {
+    pub cast: Vec<CastMember>,
+    pub crew: Vec<CrewMember>,
+    pub id: i64
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CastMember {
+    pub character: String,
+    pub credit_id: String,
+    pub id: i64,
+    pub name: String,
+    pub gender: i64,
+    pub profile_path: Option<String>,
+    pub order: i64
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CrewMember {
+    pub credit_id: String,
+    pub department: String,
+    pub id: i64,
+    pub name: String,
+    pub gender: Option<i64>,
+    pub job: String,
+    pub profile_path: Option<String>
+}
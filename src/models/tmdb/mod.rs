@@ -1,2 +1,5 @@
+pub mod collection;
+pub mod credits;
 pub mod movie;
+pub mod search;
 pub mod show;
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SearchResponse<T> {
+    pub results: Vec<T>
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MovieResult {
+    pub id: u64,                      // The movie's The Movie Database identifier.
+    pub title: String,                // The title of the movie.
+    pub poster_path: Option<String>,  // The movie's poster path.
+    pub release_date: Option<String>  // The movie's release date, if known.
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShowResult {
+    pub id: i64,                        // The show's The Movie Database identifier.
+    pub name: String,                   // The name of the show.
+    pub first_air_date: Option<String>  // The date the show first aired, if known.
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CollectionResult {
+    pub id: u64,      // The collection's The Movie Database identifier.
+    pub name: String  // The name of the collection.
+}
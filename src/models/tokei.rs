@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Statistics {
+    pub languages: Vec<Language>,
+    pub total: Language
+}
+
+#[derive(Deserialize)]
+pub struct Language {
+    pub name: String,
+    pub files: u64,
+    pub lines: u64,
+    pub code: u64,
+    pub comments: u64,
+    pub blanks: u64
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct UrbanResponse {
+    #[serde(rename = "list")]
+    pub definitions: Vec<Definition>
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Definition {
+    #[serde(rename = "definition")]
+    pub description: String,
+    pub example: String,
+    pub word: String,
+    pub thumbs_up: usize,
+    pub thumbs_down: usize,
+    pub permalink: String
+}
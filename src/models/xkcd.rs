@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct XkcdComic {
+    pub num: u16,      // the numeric ID of the xkcd comic.
+    pub alt: String,   // the caption of the xkcd comic.
+    pub img: String,   // the image URL of the xkcd comic.
+    pub title: String  // the title of the xkcd comic.
+}
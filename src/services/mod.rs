@@ -0,0 +1,82 @@
+//! External Services
+//!
+//! Commands reach the APIs they request over HTTP through the traits in this
+//! module instead of building URLs themselves. The clients implementing them
+//! read their base URLs from the `api.urls` section of the configuration, so
+//! they can be pointed at a mirror or a local stub server, and go through the
+//! shared HTTP client and response cache.
+
+#[cfg(feature = "tmdb")]
+pub mod tmdb;
+#[cfg(feature = "extra")]
+pub mod tokei;
+#[cfg(feature = "twitter")]
+pub mod twitter;
+#[cfg(feature = "fun")]
+pub mod urban;
+#[cfg(feature = "fun")]
+pub mod xkcd;
+
+use serenity::client::Context;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::{
+    config::{ApiConfig, ConfigurationData},
+    data::ServicesContainer,
+    utils::{cache::ResponseCache, http::HttpClient}
+};
+
+/// The parts shared by every client. Cloning it is cheap.
+#[derive(Clone)]
+pub struct Backend {
+    pub http: HttpClient,
+    pub cache: Arc<ResponseCache>,
+    pub config: Arc<RwLock<ConfigurationData>>
+}
+
+impl Backend {
+    /// The API configuration as currently loaded, so that keys and base URLs
+    /// changed by a reload are picked up.
+    pub async fn api(&self) -> ApiConfig {
+        self.config.read().await.api.clone()
+    }
+}
+
+/// The client of every service compiled into this build.
+#[derive(Clone)]
+pub struct Services {
+    #[cfg(feature = "extra")]
+    pub code_counter: Arc<dyn tokei::CodeCounter>,
+    #[cfg(feature = "fun")]
+    pub comics: Arc<dyn xkcd::ComicSource>,
+    #[cfg(feature = "fun")]
+    pub dictionary: Arc<dyn urban::Dictionary>,
+    #[cfg(feature = "twitter")]
+    pub microblog: Arc<dyn twitter::Microblog>,
+    #[cfg(feature = "tmdb")]
+    pub movies: Arc<dyn tmdb::MovieDatabase>
+}
+
+impl Services {
+    /// Creates clients for the services at the configured base URLs.
+    pub fn new(backend: Backend) -> Services {
+        Services {
+            #[cfg(feature = "extra")]
+            code_counter: Arc::new(tokei::TokeiClient::new(backend.clone())),
+            #[cfg(feature = "fun")]
+            comics: Arc::new(xkcd::XkcdClient::new(backend.clone())),
+            #[cfg(feature = "fun")]
+            dictionary: Arc::new(urban::UrbanDictionaryClient::new(backend.clone())),
+            #[cfg(feature = "twitter")]
+            microblog: Arc::new(twitter::TwitterClient::new(backend.clone())),
+            #[cfg(feature = "tmdb")]
+            movies: Arc::new(tmdb::TmdbClient::new(backend))
+        }
+    }
+}
+
+/// Retrieves the service clients from the client's data.
+pub async fn get_services(context: &Context) -> Services {
+    context.data.read().await.get::<ServicesContainer>().cloned().unwrap()
+}
@@ -0,0 +1,136 @@
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Serialize};
+use serenity::async_trait;
+
+use super::Backend;
+use crate::{
+    config::Integration,
+    error::TaliyahError,
+    models::tmdb::{collection::Collection, credits::Credits, movie::Movie, search::*, show::Show},
+    utils::{cache::Endpoint, http::Service}
+};
+
+/// A source of information about movies and television series.
+#[async_trait]
+pub trait MovieDatabase: Send + Sync {
+    /// Searches for movies, optionally only those released in the given year.
+    async fn search_movies(&self, query: &str, year: Option<&str>) -> Result<Vec<MovieResult>, TaliyahError>;
+
+    /// Searches for series, optionally only those first aired in the given year.
+    async fn search_shows(&self, query: &str, year: Option<&str>) -> Result<Vec<ShowResult>, TaliyahError>;
+
+    async fn search_collections(&self, query: &str) -> Result<Vec<CollectionResult>, TaliyahError>;
+
+    async fn movie(&self, id: u64) -> Result<Movie, TaliyahError>;
+
+    /// Retrieves a series, including its external IDs.
+    async fn show(&self, id: i64) -> Result<Show, TaliyahError>;
+
+    async fn collection(&self, id: u64) -> Result<Collection, TaliyahError>;
+
+    async fn movie_credits(&self, id: u64) -> Result<Credits, TaliyahError>;
+
+    async fn show_credits(&self, id: i64) -> Result<Credits, TaliyahError>;
+}
+
+/// The Movie Database's API.
+pub struct TmdbClient {
+    backend: Backend
+}
+
+impl TmdbClient {
+    pub fn new(backend: Backend) -> TmdbClient {
+        TmdbClient { backend }
+    }
+
+    /// Requests the given path, going through the response cache.
+    async fn fetch<T>(&self, path: &str, query: &[(&str, &str)]) -> Result<T, TaliyahError>
+    where
+        T: Serialize + DeserializeOwned
+    {
+        let api = self.backend.api().await;
+        let api_key = api.tmdb().ok_or_else(|| Integration::Tmdb.unavailable_message())?;
+
+        let key = format!("{path}?{}", query.iter().map(|(name, value)| format!("{name}={value}")).join("&"));
+        let request = self
+            .backend
+            .http
+            .get(Service::Tmdb, format!("{}/{path}", api.urls.tmdb))
+            .query(&[("api_key", api_key)])
+            .query(query);
+        let fetch = async { request.send().await?.json::<T>().await };
+
+        self.backend.cache.get_or_fetch(Endpoint::Tmdb, &key, fetch).await.map_err(TaliyahError::Tmdb)
+    }
+}
+
+#[async_trait]
+impl MovieDatabase for TmdbClient {
+    async fn search_movies(&self, query: &str, year: Option<&str>) -> Result<Vec<MovieResult>, TaliyahError> {
+        let mut parameters = vec![("query", query)];
+        parameters.extend(year.map(|year| ("year", year)));
+
+        let response: SearchResponse<MovieResult> = self.fetch("search/movie", &parameters).await?;
+        Ok(response.results)
+    }
+
+    async fn search_shows(&self, query: &str, year: Option<&str>) -> Result<Vec<ShowResult>, TaliyahError> {
+        let mut parameters = vec![("query", query)];
+        parameters.extend(year.map(|year| ("first_air_date_year", year)));
+
+        let response: SearchResponse<ShowResult> = self.fetch("search/tv", &parameters).await?;
+        Ok(response.results)
+    }
+
+    async fn search_collections(&self, query: &str) -> Result<Vec<CollectionResult>, TaliyahError> {
+        let response: SearchResponse<CollectionResult> = self.fetch("search/collection", &[("query", query)]).await?;
+        Ok(response.results)
+    }
+
+    async fn movie(&self, id: u64) -> Result<Movie, TaliyahError> {
+        self.fetch(&format!("movie/{id}"), &[]).await
+    }
+
+    async fn show(&self, id: i64) -> Result<Show, TaliyahError> {
+        self.fetch(&format!("tv/{id}"), &[("append_to_response", "external_ids")]).await
+    }
+
+    async fn collection(&self, id: u64) -> Result<Collection, TaliyahError> {
+        self.fetch(&format!("collection/{id}"), &[]).await
+    }
+
+    async fn movie_credits(&self, id: u64) -> Result<Credits, TaliyahError> {
+        self.fetch(&format!("movie/{id}/credits"), &[]).await
+    }
+
+    async fn show_credits(&self, id: i64) -> Result<Credits, TaliyahError> {
+        self.fetch(&format!("tv/{id}/credits"), &[]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{backend, StubServer};
+
+    #[tokio::test]
+    async fn requests_movies_with_the_api_key() {
+        let server = StubServer::start(&[("/search/movie", "movie_search"), ("/movie/603", "movie"), ("/movie/603/credits", "credits")]).await;
+        let client = TmdbClient::new(backend(&server));
+
+        let results = client.search_movies("The Matrix", Some("1999")).await.unwrap();
+        assert_eq!(results[0].id, 603);
+        assert_eq!(client.movie(603).await.unwrap().title, "The Matrix");
+        assert_eq!(client.movie_credits(603).await.unwrap().cast.len(), 15);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|request| request.query("api_key").as_deref() == Some("tmdb-key")));
+        assert_eq!(requests[0].query("query").as_deref(), Some("The Matrix"));
+        assert_eq!(requests[0].query("year").as_deref(), Some("1999"));
+
+        // Repeated lookups are answered from the cache.
+        client.movie(603).await.unwrap();
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+use serenity::async_trait;
+
+use super::Backend;
+use crate::{error::TaliyahError, models::tokei::Statistics, utils::http::Service};
+
+/// A source of code statistics for hosted repositories.
+#[async_trait]
+pub trait CodeCounter: Send + Sync {
+    /// Counts the lines of code in the given GitHub repository.
+    async fn statistics(&self, owner: &str, name: &str) -> Result<Statistics, TaliyahError>;
+}
+
+/// The tokei web service.
+pub struct TokeiClient {
+    backend: Backend
+}
+
+impl TokeiClient {
+    pub fn new(backend: Backend) -> TokeiClient {
+        TokeiClient { backend }
+    }
+}
+
+#[async_trait]
+impl CodeCounter for TokeiClient {
+    async fn statistics(&self, owner: &str, name: &str) -> Result<Statistics, TaliyahError> {
+        let url = format!("{}/{owner}/{name}", self.backend.api().await.urls.tokei);
+        Ok(self.backend.http.get(Service::Tokei, url).send().await?.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{backend, StubServer};
+
+    #[tokio::test]
+    async fn counts_the_repository() {
+        let server = StubServer::start(&[("/Taliyah-Bot/Taliyah", "tokei")]).await;
+        let client = TokeiClient::new(backend(&server));
+
+        let statistics = client.statistics("Taliyah-Bot", "Taliyah").await.unwrap();
+        assert_eq!(statistics.languages[0].name, "Rust");
+        assert_eq!(statistics.total.code, 6200);
+    }
+}
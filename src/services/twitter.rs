@@ -0,0 +1,96 @@
+use serenity::async_trait;
+
+use super::Backend;
+use crate::{
+    config::Integration,
+    error::TaliyahError,
+    models::twitter::{User, UserData, UserTweet, UserTweets},
+    utils::http::Service
+};
+
+/// A source of social media profiles and their posts.
+#[async_trait]
+pub trait Microblog: Send + Sync {
+    /// Looks up a user by their handle.
+    async fn user(&self, username: &str) -> Result<UserData, TaliyahError>;
+
+    /// Retrieves the latest posts of the user with the given ID, leaving out
+    /// reposts and replies.
+    async fn tweets(&self, id: &str) -> Result<Vec<UserTweet>, TaliyahError>;
+}
+
+/// Version 2 of Twitter's API.
+pub struct TwitterClient {
+    backend: Backend
+}
+
+impl TwitterClient {
+    pub fn new(backend: Backend) -> TwitterClient {
+        TwitterClient { backend }
+    }
+}
+
+#[async_trait]
+impl Microblog for TwitterClient {
+    async fn user(&self, username: &str) -> Result<UserData, TaliyahError> {
+        let user_fields = [("user.fields", "created_at,protected,location,public_metrics,description,verified,profile_image_url")];
+
+        let api = self.backend.api().await;
+        let bearer = api.twitter().ok_or_else(|| Integration::Twitter.unavailable_message())?;
+        let request = self
+            .backend
+            .http
+            .get(Service::Twitter, format!("{}/users/by/username/{username}", api.urls.twitter))
+            .bearer_auth(bearer)
+            .query(&user_fields)
+            .send()
+            .await
+            .map_err(TaliyahError::Twitter)?;
+
+        Ok(request.json::<User>().await.map_err(TaliyahError::Twitter)?.data)
+    }
+
+    async fn tweets(&self, id: &str) -> Result<Vec<UserTweet>, TaliyahError> {
+        let tweet_fields = [("max_results", "5"), ("exclude", "retweets,replies")];
+
+        let api = self.backend.api().await;
+        let bearer = api.twitter().ok_or_else(|| Integration::Twitter.unavailable_message())?;
+        let request = self
+            .backend
+            .http
+            .get(Service::Twitter, format!("{}/users/{id}/tweets", api.urls.twitter))
+            .bearer_auth(bearer)
+            .query(&tweet_fields)
+            .send()
+            .await
+            .map_err(TaliyahError::Twitter)?;
+
+        Ok(request.json::<UserTweets>().await.map_err(TaliyahError::Twitter)?.data.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{backend, StubServer};
+
+    #[tokio::test]
+    async fn looks_up_users_and_their_tweets_with_the_bearer_token() {
+        let server = StubServer::start(&[("/users/by/username/Twitter", "twitter_user"), ("/users/783214/tweets", "twitter_tweets")]).await;
+        let client = TwitterClient::new(backend(&server));
+
+        let user = client.user("Twitter").await.unwrap();
+        assert_eq!(user.id, "783214");
+        assert_eq!(user.public_metrics.followers_count, 65_000_000);
+
+        let tweets = client.tweets(&user.id).await.unwrap();
+        assert_eq!(tweets.len(), 2);
+        assert_eq!(tweets[0].text, "a hit Tweet");
+
+        let requests = server.requests();
+        assert!(requests
+            .iter()
+            .all(|request| request.headers.get("authorization").map(String::as_str) == Some("Bearer bearer-token")));
+        assert_eq!(requests[1].query("exclude").as_deref(), Some("retweets,replies"));
+    }
+}
@@ -0,0 +1,69 @@
+use serenity::async_trait;
+
+use super::Backend;
+use crate::{
+    error::TaliyahError,
+    models::urban::{Definition, UrbanResponse},
+    utils::{cache::Endpoint, http::Service}
+};
+
+/// A source of definitions for slang words and phrases.
+#[async_trait]
+pub trait Dictionary: Send + Sync {
+    /// Looks up the definitions of the given term, in the order they are ranked.
+    async fn define(&self, term: &str) -> Result<Vec<Definition>, TaliyahError>;
+
+    /// Retrieves definitions of random terms.
+    async fn random(&self) -> Result<Vec<Definition>, TaliyahError>;
+}
+
+/// The Urban Dictionary's API.
+pub struct UrbanDictionaryClient {
+    backend: Backend
+}
+
+impl UrbanDictionaryClient {
+    pub fn new(backend: Backend) -> UrbanDictionaryClient {
+        UrbanDictionaryClient { backend }
+    }
+}
+
+#[async_trait]
+impl Dictionary for UrbanDictionaryClient {
+    async fn define(&self, term: &str) -> Result<Vec<Definition>, TaliyahError> {
+        let url = format!("{}/define", self.backend.api().await.urls.urban_dictionary);
+        let fetch = async {
+            let request = self.backend.http.get(Service::UrbanDictionary, url).query(&[("term", term)]).send().await?;
+            request.json::<UrbanResponse>().await
+        };
+
+        let response = self.backend.cache.get_or_fetch(Endpoint::UrbanDictionary, term, fetch).await?;
+        Ok(response.definitions)
+    }
+
+    async fn random(&self) -> Result<Vec<Definition>, TaliyahError> {
+        let url = format!("{}/random", self.backend.api().await.urls.urban_dictionary);
+        let response: UrbanResponse = self.backend.http.get(Service::UrbanDictionary, url).send().await?.json().await?;
+        Ok(response.definitions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{backend, StubServer};
+
+    #[tokio::test]
+    async fn looks_up_the_term() {
+        let server = StubServer::start(&[("/define", "urban")]).await;
+        let client = UrbanDictionaryClient::new(backend(&server));
+
+        let definitions = client.define("yeet").await.unwrap();
+        assert_eq!(definitions[0].word, "yeet");
+        assert_eq!(definitions[0].thumbs_up, 120);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].query("term").as_deref(), Some("yeet"));
+    }
+}
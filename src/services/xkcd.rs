@@ -0,0 +1,77 @@
+use reqwest::StatusCode;
+use serenity::async_trait;
+
+use super::Backend;
+use crate::{
+    error::TaliyahError,
+    models::xkcd::XkcdComic,
+    utils::{cache::Endpoint, http::Service}
+};
+
+/// A source of numbered comics.
+#[async_trait]
+pub trait ComicSource: Send + Sync {
+    /// Retrieves the comic with the given number, or the latest comic if no
+    /// number is given. Returns `None` if there is no such comic.
+    async fn comic(&self, number: Option<u16>) -> Result<Option<XkcdComic>, TaliyahError>;
+}
+
+/// The JSON interface of xkcd.
+pub struct XkcdClient {
+    backend: Backend
+}
+
+impl XkcdClient {
+    pub fn new(backend: Backend) -> XkcdClient {
+        XkcdClient { backend }
+    }
+}
+
+#[async_trait]
+impl ComicSource for XkcdClient {
+    async fn comic(&self, number: Option<u16>) -> Result<Option<XkcdComic>, TaliyahError> {
+        let base = self.backend.api().await.urls.xkcd;
+        let url = match number {
+            Some(number) => format!("{base}/{number}/info.0.json"),
+            None => format!("{base}/info.0.json")
+        };
+
+        let fetch = async {
+            let request = self.backend.http.get(Service::Xkcd, url).send().await?;
+            if request.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            request.json::<XkcdComic>().await.map(Some)
+        };
+
+        // The latest comic changes, so only numbered comics are cached, and
+        // only once they are out.
+        let comic = match number {
+            Some(number) => self.backend.cache.get_or_fetch_found(Endpoint::Xkcd, &number.to_string(), fetch).await?,
+            None => fetch.await?
+        };
+
+        Ok(comic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{backend, StubServer};
+
+    #[tokio::test]
+    async fn finds_numbered_comics_and_retries_missing_ones() {
+        let server = StubServer::start(&[("/1/info.0.json", "xkcd")]).await;
+        let client = XkcdClient::new(backend(&server));
+
+        assert_eq!(client.comic(Some(1)).await.unwrap().unwrap().title, "Barrel - Part 1");
+        assert!(client.comic(Some(9999)).await.unwrap().is_none());
+        assert!(client.comic(Some(9999)).await.unwrap().is_none());
+
+        // A comic that isn't out yet is requested again rather than cached.
+        let targets: Vec<String> = server.requests().into_iter().map(|request| request.target).collect();
+        assert_eq!(targets, ["/1/info.0.json", "/9999/info.0.json", "/9999/info.0.json"]);
+    }
+}
@@ -0,0 +1,192 @@
+//! Test helpers
+//!
+//! Embed builders are tested against the JSON fixtures in `tests/fixtures`,
+//! which are trimmed copies of real API responses. The service clients are
+//! tested against a [`StubServer`] that serves those fixtures, with the base
+//! URLs in `api.urls` pointed at it.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, Mutex}
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::RwLock
+};
+
+use crate::{
+    config::ConfigurationData,
+    services::Backend,
+    utils::{cache::ResponseCache, http::HttpClient}
+};
+
+/// A configuration with every required key and every integration configured.
+/// Failed requests aren't retried, so tests of them finish right away.
+const CONFIG: &str = r#"
+[bot.general]
+codename = "taliyah"
+prefix = "x;"
+
+[bot.database]
+url = "postgres://taliyah@localhost/taliyah"
+
+[bot.discord]
+appid = 1
+token = "token"
+
+[bot.denylist.spotify]
+ids = []
+
+[bot.logging]
+enabled = false
+level = "info"
+
+[bot.http]
+retries = 0
+
+[api.entertainment]
+tmdb = "tmdb-key"
+
+[api.social.twitter.core]
+api_key = "key"
+api_key_secret = "secret"
+bearer_token = "bearer-token"
+access_token = "token"
+access_token_secret = "secret"
+
+[api.social.twitter.client]
+client_id = "id"
+client_secret = "secret"
+"#;
+
+/// Reads the fixture with the given name from `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("Unable to read {path}: {err}"))
+}
+
+/// Deserializes the fixture with the given name.
+pub fn parse_fixture<T: DeserializeOwned>(name: &str) -> T {
+    serde_json::from_str(&fixture(name)).unwrap_or_else(|err| panic!("Unable to parse the {name} fixture: {err}"))
+}
+
+/// Serializes a builder into the JSON sent to Discord, so that its contents
+/// can be inspected.
+pub fn to_json(builder: &impl Serialize) -> Value {
+    serde_json::to_value(builder).unwrap()
+}
+
+/// The value of the embed field with the given name.
+pub fn field<'a>(embed: &'a Value, name: &str) -> &'a str {
+    let fields = embed["fields"].as_array().expect("The embed has no fields.");
+    let field = fields.iter().find(|field| field["name"] == name).unwrap_or_else(|| panic!("The embed has no {name} field."));
+    field["value"].as_str().unwrap()
+}
+
+/// A request received by a [`StubServer`].
+pub struct StubRequest {
+    /// The path and query string.
+    pub target: String,
+    /// The headers, with lowercase names.
+    pub headers: HashMap<String, String>
+}
+
+impl StubRequest {
+    /// The decoded value of the given query parameter.
+    pub fn query(&self, name: &str) -> Option<String> {
+        let (_, query) = self.target.split_once('?')?;
+        reqwest::Url::parse(&format!("http://localhost/?{query}"))
+            .ok()?
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+}
+
+/// A local HTTP server answering each known path with a fixture and anything
+/// else with a 404, recording every request it receives.
+pub struct StubServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<StubRequest>>>
+}
+
+impl StubServer {
+    /// Starts serving the given fixtures, keyed by path, on a free port.
+    pub async fn start(routes: &[(&str, &str)]) -> StubServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Arc<HashMap<String, String>> = Arc::new(routes.iter().map(|(path, name)| (path.to_string(), fixture(name))).collect());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(respond(stream, Arc::clone(&routes), Arc::clone(&recorded)));
+            }
+        });
+
+        StubServer { url, requests }
+    }
+
+    /// Takes the requests received so far, in the order they arrived.
+    pub fn requests(&self) -> Vec<StubRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+async fn respond(mut stream: TcpStream, routes: Arc<HashMap<String, String>>, requests: Arc<Mutex<Vec<StubRequest>>>) {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => head.extend_from_slice(&buffer[..read])
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let target = lines.next().and_then(|line| line.split(' ').nth(1)).unwrap_or_default().to_string();
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let path = target.split('?').next().unwrap_or_default();
+    let (status, body) = match routes.get(path) {
+        Some(body) => ("200 OK", body.as_str()),
+        None => ("404 Not Found", "{}")
+    };
+
+    requests.lock().unwrap().push(StubRequest { target, headers });
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// A backend requesting every service from the given stub server. The
+/// response cache is kept in memory only, so no database is needed.
+pub fn backend(server: &StubServer) -> Backend {
+    let mut config: ConfigurationData = toml::from_str(CONFIG).unwrap();
+    let urls = &mut config.api.urls;
+    for url in [&mut urls.tmdb, &mut urls.tokei, &mut urls.twitter, &mut urls.urban_dictionary, &mut urls.xkcd] {
+        url.clone_from(&server.url);
+    }
+
+    let pool = PgPoolOptions::new().connect_lazy(&config.bot.database.url).unwrap();
+    Backend {
+        http: HttpClient::new(&config.bot.http).unwrap(),
+        cache: Arc::new(ResponseCache::new(&config.bot.cache, &pool)),
+        config: Arc::new(RwLock::new(config))
+    }
+}
@@ -0,0 +1,286 @@
+//! Response Cache
+//!
+//! Lookups against external APIs are cached for a time that depends on the
+//! endpoint, so repeating a command doesn't repeat its requests. Entries are
+//! kept in memory and evicted least-recently-used once the cache is full. If
+//! persistence is enabled, they are also written to the `response_cache`
+//! table, letting them survive restarts.
+//!
+//! Values are stored as JSON, so anything cached has to round-trip through
+//! serde.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serenity::client::Context;
+use sqlx::{PgPool, Row};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex
+    },
+    time::{Duration, Instant}
+};
+use tracing::warn;
+
+use crate::{config::CacheConfig, data::ResponseCacheContainer};
+
+/// A group of lookups that share a time to live.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    #[cfg(feature = "music-lastfm")]
+    LastFmProfile,
+    #[cfg(feature = "music-spotify")]
+    SpotifyAlbum,
+    #[cfg(feature = "music-spotify")]
+    SpotifyArtwork,
+    #[cfg(feature = "tmdb")]
+    Tmdb,
+    #[cfg(feature = "fun")]
+    UrbanDictionary,
+    #[cfg(feature = "fun")]
+    Xkcd
+}
+
+impl Endpoint {
+    /// The name used for the endpoint in the configuration and cache keys.
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "music-lastfm")]
+            Endpoint::LastFmProfile => "lastfm_profile",
+            #[cfg(feature = "music-spotify")]
+            Endpoint::SpotifyAlbum => "spotify_album",
+            #[cfg(feature = "music-spotify")]
+            Endpoint::SpotifyArtwork => "spotify_artwork",
+            #[cfg(feature = "tmdb")]
+            Endpoint::Tmdb => "tmdb",
+            #[cfg(feature = "fun")]
+            Endpoint::UrbanDictionary => "urban",
+            #[cfg(feature = "fun")]
+            Endpoint::Xkcd => "xkcd"
+        }
+    }
+
+    /// How long, in seconds, lookups are cached unless configured otherwise.
+    fn default_ttl(self) -> u64 {
+        match self {
+            #[cfg(feature = "music-lastfm")]
+            Endpoint::LastFmProfile => 300,
+            #[cfg(feature = "music-spotify")]
+            Endpoint::SpotifyAlbum => 86400,
+            #[cfg(feature = "music-spotify")]
+            Endpoint::SpotifyArtwork => 86400,
+            #[cfg(feature = "tmdb")]
+            Endpoint::Tmdb => 86400,
+            #[cfg(feature = "fun")]
+            Endpoint::UrbanDictionary => 3600,
+            #[cfg(feature = "fun")]
+            Endpoint::Xkcd => 86400
+        }
+    }
+}
+
+/// Hit and miss counts since the bot started or the cache was last flushed.
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64
+}
+
+struct Entry {
+    value: String,
+    expires: Instant,
+    last_used: u64
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<String, Entry>,
+    clock: u64
+}
+
+pub struct ResponseCache {
+    config: CacheConfig,
+    pool: Option<PgPool>,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64
+}
+
+impl ResponseCache {
+    /// Creates an empty cache. The pool is only used if persistence is enabled.
+    pub fn new(config: &CacheConfig, pool: &PgPool) -> ResponseCache {
+        ResponseCache {
+            config: config.clone(),
+            pool: config.persist.then(|| pool.clone()),
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0)
+        }
+    }
+
+    /// Returns the cached value for the given lookup, or runs `fetch` and
+    /// caches its result. Errors are never cached.
+    pub async fn get_or_fetch<T, E>(&self, endpoint: Endpoint, key: &str, fetch: impl Future<Output = Result<T, E>>) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned
+    {
+        self.fetch_cached(endpoint, key, fetch, |_| true).await
+    }
+
+    /// Like [`ResponseCache::get_or_fetch`], but only caches values that were
+    /// found, so that looking up something that doesn't exist yet is retried
+    /// the next time.
+    pub async fn get_or_fetch_found<T, E>(&self, endpoint: Endpoint, key: &str, fetch: impl Future<Output = Result<Option<T>, E>>) -> Result<Option<T>, E>
+    where
+        T: Serialize + DeserializeOwned
+    {
+        self.fetch_cached(endpoint, key, fetch, Option::is_some).await
+    }
+
+    async fn fetch_cached<T, E>(&self, endpoint: Endpoint, key: &str, fetch: impl Future<Output = Result<T, E>>, cacheable: impl Fn(&T) -> bool) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned
+    {
+        if !self.config.enabled {
+            return fetch.await;
+        }
+
+        let key = format!("{}:{}", endpoint.name(), key.to_lowercase());
+        if let Some(value) = self.get(&key).await.and_then(|value| serde_json::from_str(&value).ok()) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = fetch.await?;
+        if !cacheable(&value) {
+            return Ok(value);
+        }
+
+        match serde_json::to_string(&value) {
+            Ok(json) => self.insert(key, json, self.ttl(endpoint)).await,
+            Err(why) => warn!("Unable to cache {key}: {why}")
+        }
+
+        Ok(value)
+    }
+
+    /// Removes every entry, returning how many were held in memory.
+    pub async fn flush(&self) -> Result<usize, sqlx::Error> {
+        let removed = {
+            let mut entries = self.entries.lock().unwrap();
+            let removed = entries.map.len();
+            entries.map.clear();
+            removed
+        };
+
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+
+        if let Some(pool) = &self.pool {
+            sqlx::query("DELETE FROM response_cache").execute(pool).await?;
+        }
+
+        Ok(removed)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.lock().unwrap().map.len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed)
+        }
+    }
+
+    fn ttl(&self, endpoint: Endpoint) -> Duration {
+        Duration::from_secs(self.config.ttl.get(endpoint.name()).copied().unwrap_or_else(|| endpoint.default_ttl()))
+    }
+
+    async fn get(&self, key: &str) -> Option<String> {
+        if let Some(value) = self.get_memory(key) {
+            return Some(value);
+        }
+
+        let pool = self.pool.as_ref()?;
+        let query = "SELECT value, CAST(EXTRACT(EPOCH FROM expires_at - now()) AS BIGINT) FROM response_cache WHERE key = $1 AND expires_at > now()";
+        let row = match sqlx::query(query).bind(key).fetch_optional(pool).await {
+            Ok(row) => row?,
+            Err(why) => {
+                warn!("Unable to read {key} from the response cache: {why}");
+                return None;
+            }
+        };
+
+        let value: String = row.try_get(0).ok()?;
+        let remaining: i64 = row.try_get(1).ok()?;
+        self.insert_memory(key.to_string(), value.clone(), Duration::from_secs(remaining.max(0) as u64));
+        Some(value)
+    }
+
+    fn get_memory(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clock += 1;
+        let clock = entries.clock;
+
+        let entry = entries.map.get_mut(key)?;
+        if entry.expires <= Instant::now() {
+            entries.map.remove(key);
+            return None;
+        }
+
+        entry.last_used = clock;
+        Some(entry.value.clone())
+    }
+
+    async fn insert(&self, key: String, value: String, ttl: Duration) {
+        if let Some(pool) = &self.pool {
+            let query = "INSERT INTO response_cache (key, value, expires_at) VALUES ($1, $2, now() + make_interval(secs => $3))
+                         ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at";
+            if let Err(why) = sqlx::query(query).bind(&key).bind(&value).bind(ttl.as_secs_f64()).execute(pool).await {
+                warn!("Unable to write {key} to the response cache: {why}");
+            }
+
+            if let Err(why) = sqlx::query("DELETE FROM response_cache WHERE expires_at <= now()").execute(pool).await {
+                warn!("Unable to remove expired entries from the response cache: {why}");
+            }
+        }
+
+        self.insert_memory(key, value, ttl);
+    }
+
+    fn insert_memory(&self, key: String, value: String, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+
+        if entries.map.len() >= self.config.capacity && !entries.map.contains_key(&key) {
+            entries.map.retain(|_, entry| entry.expires > now);
+        }
+
+        // Scanning for the least recently used entry is linear, which is fine
+        // for the few thousand entries the cache is meant to hold.
+        while entries.map.len() >= self.config.capacity.max(1) && !entries.map.contains_key(&key) {
+            let oldest = entries.map.iter().min_by_key(|(_, entry)| entry.last_used).map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => entries.map.remove(&oldest),
+                None => break
+            };
+        }
+
+        entries.clock += 1;
+        let last_used = entries.clock;
+        entries.map.insert(
+            key,
+            Entry {
+                value,
+                expires: now + ttl,
+                last_used
+            }
+        );
+    }
+}
+
+/// Retrieves the response cache from the client's data.
+pub async fn get_cache(context: &Context) -> Arc<ResponseCache> {
+    context.data.read().await.get::<ResponseCacheContainer>().cloned().unwrap()
+}
@@ -0,0 +1,240 @@
+//! Configuration Utilities
+//!
+//! The configuration is read once on startup and shared through the
+//! `ConfigContainer`. It is reloaded when the process receives `SIGHUP` or
+//! when the configuration file changes on disk. A configuration that fails
+//! to parse or validate is rejected, and the one already loaded stays in use.
+//! Clients built from credentials in the configuration, such as the Spotify
+//! client, are rebuilt after every reload.
+//!
+//! Any value in the configuration file can be overridden by an environment
+//! variable named after its path, prefixed with `TALIYAH_` and with the
+//! sections separated by double underscores, e.g. `TALIYAH_BOT__DISCORD__TOKEN`
+//! for `bot.discord.token`. Appending `_FILE` to the name reads the value from
+//! the file the variable points to instead, which is useful for secrets
+//! mounted into containers.
+
+#[cfg(feature = "music-spotify")]
+use aspotify::{Client as SpotifyClient, ClientCredentials};
+use serenity::{client::Context, prelude::TypeMap};
+use std::{env, fs, path::Path, sync::Arc, time::SystemTime};
+use tokio::{
+    sync::RwLock,
+    time::{interval, Duration}
+};
+use toml::{value::Table, Value};
+use tracing::{error, info, warn};
+
+use super::read_config;
+#[cfg(feature = "music-spotify")]
+use crate::data::SpotifyContainer;
+use crate::{
+    config::{ConfigurationData, REQUIRED_KEYS},
+    constants::CONFIG_FILE,
+    data::ConfigContainer
+};
+
+/// How often the configuration file is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The prefix of environment variables that override configuration values.
+const ENV_PREFIX: &str = "TALIYAH_";
+
+/// The environment variable holding the path of the configuration file.
+pub const CONFIG_PATH_VAR: &str = "TALIYAH_CONFIG";
+
+/// Returns the path of the configuration file, which can be changed through
+/// the `TALIYAH_CONFIG` environment variable.
+pub fn config_path() -> String {
+    env::var(CONFIG_PATH_VAR).unwrap_or_else(|_| CONFIG_FILE.to_string())
+}
+
+/// Applies every `TALIYAH_` environment variable to the given configuration.
+pub fn apply_env_overrides(config: &mut Value) -> Result<(), String> {
+    for (key, raw) in env::vars() {
+        if key == CONFIG_PATH_VAR {
+            continue;
+        }
+
+        let name = match key.strip_prefix(ENV_PREFIX) {
+            Some(name) => name,
+            None => continue
+        };
+
+        let (name, raw) = match name.strip_suffix("_FILE") {
+            Some(name) => {
+                let contents = fs::read_to_string(&raw).map_err(|err| format!("Unable to read {raw} for {key}: {err}"))?;
+                (name, contents.trim_end_matches(['\r', '\n']).to_string())
+            }
+            None => (name, raw)
+        };
+
+        let path = name.split("__").map(str::to_lowercase).collect::<Vec<String>>();
+        set_value(config, &path, raw).map_err(|err| format!("Unable to apply {key}: {err}"))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the required keys that are missing from the given configuration.
+pub fn missing_keys(config: &Value) -> Vec<&'static str> {
+    REQUIRED_KEYS
+        .iter()
+        .copied()
+        .filter(|key| key.split('.').try_fold(config, |value, section| value.get(section)).is_none())
+        .collect()
+}
+
+fn set_value(config: &mut Value, path: &[String], raw: String) -> Result<(), String> {
+    let (field, sections) = path.split_last().ok_or("No configuration value given.")?;
+    let mut table = config.as_table_mut().ok_or("The configuration is not a table.")?;
+    for section in sections {
+        table = table
+            .entry(section.as_str())
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| format!("{section} is not a section."))?;
+    }
+
+    let value = parse_value(table.get(field), raw)?;
+    table.insert(field.to_string(), value);
+
+    Ok(())
+}
+
+/// Parses a raw environment value, using the type of the value it replaces
+/// if there is one. Values without a counterpart in the configuration file
+/// are parsed as TOML, and treated as strings if that fails.
+fn parse_value(existing: Option<&Value>, raw: String) -> Result<Value, String> {
+    match existing {
+        Some(Value::String(_)) => Ok(Value::String(raw)),
+        Some(Value::Integer(_)) => raw.trim().parse().map(Value::Integer).map_err(|err| format!("{raw} is not an integer: {err}")),
+        Some(Value::Boolean(_)) => raw.trim().parse().map(Value::Boolean).map_err(|err| format!("{raw} is not a boolean: {err}")),
+        Some(_) => parse_toml(&raw).ok_or_else(|| format!("{raw} is not a valid TOML value.")),
+        None => Ok(parse_toml(&raw).unwrap_or(Value::String(raw)))
+    }
+}
+
+fn parse_toml(raw: &str) -> Option<Value> {
+    toml::from_str::<Table>(&format!("value = {raw}")).ok()?.remove("value")
+}
+
+/// Returns a snapshot of the current configuration.
+pub async fn get_config(context: &Context) -> ConfigurationData {
+    let config = context.data.read().await.get::<ConfigContainer>().cloned().unwrap();
+    let config = config.read().await;
+    config.clone()
+}
+
+/// Reloads the configuration from the given path, keeping the current
+/// configuration if the new one is invalid. Returns whether the new
+/// configuration was applied.
+pub async fn reload_config(config: &RwLock<ConfigurationData>, path: &str) -> bool {
+    let new_config = match read_config(path) {
+        Ok(new_config) => new_config,
+        Err(why) => {
+            error!("Rejected configuration reload, keeping the current configuration: {why}");
+            return false;
+        }
+    };
+
+    let mut config = config.write().await;
+    if config.bot.discord.token != new_config.bot.discord.token || config.bot.discord.appid != new_config.bot.discord.appid {
+        warn!("Discord credentials changed; restart the bot for this to take effect.");
+    }
+
+    if config.bot.database.url != new_config.bot.database.url {
+        warn!("Database URL changed; restart the bot for this to take effect.");
+    }
+
+    *config = new_config;
+    info!("Configuration reloaded from {path}.");
+
+    true
+}
+
+/// Replaces the Spotify client with one using the credentials in the given
+/// configuration, or removes it if they aren't set.
+#[cfg(feature = "music-spotify")]
+pub fn refresh_spotify(data: &mut TypeMap, config: &ConfigurationData) {
+    match config.api.spotify() {
+        Some(spotify) => {
+            let id = spotify.client_id.clone();
+            let secret = spotify.client_secret.clone();
+            data.insert::<SpotifyContainer>(Arc::new(SpotifyClient::new(ClientCredentials { id, secret })));
+        }
+        None => {
+            data.remove::<SpotifyContainer>();
+        }
+    }
+}
+
+/// Watches for `SIGHUP` and changes to the configuration file, reloading the
+/// configuration whenever either happens.
+pub async fn watch_config(data: Arc<RwLock<TypeMap>>, path: String) {
+    let config = data.read().await.get::<ConfigContainer>().cloned().unwrap();
+    let mut modified = modified_time(&path);
+    let mut ticker = interval(WATCH_INTERVAL);
+    let mut listener = listen_hangup();
+
+    loop {
+        tokio::select! {
+            _ = hangup(&mut listener) => info!("Received SIGHUP, reloading configuration."),
+            _ = ticker.tick() => {
+                let current = modified_time(&path);
+                if current.is_none() || current == modified {
+                    continue;
+                }
+
+                modified = current;
+                info!("Configuration file {path} changed, reloading configuration.");
+            }
+        }
+
+        if !reload_config(&config, &path).await {
+            continue;
+        }
+
+        #[cfg(feature = "music-spotify")]
+        refresh_spotify(&mut *data.write().await, &*config.read().await);
+    }
+}
+
+fn modified_time(path: impl AsRef<Path>) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(unix)]
+type HangupListener = Option<tokio::signal::unix::Signal>;
+
+#[cfg(not(unix))]
+type HangupListener = ();
+
+#[cfg(unix)]
+fn listen_hangup() -> HangupListener {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::hangup()) {
+        Ok(listener) => Some(listener),
+        Err(why) => {
+            error!("Unable to listen for SIGHUP: {why}");
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn listen_hangup() -> HangupListener {}
+
+#[cfg(unix)]
+async fn hangup(listener: &mut HangupListener) {
+    match listener {
+        Some(listener) => drop(listener.recv().await),
+        None => std::future::pending().await
+    }
+}
+
+#[cfg(not(unix))]
+async fn hangup(_listener: &mut HangupListener) {
+    std::future::pending().await
+}
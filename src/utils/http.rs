@@ -0,0 +1,202 @@
+//! Outbound HTTP
+//!
+//! Requests to external APIs go through [`HttpClient`], which applies the
+//! timeout from the configuration, retries timeouts, rate limits and server
+//! errors with jittered exponential backoff, honours `Retry-After` and limits
+//! how many requests may be in flight to each service at once.
+//!
+//! Last.fm and Spotify are requested through their own libraries, which build
+//! and send their requests themselves. They hold a permit from
+//! [`HttpClient::reserve`] while they do, so they share the concurrency limits,
+//! but they aren't retried: their errors don't expose the status code or the
+//! `Retry-After` header, so a rate limit or server error can't be told apart
+//! from a missing user or track. Last.fm is also given the underlying client
+//! so that it shares the timeout; Spotify's client can't be configured this
+//! way.
+
+use reqwest::{header::RETRY_AFTER, redirect::Policy, Client, IntoUrl, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH}
+};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::sleep
+};
+use tracing::warn;
+
+use crate::{config::HttpConfig, constants::REQWEST_USER_AGENT};
+
+/// An external service requested through [`HttpClient`]. Each service has its
+/// own concurrency limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Service {
+    LastFm,
+    Spotify,
+    Tmdb,
+    Tokei,
+    Twitter,
+    UrbanDictionary,
+    Xkcd
+}
+
+impl Service {
+    const ALL: &'static [Service] = &[
+        Service::LastFm,
+        Service::Spotify,
+        Service::Tmdb,
+        Service::Tokei,
+        Service::Twitter,
+        Service::UrbanDictionary,
+        Service::Xkcd
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Service::LastFm => "Last.fm",
+            Service::Spotify => "Spotify",
+            Service::Tmdb => "TMDb",
+            Service::Tokei => "tokei",
+            Service::Twitter => "Twitter",
+            Service::UrbanDictionary => "Urban Dictionary",
+            Service::Xkcd => "xkcd"
+        }
+    }
+}
+
+/// A shared HTTP client for external APIs. Cloning it is cheap, and clones
+/// share their concurrency limits.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    config: HttpConfig,
+    limits: Arc<HashMap<Service, Arc<Semaphore>>>
+}
+
+impl HttpClient {
+    pub fn new(config: &HttpConfig) -> reqwest::Result<HttpClient> {
+        let client = Client::builder()
+            .user_agent(REQWEST_USER_AGENT)
+            .redirect(Policy::none())
+            .timeout(Duration::from_secs(config.timeout))
+            .build()?;
+
+        let limits = Service::ALL.iter().map(|service| (*service, Arc::new(Semaphore::new(config.concurrency.max(1))))).collect();
+
+        Ok(HttpClient {
+            client,
+            config: config.clone(),
+            limits: Arc::new(limits)
+        })
+    }
+
+    /// The underlying client, for libraries that make their own requests.
+    pub fn inner(&self) -> Client {
+        self.client.clone()
+    }
+
+    /// Waits for one of the concurrency permits of the given service, for
+    /// libraries that send their own requests to it. The permit is given back
+    /// once it is dropped.
+    pub async fn reserve(&self, service: Service) -> OwnedSemaphorePermit {
+        // The semaphores are never closed, so acquiring a permit can't fail.
+        Arc::clone(&self.limits[&service]).acquire_owned().await.unwrap()
+    }
+
+    /// Starts a GET request to the given service.
+    pub fn get(&self, service: Service, url: impl IntoUrl) -> Request {
+        Request {
+            client: self.clone(),
+            service,
+            builder: self.client.get(url)
+        }
+    }
+
+    async fn execute(&self, service: Service, request: RequestBuilder) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            // Requests with a streamed body can't be cloned, and so are only sent once.
+            let pending = match request.try_clone() {
+                Some(pending) if attempt < self.config.retries => pending,
+                _ => {
+                    let response = self.send(service, request).await?;
+                    return if should_retry(response.status()) { response.error_for_status() } else { Ok(response) };
+                }
+            };
+
+            let delay = match self.send(service, pending).await {
+                Ok(response) if should_retry(response.status()) => match retry_after(&response) {
+                    Some(delay) if delay > Duration::from_secs(self.config.max_backoff) => return response.error_for_status(),
+                    Some(delay) => delay,
+                    None => self.backoff(attempt)
+                },
+                Ok(response) => return Ok(response),
+                Err(why) if why.is_timeout() || why.is_connect() => self.backoff(attempt),
+                Err(why) => return Err(why)
+            };
+
+            attempt += 1;
+            warn!(
+                "Request to {} failed, retrying in {}ms (retry {attempt} of {}).",
+                service.name(),
+                delay.as_millis(),
+                self.config.retries
+            );
+            sleep(delay).await;
+        }
+    }
+
+    async fn send(&self, service: Service, request: RequestBuilder) -> reqwest::Result<Response> {
+        // The semaphores are never closed, so acquiring a permit can't fail.
+        let _permit = self.limits[&service].acquire().await;
+        request.send().await
+    }
+
+    /// Doubles the base delay with every attempt, adding up to one base delay
+    /// of jitter so that concurrent retries don't line up.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = self.config.backoff.max(1);
+        let jitter = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| u64::from(time.subsec_nanos()) % base).unwrap_or_default();
+        let delay = base.saturating_mul(2_u64.saturating_pow(attempt)).saturating_add(jitter);
+        Duration::from_millis(delay).min(Duration::from_secs(self.config.max_backoff))
+    }
+}
+
+/// A request that hasn't been sent yet, see [`HttpClient::get`].
+pub struct Request {
+    client: HttpClient,
+    service: Service,
+    builder: RequestBuilder
+}
+
+impl Request {
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Request {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    pub fn bearer_auth<T: Display>(mut self, token: T) -> Request {
+        self.builder = self.builder.bearer_auth(token);
+        self
+    }
+
+    /// Sends the request, retrying it if it times out, is rate limited or
+    /// fails with a server error. A response with any other status is
+    /// returned as-is.
+    pub async fn send(self) -> reqwest::Result<Response> {
+        self.client.execute(self.service, self.builder).await
+    }
+}
+
+fn should_retry(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Reads the delay from the `Retry-After` header, if it is given in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse().ok().map(Duration::from_secs)
+}
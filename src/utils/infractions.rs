@@ -0,0 +1,463 @@
+//! Infractions
+//!
+//! Every moderation action is recorded in the `infractions` table as a case,
+//! numbered per guild. Moderation commands record what they did through
+//! [`record_infraction`] once the action has been carried out, so the log
+//! only holds actions that actually happened.
+//!
+//! Warnings are infractions too. They count towards the escalation rules of
+//! their guild until they expire or are pardoned, and expire after the
+//! guild's warning decay, if it has one.
+//!
+//! Temporary bans are also kept in the `tempbans` table until they are
+//! lifted by [`run_unban_scheduler`]. As with reminders, bans that expired
+//! while the bot was offline are lifted as soon as it is back, and each ban
+//! is claimed for a while before it is lifted, so that several instances of
+//! the bot never lift the same ban.
+
+use serenity::{
+    client::Context,
+    http::{Http, HttpError},
+    model::id::{GuildId, UserId}
+};
+use sqlx::{PgExecutor, PgPool, Row};
+use std::{sync::Arc, time::Duration};
+use tokio::time::interval;
+use tracing::{error, info, warn};
+
+use crate::{
+    data::DatabasePool,
+    error::TaliyahError,
+    models::infraction::{Action, EscalationRule, Infraction}
+};
+
+/// How often the scheduler checks for temporary bans that have expired.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long, in seconds, to wait before retrying a ban that couldn't be
+/// lifted.
+const RETRY_DELAY: i64 = 300;
+
+/// How many times lifting a ban may fail before it is given up on.
+const MAX_ATTEMPTS: i32 = 5;
+
+/// How long, in seconds, a ban is claimed for while it is lifted. A ban whose
+/// claim runs out, e.g. because the bot stopped while lifting it, is claimed
+/// again.
+const CLAIM_DURATION: i64 = 300;
+
+/// How many days of messages are deleted on a ban in guilds that haven't
+/// changed it.
+pub const DEFAULT_DELETE_MESSAGE_DAYS: u8 = 1;
+
+/// The most days of messages Discord deletes on a ban.
+pub const MAX_DELETE_MESSAGE_DAYS: u8 = 7;
+
+const COLUMNS: &str = "case_id, guild_id, user_id, moderator_id, action, reason, CAST(EXTRACT(EPOCH FROM created_at) AS BIGINT) AS created_at, \
+    CAST(EXTRACT(EPOCH FROM expires_at) AS BIGINT) AS expires_at, CAST(EXTRACT(EPOCH FROM pardoned_at) AS BIGINT) AS pardoned_at, pardoned_by";
+
+/// Records a moderation action against a user, returning its case number.
+/// `expires_at` is a Unix timestamp, for actions that end on their own.
+pub async fn record_infraction(
+    context: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    moderator_id: UserId,
+    action: Action,
+    reason: Option<&str>,
+    expires_at: Option<i64>
+) -> Result<i32, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    insert_infraction(&pool, guild_id, user_id, moderator_id, action, reason, expires_at).await
+}
+
+async fn insert_infraction(
+    executor: impl PgExecutor<'_>,
+    guild_id: GuildId,
+    user_id: UserId,
+    moderator_id: UserId,
+    action: Action,
+    reason: Option<&str>,
+    expires_at: Option<i64>
+) -> Result<i32, TaliyahError> {
+    let case_id = sqlx::query(
+        "WITH counter AS ( \
+            INSERT INTO infraction_counters (guild_id, last_case) VALUES ($1, 1) \
+            ON CONFLICT (guild_id) DO UPDATE SET last_case = infraction_counters.last_case + 1 RETURNING last_case \
+        ) \
+        INSERT INTO infractions (guild_id, case_id, user_id, moderator_id, action, reason, expires_at) \
+        SELECT $1, last_case, $2, $3, $4, $5, to_timestamp($6) FROM counter RETURNING case_id"
+    )
+    .bind(guild_id.get() as i64)
+    .bind(user_id.get() as i64)
+    .bind(moderator_id.get() as i64)
+    .bind(action.key())
+    .bind(reason)
+    .bind(expires_at)
+    .fetch_one(executor)
+    .await?
+    .try_get(0)?;
+
+    Ok(case_id)
+}
+
+/// Returns a single case of a guild.
+pub async fn get_infraction(context: &Context, guild_id: GuildId, case_id: i32) -> Result<Option<Infraction>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let query = format!("SELECT {COLUMNS} FROM infractions WHERE guild_id = $1 AND case_id = $2");
+    let infraction = sqlx::query_as::<_, Infraction>(&query).bind(guild_id.get() as i64).bind(case_id).fetch_optional(&pool).await?;
+
+    Ok(infraction)
+}
+
+/// Returns every case of a guild against a user, newest first.
+pub async fn get_user_infractions(context: &Context, guild_id: GuildId, user_id: UserId) -> Result<Vec<Infraction>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let query = format!("SELECT {COLUMNS} FROM infractions WHERE guild_id = $1 AND user_id = $2 ORDER BY case_id DESC");
+    let infractions = sqlx::query_as::<_, Infraction>(&query)
+        .bind(guild_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(infractions)
+}
+
+/// Replaces the reason of a case, returning whether the guild has a case with
+/// that number.
+pub async fn set_infraction_reason(context: &Context, guild_id: GuildId, case_id: i32, reason: &str) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let result = sqlx::query("UPDATE infractions SET reason = $1 WHERE guild_id = $2 AND case_id = $3")
+        .bind(reason)
+        .bind(guild_id.get() as i64)
+        .bind(case_id)
+        .execute(&pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Pardons a warning, returning whether the guild has an unpardoned warning
+/// with that case number.
+pub async fn pardon_warning(context: &Context, guild_id: GuildId, case_id: i32, moderator_id: UserId) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let result = sqlx::query(
+        "UPDATE infractions SET pardoned_at = now(), pardoned_by = $1 \
+        WHERE guild_id = $2 AND case_id = $3 AND action = $4 AND pardoned_at IS NULL"
+    )
+    .bind(moderator_id.get() as i64)
+    .bind(guild_id.get() as i64)
+    .bind(case_id)
+    .bind(Action::Warn.key())
+    .execute(&pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Counts the warnings of a user that haven't expired or been pardoned.
+pub async fn count_active_warnings(context: &Context, guild_id: GuildId, user_id: UserId) -> Result<i64, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let count = sqlx::query(
+        "SELECT COUNT(*) FROM infractions WHERE guild_id = $1 AND user_id = $2 AND action = $3 \
+        AND pardoned_at IS NULL AND (expires_at IS NULL OR expires_at > now())"
+    )
+    .bind(guild_id.get() as i64)
+    .bind(user_id.get() as i64)
+    .bind(Action::Warn.key())
+    .fetch_one(&pool)
+    .await?
+    .try_get(0)?;
+
+    Ok(count)
+}
+
+/// Returns how long, in seconds, warnings stay active in a guild, or `None`
+/// if they never expire.
+pub async fn get_warning_decay(context: &Context, guild_id: GuildId) -> Result<Option<i64>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let decay = sqlx::query("SELECT warning_decay FROM moderation_settings WHERE guild_id = $1")
+        .bind(guild_id.get() as i64)
+        .fetch_optional(&pool)
+        .await?
+        .map(|row| row.try_get::<Option<i64>, _>(0))
+        .transpose()?
+        .flatten();
+
+    Ok(decay)
+}
+
+/// Sets how long, in seconds, warnings given from now on stay active in a
+/// guild. Warnings already given keep their expiry.
+pub async fn set_warning_decay(context: &Context, guild_id: GuildId, decay: Option<i64>) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    sqlx::query(
+        "INSERT INTO moderation_settings (guild_id, warning_decay) VALUES ($1, $2) \
+        ON CONFLICT (guild_id) DO UPDATE SET warning_decay = EXCLUDED.warning_decay"
+    )
+    .bind(guild_id.get() as i64)
+    .bind(decay)
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns how many days of messages are deleted when a user is banned from a
+/// guild.
+pub async fn get_delete_message_days(context: &Context, guild_id: GuildId) -> Result<u8, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let days = sqlx::query("SELECT delete_message_days FROM moderation_settings WHERE guild_id = $1")
+        .bind(guild_id.get() as i64)
+        .fetch_optional(&pool)
+        .await?
+        .map(|row| row.try_get::<Option<i16>, _>(0))
+        .transpose()?
+        .flatten();
+
+    Ok(days.map_or(DEFAULT_DELETE_MESSAGE_DAYS, |days| days.clamp(0, MAX_DELETE_MESSAGE_DAYS as i16) as u8))
+}
+
+/// Sets how many days of messages are deleted when a user is banned from a
+/// guild.
+pub async fn set_delete_message_days(context: &Context, guild_id: GuildId, days: u8) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    sqlx::query(
+        "INSERT INTO moderation_settings (guild_id, delete_message_days) VALUES ($1, $2) \
+        ON CONFLICT (guild_id) DO UPDATE SET delete_message_days = EXCLUDED.delete_message_days"
+    )
+    .bind(guild_id.get() as i64)
+    .bind(days as i16)
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the newest ban or temporary ban case of a guild for every user it
+/// has banned.
+pub async fn get_latest_bans(context: &Context, guild_id: GuildId) -> Result<Vec<Infraction>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let query = format!("SELECT DISTINCT ON (user_id) {COLUMNS} FROM infractions WHERE guild_id = $1 AND action = ANY($2) ORDER BY user_id, case_id DESC");
+    let infractions = sqlx::query_as::<_, Infraction>(&query)
+        .bind(guild_id.get() as i64)
+        .bind(vec![Action::Ban.key(), Action::Tempban.key()])
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(infractions)
+}
+
+/// Returns the escalation rules of a guild, ordered by their number of
+/// warnings.
+pub async fn get_escalation_rules(context: &Context, guild_id: GuildId) -> Result<Vec<EscalationRule>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let rules = sqlx::query_as::<_, EscalationRule>("SELECT warnings, action, duration FROM escalation_rules WHERE guild_id = $1 ORDER BY warnings")
+        .bind(guild_id.get() as i64)
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(rules)
+}
+
+/// Returns the escalation rule of a guild for the given number of warnings.
+pub async fn get_escalation_rule(context: &Context, guild_id: GuildId, warnings: i64) -> Result<Option<EscalationRule>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let rule = sqlx::query_as::<_, EscalationRule>("SELECT warnings, action, duration FROM escalation_rules WHERE guild_id = $1 AND warnings = $2")
+        .bind(guild_id.get() as i64)
+        .bind(warnings as i32)
+        .fetch_optional(&pool)
+        .await?;
+
+    Ok(rule)
+}
+
+/// Sets the action taken once a user reaches the given number of active
+/// warnings, replacing any action already set for it.
+pub async fn set_escalation_rule(context: &Context, guild_id: GuildId, warnings: i32, action: Action, duration: Option<i64>) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    sqlx::query(
+        "INSERT INTO escalation_rules (guild_id, warnings, action, duration) VALUES ($1, $2, $3, $4) \
+        ON CONFLICT (guild_id, warnings) DO UPDATE SET action = EXCLUDED.action, duration = EXCLUDED.duration"
+    )
+    .bind(guild_id.get() as i64)
+    .bind(warnings)
+    .bind(action.key())
+    .bind(duration)
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes the escalation rule for the given number of warnings, returning
+/// whether there was one.
+pub async fn remove_escalation_rule(context: &Context, guild_id: GuildId, warnings: i32) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let result = sqlx::query("DELETE FROM escalation_rules WHERE guild_id = $1 AND warnings = $2")
+        .bind(guild_id.get() as i64)
+        .bind(warnings)
+        .execute(&pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Records a temporary ban as a case and schedules it to be lifted at the
+/// given Unix timestamp, replacing any ban of the user that was already
+/// scheduled to be lifted. Both happen in one transaction, so a ban is never
+/// recorded without being lifted later, or the other way around.
+pub async fn record_tempban(context: &Context, guild_id: GuildId, user_id: UserId, moderator_id: UserId, reason: Option<&str>, expires_at: i64) -> Result<i32, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let mut transaction = pool.begin().await?;
+    let case_id = insert_infraction(&mut *transaction, guild_id, user_id, moderator_id, Action::Tempban, reason, Some(expires_at)).await?;
+    sqlx::query(
+        "INSERT INTO tempbans (guild_id, user_id, case_id, expires_at) VALUES ($1, $2, $3, to_timestamp($4)) \
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET case_id = EXCLUDED.case_id, expires_at = EXCLUDED.expires_at, attempts = 0, claimed_until = NULL"
+    )
+    .bind(guild_id.get() as i64)
+    .bind(user_id.get() as i64)
+    .bind(case_id)
+    .bind(expires_at)
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(case_id)
+}
+
+/// Returns whether a temporary ban of the user is scheduled to be lifted.
+pub async fn is_unban_scheduled(context: &Context, guild_id: GuildId, user_id: UserId) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let scheduled = sqlx::query("SELECT EXISTS (SELECT 1 FROM tempbans WHERE guild_id = $1 AND user_id = $2)")
+        .bind(guild_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .fetch_one(&pool)
+        .await?
+        .try_get(0)?;
+
+    Ok(scheduled)
+}
+
+/// Stops a temporary ban from being lifted, returning whether one was
+/// scheduled.
+pub async fn cancel_unban(context: &Context, guild_id: GuildId, user_id: UserId) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let result = sqlx::query("DELETE FROM tempbans WHERE guild_id = $1 AND user_id = $2")
+        .bind(guild_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .execute(&pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lifts temporary bans as they expire, for as long as the bot runs. Each one
+/// is recorded as an unban made by the bot.
+pub async fn run_unban_scheduler(http: Arc<Http>, pool: PgPool) {
+    let bot = match http.get_current_user().await {
+        Ok(user) => user.id,
+        Err(why) => {
+            error!("Unable to start lifting temporary bans: {why}");
+            return;
+        }
+    };
+
+    match sqlx::query("SELECT COUNT(*) FROM tempbans WHERE expires_at <= now()").fetch_one(&pool).await {
+        Ok(row) => {
+            let expired: i64 = row.get(0);
+            if expired > 0 {
+                info!("Lifting {expired} temporary ban(s) that expired while offline.");
+            }
+        }
+        Err(why) => error!("Unable to count expired temporary bans: {why}")
+    }
+
+    let mut ticker = interval(POLL_INTERVAL);
+    loop {
+        ticker.tick().await;
+        if let Err(why) = lift_expired_bans(&http, &pool, bot).await {
+            error!("Unable to lift temporary bans: {why}");
+        }
+    }
+}
+
+/// Lifts every temporary ban that has expired, one at a time.
+async fn lift_expired_bans(http: &Http, pool: &PgPool, bot: UserId) -> Result<(), TaliyahError> {
+    while lift_next_ban(http, pool, bot).await? {}
+
+    Ok(())
+}
+
+/// Claims the temporary ban that expired the longest ago, lifts it and
+/// records the unban, returning whether there was one. The claim is committed
+/// before Discord is asked to lift the ban, so no row is locked in the
+/// meantime, and bans claimed by another instance of the bot are skipped
+/// until their claim runs out.
+async fn lift_next_ban(http: &Http, pool: &PgPool, bot: UserId) -> Result<bool, TaliyahError> {
+    let row = sqlx::query(
+        "UPDATE tempbans SET claimed_until = now() + make_interval(secs => $1) \
+        WHERE (guild_id, user_id) = ( \
+            SELECT guild_id, user_id FROM tempbans WHERE expires_at <= now() AND (claimed_until IS NULL OR claimed_until <= now()) \
+            ORDER BY expires_at LIMIT 1 FOR UPDATE SKIP LOCKED \
+        ) \
+        RETURNING guild_id, user_id, case_id, attempts"
+    )
+    .bind(CLAIM_DURATION as f64)
+    .fetch_optional(pool)
+    .await?;
+
+    let row = match row {
+        Some(row) => row,
+        None => return Ok(false)
+    };
+
+    let guild_id = GuildId::new(row.try_get::<i64, _>(0)? as u64);
+    let user_id = UserId::new(row.try_get::<i64, _>(1)? as u64);
+    let case_id: i32 = row.try_get(2)?;
+    let attempts: i32 = row.try_get(3)?;
+
+    let lifted = match guild_id.unban(http, user_id).await {
+        Ok(()) => true,
+        // The ban was already lifted by hand.
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(response))) if response.status_code.as_u16() == 404 => false,
+        Err(why) if attempts + 1 >= MAX_ATTEMPTS => {
+            warn!("Giving up on lifting the ban of case #{case_id} in guild {guild_id} after {MAX_ATTEMPTS} attempts: {why}");
+            false
+        }
+        Err(why) => {
+            warn!("Unable to lift the ban of case #{case_id} in guild {guild_id}, retrying later: {why}");
+            sqlx::query(
+                "UPDATE tempbans SET expires_at = now() + make_interval(secs => $1), attempts = attempts + 1, claimed_until = NULL \
+                WHERE guild_id = $2 AND user_id = $3 AND case_id = $4"
+            )
+            .bind(RETRY_DELAY as f64)
+            .bind(guild_id.get() as i64)
+            .bind(user_id.get() as i64)
+            .bind(case_id)
+            .execute(pool)
+            .await?;
+            return Ok(true);
+        }
+    };
+
+    // The ban may have been replaced or cancelled while it was being lifted,
+    // in which case it is left to whoever did that.
+    let mut transaction = pool.begin().await?;
+    let result = sqlx::query("DELETE FROM tempbans WHERE guild_id = $1 AND user_id = $2 AND case_id = $3")
+        .bind(guild_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .bind(case_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    if lifted && result.rows_affected() > 0 {
+        let reason = format!("Temporary ban of case #{case_id} expired.");
+        insert_infraction(&mut *transaction, guild_id, user_id, bot, Action::Unban, Some(&reason), None).await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(true)
+}
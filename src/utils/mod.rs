@@ -1,42 +1,48 @@
+pub mod cache;
 // pub mod color;
+pub mod config;
+#[cfg(feature = "info")]
 pub mod git;
+pub mod http;
+#[cfg(feature = "moderation")]
+pub mod infractions;
+#[cfg(any(feature = "music-spotify", feature = "tmdb"))]
 pub mod locale;
+#[cfg(feature = "music-lastfm")]
 pub mod net;
 pub mod parsing;
+pub mod prefixes;
+pub mod profiles;
+pub mod reminders;
+pub mod time;
 
-use serenity::{client::Context, model::id::UserId};
-use sqlx::Row;
-use std::{fs::File, io::prelude::Read};
-use tracing::error;
+use std::{fs::File, io::prelude::Read, path::Path};
 
-use crate::{config::ConfigurationData, data::DatabasePool, error::TaliyahError};
+use crate::{config::ConfigurationData, error::TaliyahError};
 
-pub fn read_config(file: &str) -> ConfigurationData {
-    let mut file = File::open(file).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-    toml::from_str::<ConfigurationData>(&contents).unwrap()
+/// Reads the configuration file at the given path, applies any environment
+/// variable overrides and validates the result.
+pub fn read_config(path: &str) -> Result<ConfigurationData, TaliyahError> {
+    let value = read_config_value(path)?;
+    let config = value.try_into::<ConfigurationData>().map_err(|err| format!("Invalid configuration: {err}"))?;
+    config.validate()?;
+    Ok(config)
 }
 
-pub async fn get_profile_field(context: &Context, field: &str, user_id: UserId) -> Result<String, TaliyahError> {
-    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
-    match sqlx::query(format!("SELECT {field} FROM profile_data WHERE user_id = $1").as_str())
-        .bind(user_id.get() as i64)
-        .fetch_one(&pool)
-        .await
-    {
-        Ok(row) => match row.try_get(0).map_err(TaliyahError::Database) {
-            Ok(row) => Ok(row),
-            Err(err) => {
-                error!("Field not set in database: {err}");
-                Ok("Field not set.".to_string())
-            }
-        },
-        Err(err) => {
-            error!("Error querying database: {err}");
-            Ok("Database unsuccessfully queried.".to_string())
-        }
+/// Reads the configuration file at the given path as a TOML value and applies
+/// any environment variable overrides, without checking its contents.
+///
+/// The file is optional as long as the environment provides every value.
+pub fn read_config_value(path: &str) -> Result<toml::Value, TaliyahError> {
+    let mut contents = String::new();
+    if Path::new(path).exists() {
+        let mut file = File::open(path).map_err(|err| format!("Unable to open {path}: {err}"))?;
+        file.read_to_string(&mut contents).map_err(|err| format!("Unable to read {path}: {err}"))?;
     }
+
+    let mut value = toml::from_str::<toml::Value>(&contents).map_err(|err| format!("Unable to parse {path}: {err}"))?;
+    config::apply_env_overrides(&mut value)?;
+    Ok(value)
 }
 
 /// Converts integers to human-readable integers separated by
@@ -54,6 +60,7 @@ pub fn format_int(int: u64) -> String {
 }
 
 /// Calculates the average sum of an array of i64's.
+#[cfg(feature = "tmdb")]
 pub fn calculate_average_sum(ints: &[i64]) -> f64 {
     ints.iter().sum::<i64>() as f64 / ints.len() as f64
 }
@@ -3,30 +3,71 @@
 //! These utilities help with various network-related tasks
 //! and functions.
 
-use super::read_config;
-use crate::data::{ReqwestContainer, SpotifyContainer};
-use aspotify::{CountryCode::CAN, ItemType, Market::Country};
+use super::{config::get_config, http::Service};
+use crate::data::ReqwestContainer;
 use lastfm_rs::Client;
 use serenity::client::Context;
+use tokio::sync::OwnedSemaphorePermit;
 
-pub async fn get_lastfm_client(context: &Context) -> Client {
-    let config = read_config("config.toml");
-    let api_key = config.api.music.lastfm.api_key;
+#[cfg(feature = "music-spotify")]
+use super::cache::{get_cache, Endpoint};
+#[cfg(feature = "music-spotify")]
+use crate::data::SpotifyContainer;
+#[cfg(feature = "music-spotify")]
+use aspotify::{Client as SpotifyClient, CountryCode::CAN, ItemType, Market::Country};
+#[cfg(feature = "music-spotify")]
+use std::sync::Arc;
+
+/// Builds a Last.fm client, along with a permit to request Last.fm that has to
+/// be held for as long as the client is used.
+pub async fn get_lastfm_client(context: &Context) -> (Client, OwnedSemaphorePermit) {
+    let config = get_config(context).await;
+    let api_key = config.api.lastfm().unwrap_or_default().to_string();
     let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
-    Client::from_reqwest_client(client, &api_key)
+    let permit = client.reserve(Service::LastFm).await;
+    (Client::from_reqwest_client(client.inner(), &api_key), permit)
 }
 
-pub async fn get_album_artwork(context: &Context, artist: &str, track: &str, album: &str) -> String {
-    let data = context.data.read().await;
-    let spotify = data.get::<SpotifyContainer>().unwrap();
+/// Waits for a permit to request the given service through its own library,
+/// which has to be held until the requests are done.
+pub async fn reserve(context: &Context, service: Service) -> OwnedSemaphorePermit {
+    let client = context.data.read().await.get::<ReqwestContainer>().cloned().unwrap();
+    client.reserve(service).await
+}
+
+/// The Spotify client, or `None` if Spotify isn't configured. The client is
+/// cloned out so that the data lock isn't held while requesting Spotify.
+#[cfg(feature = "music-spotify")]
+pub async fn get_spotify(context: &Context) -> Option<Arc<SpotifyClient>> {
+    context.data.read().await.get::<SpotifyContainer>().cloned()
+}
+
+/// Looks up the album artwork of a track on Spotify. Returns `None` if Spotify
+/// isn't configured or the track couldn't be found.
+#[cfg(feature = "music-spotify")]
+pub async fn get_album_artwork(context: &Context, artist: &str, track: &str, album: &str) -> Option<String> {
+    let cache = get_cache(context).await;
+    let spotify = get_spotify(context).await?;
+    let _permit = reserve(context, Service::Spotify).await;
 
     let search_string = format!("artist:\"{artist}\" track:\"{track}\" album:\"{album}\"");
-    let track_search = spotify.search().search(&search_string, [ItemType::Track].iter().copied(), false, 1, 0, Some(Country(CAN))).await;
-    let track_result = &track_search.unwrap().data.tracks.unwrap();
-
-    let items = &track_result.items;
-    let track = items.first().unwrap();
-    let image = track.album.images.first().unwrap();
-    let album_art = image.url.as_str();
-    album_art.to_string()
+    let fetch = async {
+        let track_search = spotify
+            .search()
+            .search(&search_string, [ItemType::Track].iter().copied(), false, 1, 0, Some(Country(CAN)))
+            .await?;
+        let artwork = track_search
+            .data
+            .tracks
+            .and_then(|tracks| tracks.items.into_iter().next())
+            .and_then(|track| track.album.images.into_iter().next());
+        Ok::<_, aspotify::Error>(artwork.map(|image| image.url))
+    };
+
+    cache.get_or_fetch(Endpoint::SpotifyArtwork, &search_string, fetch).await.ok()?
+}
+
+#[cfg(not(feature = "music-spotify"))]
+pub async fn get_album_artwork(_context: &Context, _artist: &str, _track: &str, _album: &str) -> Option<String> {
+    None
 }
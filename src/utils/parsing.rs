@@ -28,3 +28,11 @@ pub async fn parse_user(name: &str, guild_id: GuildId, context: &Context) -> Opt
 
     None
 }
+
+/// Parses a mention or a raw ID, without looking the user up, so that users who
+/// aren't in a guild can be given.
+pub fn parse_user_id(name: &str) -> Option<UserId> {
+    parse_username(name)
+        .map(|id| UserId(NonZeroU64::new(id.get()).unwrap()))
+        .or_else(|| name.parse::<NonZeroU64>().ok().map(UserId))
+}
@@ -0,0 +1,115 @@
+//! Guild prefixes
+//!
+//! Prefixes are stored per guild in the `guild_info` table and cached in
+//! memory, so that resolving the prefix of a message doesn't require a
+//! database query. The prefix from the configuration file works in every
+//! guild alongside the guild's own prefixes. Only the guild's own prefixes
+//! are stored, so changing the default applies to every guild right away.
+
+use serenity::{client::Context, model::id::GuildId};
+use sqlx::Row;
+
+use super::config::get_config;
+use crate::{
+    data::{DatabasePool, PrefixCache},
+    error::TaliyahError
+};
+
+/// The maximum number of prefixes a guild can have at once.
+pub const MAX_PREFIXES: usize = 5;
+
+/// The maximum length of a single prefix.
+pub const MAX_PREFIX_LENGTH: usize = 10;
+
+/// Returns the prefix set in the configuration file.
+pub async fn default_prefix(context: &Context) -> String {
+    get_config(context).await.bot.general.prefix
+}
+
+/// Returns the prefixes a guild has set of its own, which is empty if it only
+/// uses the default prefix.
+pub async fn get_custom_prefixes(context: &Context, guild_id: GuildId) -> Result<Vec<String>, TaliyahError> {
+    let cache = context.data.read().await.get::<PrefixCache>().cloned().unwrap();
+    if let Some(prefixes) = cache.read().await.get(&guild_id) {
+        return Ok(prefixes.clone());
+    }
+
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let prefixes = sqlx::query("SELECT guild_prefixes FROM guild_info WHERE guild_id = $1")
+        .bind(guild_id.get() as i64)
+        .fetch_optional(&pool)
+        .await?
+        .and_then(|row| row.try_get::<Option<Vec<String>>, _>(0).ok().flatten())
+        .unwrap_or_default();
+
+    cache.write().await.insert(guild_id, prefixes.clone());
+
+    Ok(prefixes)
+}
+
+/// Returns every prefix the bot responds to in a guild, starting with the
+/// default prefix.
+pub async fn get_guild_prefixes(context: &Context, guild_id: GuildId) -> Result<Vec<String>, TaliyahError> {
+    let default = default_prefix(context).await;
+    let custom = get_custom_prefixes(context, guild_id).await?;
+    Ok(with_default(default, custom))
+}
+
+/// Puts the default prefix in front of a guild's own prefixes, leaving out
+/// any of them that are the same as it.
+pub fn with_default(default: String, mut custom: Vec<String>) -> Vec<String> {
+    custom.retain(|prefix| *prefix != default);
+    custom.insert(0, default);
+    custom
+}
+
+/// Replaces the prefixes a guild has set of its own. Passing no prefixes
+/// leaves the guild with only the default prefix.
+pub async fn set_guild_prefixes(context: &Context, guild_id: GuildId, prefixes: Vec<String>) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let cache = context.data.read().await.get::<PrefixCache>().cloned().unwrap();
+    let value = if prefixes.is_empty() { None } else { Some(&prefixes) };
+    // The guild may not have a row yet if it was joined while the bot was
+    // offline, so one is created with its cached name.
+    let name = guild_id.name(context).unwrap_or_default();
+
+    sqlx::query(
+        "INSERT INTO guild_info (guild_id, guild_name, guild_prefixes) VALUES ($2, $3, $1) \
+        ON CONFLICT (guild_id) DO UPDATE SET guild_prefixes = EXCLUDED.guild_prefixes"
+    )
+    .bind(value)
+    .bind(guild_id.get() as i64)
+    .bind(name)
+    .execute(&pool)
+    .await?;
+
+    cache.write().await.insert(guild_id, prefixes);
+
+    Ok(())
+}
+
+/// Checks whether the given prefix can be used, returning the reason it
+/// can't otherwise.
+pub fn validate_prefix(prefix: &str) -> Result<(), String> {
+    if prefix.is_empty() {
+        Err("A prefix cannot be empty.".to_string())
+    } else if prefix.chars().count() > MAX_PREFIX_LENGTH {
+        Err(format!("A prefix cannot be longer than {MAX_PREFIX_LENGTH} characters."))
+    } else if prefix.chars().any(|c| c.is_whitespace() || c == '`') {
+        Err("A prefix cannot contain spaces or backticks.".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_default_puts_the_default_first_once() {
+        let custom = vec!["!".to_string(), "x;".to_string(), "?".to_string()];
+        assert_eq!(with_default("x;".to_string(), custom), ["x;", "!", "?"]);
+        assert_eq!(with_default("x;".to_string(), Vec::new()), ["x;"]);
+    }
+}
@@ -0,0 +1,366 @@
+//! User profiles
+//!
+//! Profiles are stored in the `profile_data` table, with a row for every user
+//! that has sent a message the bot has seen. Each [`ProfileField`] has its
+//! own static queries, so no user input ends up in a query's text.
+//! Accounts linked from other services are kept in the `linked_accounts`
+//! table, keyed by the user and the service.
+//!
+//! Users choose who can see each part of their profile, which is kept in the
+//! `profile_privacy` table. Anything shown to someone other than the user
+//! should be read through the `get_visible_*` functions, which leave out what
+//! the [`Viewer`] isn't allowed to see.
+
+use serde_json::{Map, Value};
+use serenity::{
+    client::Context,
+    model::{
+        id::{GuildId, UserId},
+        user::User
+    }
+};
+use sqlx::{PgConnection, Row};
+
+use crate::{
+    data::DatabasePool,
+    error::TaliyahError,
+    models::profile::{LinkedAccount, PrivacySettings, PrivacyTarget, Profile, ProfileField, Visibility}
+};
+
+/// Every table with rows keyed to a user, which are exported and deleted
+/// along with their profile. Moderation cases belong to the guild they were
+/// made in rather than the user, so `infractions` is left out.
+const USER_TABLES: &[&str] = &["profile_data", "linked_accounts", "profile_privacy", "profile_opt_outs", "reminders"];
+
+/// Someone looking at a profile, and the guild they are looking from, if any.
+#[derive(Clone, Copy, Debug)]
+pub struct Viewer {
+    pub user_id: UserId,
+    pub guild_id: Option<GuildId>
+}
+
+impl Viewer {
+    pub fn new(user_id: UserId, guild_id: Option<GuildId>) -> Viewer {
+        Viewer { user_id, guild_id }
+    }
+
+    /// Whether the owner of a profile is a member of the guild the viewer is
+    /// looking from.
+    async fn shares_guild_with(&self, context: &Context, owner: UserId) -> bool {
+        match self.guild_id {
+            Some(guild_id) => guild_id.member(context, owner).await.is_ok(),
+            None => false
+        }
+    }
+
+    /// Whether the viewer can see something of the owner's with the given
+    /// visibility. Users can always see their own profile.
+    fn can_see(&self, owner: UserId, visibility: Visibility, shares_guild: bool) -> bool {
+        if self.user_id == owner {
+            return true;
+        }
+
+        match visibility {
+            Visibility::Public => true,
+            Visibility::Guild => shares_guild,
+            Visibility::Private => false
+        }
+    }
+
+    /// Whether the viewer can see the given part of the owner's profile.
+    async fn can_see_target(&self, context: &Context, owner: UserId, privacy: &PrivacySettings, target: PrivacyTarget) -> bool {
+        let visibility = privacy.visibility(target);
+        let shares_guild = visibility == Visibility::Guild && self.shares_guild_with(context, owner).await;
+        self.can_see(owner, visibility, shares_guild)
+    }
+}
+
+/// Returns the profile of a user, or `None` if they don't have one yet.
+pub async fn get_profile(context: &Context, user_id: UserId) -> Result<Option<Profile>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let profile = sqlx::query_as::<_, Profile>(
+        "SELECT user_name, user_location, user_gender, user_pronouns, user_lastfm_id, user_bio, user_timezone, user_birthday \
+        FROM profile_data WHERE user_id = $1"
+    )
+    .bind(user_id.get() as i64)
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(profile)
+}
+
+/// Returns a single field of a user's profile, or `None` if it isn't set.
+pub async fn get_profile_field(context: &Context, user_id: UserId, field: ProfileField) -> Result<Option<String>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let value = sqlx::query(select_field_query(field))
+        .bind(user_id.get() as i64)
+        .fetch_optional(&pool)
+        .await?
+        .map(|row| row.try_get::<Option<String>, _>(0))
+        .transpose()?
+        .flatten();
+
+    Ok(value)
+}
+
+/// Sets a field of a user's profile, creating the profile if they don't have
+/// one yet.
+pub async fn set_profile_field(context: &Context, user: &User, field: ProfileField, value: &str) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let mut transaction = pool.begin().await?;
+    create_profile(&mut *transaction, user).await?;
+
+    sqlx::query(set_field_query(field)).bind(value).bind(user.id.get() as i64).execute(&mut *transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Clears a field of a user's profile. Users without a profile are left as
+/// they are.
+pub async fn clear_profile_field(context: &Context, user_id: UserId, field: ProfileField) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    sqlx::query(clear_field_query(field)).bind(user_id.get() as i64).execute(&pool).await?;
+
+    Ok(())
+}
+
+/// The queries reading, setting and clearing each field's column.
+fn select_field_query(field: ProfileField) -> &'static str {
+    match field {
+        ProfileField::Name => "SELECT user_name FROM profile_data WHERE user_id = $1",
+        ProfileField::Location => "SELECT user_location FROM profile_data WHERE user_id = $1",
+        ProfileField::Gender => "SELECT user_gender FROM profile_data WHERE user_id = $1",
+        ProfileField::Pronouns => "SELECT user_pronouns FROM profile_data WHERE user_id = $1",
+        ProfileField::LastFm => "SELECT user_lastfm_id FROM profile_data WHERE user_id = $1",
+        ProfileField::Bio => "SELECT user_bio FROM profile_data WHERE user_id = $1",
+        ProfileField::Timezone => "SELECT user_timezone FROM profile_data WHERE user_id = $1",
+        ProfileField::Birthday => "SELECT user_birthday FROM profile_data WHERE user_id = $1"
+    }
+}
+
+fn set_field_query(field: ProfileField) -> &'static str {
+    match field {
+        ProfileField::Name => "UPDATE profile_data SET user_name = $1 WHERE user_id = $2",
+        ProfileField::Location => "UPDATE profile_data SET user_location = $1 WHERE user_id = $2",
+        ProfileField::Gender => "UPDATE profile_data SET user_gender = $1 WHERE user_id = $2",
+        ProfileField::Pronouns => "UPDATE profile_data SET user_pronouns = $1 WHERE user_id = $2",
+        ProfileField::LastFm => "UPDATE profile_data SET user_lastfm_id = $1 WHERE user_id = $2",
+        ProfileField::Bio => "UPDATE profile_data SET user_bio = $1 WHERE user_id = $2",
+        ProfileField::Timezone => "UPDATE profile_data SET user_timezone = $1 WHERE user_id = $2",
+        ProfileField::Birthday => "UPDATE profile_data SET user_birthday = $1 WHERE user_id = $2"
+    }
+}
+
+fn clear_field_query(field: ProfileField) -> &'static str {
+    match field {
+        ProfileField::Name => "UPDATE profile_data SET user_name = NULL WHERE user_id = $1",
+        ProfileField::Location => "UPDATE profile_data SET user_location = NULL WHERE user_id = $1",
+        ProfileField::Gender => "UPDATE profile_data SET user_gender = NULL WHERE user_id = $1",
+        ProfileField::Pronouns => "UPDATE profile_data SET user_pronouns = NULL WHERE user_id = $1",
+        ProfileField::LastFm => "UPDATE profile_data SET user_lastfm_id = NULL WHERE user_id = $1",
+        ProfileField::Bio => "UPDATE profile_data SET user_bio = NULL WHERE user_id = $1",
+        ProfileField::Timezone => "UPDATE profile_data SET user_timezone = NULL WHERE user_id = $1",
+        ProfileField::Birthday => "UPDATE profile_data SET user_birthday = NULL WHERE user_id = $1"
+    }
+}
+
+/// Returns the accounts a user has linked, ordered by service.
+pub async fn get_linked_accounts(context: &Context, user_id: UserId) -> Result<Vec<LinkedAccount>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let accounts = sqlx::query_as::<_, LinkedAccount>("SELECT service, account FROM linked_accounts WHERE user_id = $1 ORDER BY service")
+        .bind(user_id.get() as i64)
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(accounts)
+}
+
+/// Links an account from a service to a user's profile, replacing any account
+/// they had linked from the same service.
+pub async fn link_account(context: &Context, user: &User, service: &str, account: &str) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let user_id = user.id.get() as i64;
+    let mut transaction = pool.begin().await?;
+
+    create_profile(&mut *transaction, user).await?;
+
+    sqlx::query(
+        "INSERT INTO linked_accounts (user_id, service, account) VALUES ($1, $2, $3) \
+        ON CONFLICT (user_id, service) DO UPDATE SET account = EXCLUDED.account"
+    )
+    .bind(user_id)
+    .bind(service)
+    .bind(account)
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Unlinks the account a user had linked from a service, returning whether
+/// there was one.
+pub async fn unlink_account(context: &Context, user_id: UserId, service: &str) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let result = sqlx::query("DELETE FROM linked_accounts WHERE user_id = $1 AND service = $2")
+        .bind(user_id.get() as i64)
+        .bind(service)
+        .execute(&pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns the visibility a user has chosen for each part of their profile.
+pub async fn get_privacy_settings(context: &Context, user_id: UserId) -> Result<PrivacySettings, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let rows = sqlx::query("SELECT field, visibility FROM profile_privacy WHERE user_id = $1")
+        .bind(user_id.get() as i64)
+        .fetch_all(&pool)
+        .await?;
+
+    let mut settings = PrivacySettings::default();
+    for row in rows {
+        let field: String = row.try_get("field")?;
+        let visibility: String = row.try_get("visibility")?;
+        if let Some(visibility) = Visibility::parse(&visibility) {
+            settings.visibilities.insert(field, visibility);
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Sets who can see a part of a user's profile, creating the profile if they
+/// don't have one yet.
+pub async fn set_visibility(context: &Context, user: &User, target: PrivacyTarget, visibility: Visibility) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let user_id = user.id.get() as i64;
+    let mut transaction = pool.begin().await?;
+
+    create_profile(&mut *transaction, user).await?;
+
+    sqlx::query(
+        "INSERT INTO profile_privacy (user_id, field, visibility) VALUES ($1, $2, $3) \
+        ON CONFLICT (user_id, field) DO UPDATE SET visibility = EXCLUDED.visibility"
+    )
+    .bind(user_id)
+    .bind(target.key())
+    .bind(visibility.key())
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Returns the profile of a user with the fields the viewer can't see left
+/// out, or `None` if they don't have a profile yet.
+pub async fn get_visible_profile(context: &Context, owner: UserId, viewer: &Viewer) -> Result<Option<Profile>, TaliyahError> {
+    let mut profile = match get_profile(context, owner).await? {
+        Some(profile) => profile,
+        None => return Ok(None)
+    };
+
+    let privacy = get_privacy_settings(context, owner).await?;
+    let restricted = privacy.visibilities.values().any(|visibility| *visibility == Visibility::Guild);
+    let shares_guild = restricted && viewer.shares_guild_with(context, owner).await;
+
+    for field in ProfileField::ALL {
+        if !viewer.can_see(owner, privacy.visibility(PrivacyTarget::Field(field)), shares_guild) {
+            profile.clear(field);
+        }
+    }
+
+    Ok(Some(profile))
+}
+
+/// Returns a single field of a user's profile, or `None` if it isn't set or
+/// the viewer can't see it.
+pub async fn get_visible_profile_field(context: &Context, owner: UserId, field: ProfileField, viewer: &Viewer) -> Result<Option<String>, TaliyahError> {
+    let privacy = get_privacy_settings(context, owner).await?;
+    if !viewer.can_see_target(context, owner, &privacy, PrivacyTarget::Field(field)).await {
+        return Ok(None);
+    }
+
+    get_profile_field(context, owner, field).await
+}
+
+/// Returns the accounts a user has linked, or none if the viewer can't see
+/// them.
+pub async fn get_visible_linked_accounts(context: &Context, owner: UserId, viewer: &Viewer) -> Result<Vec<LinkedAccount>, TaliyahError> {
+    let privacy = get_privacy_settings(context, owner).await?;
+    if !viewer.can_see_target(context, owner, &privacy, PrivacyTarget::LinkedAccounts).await {
+        return Ok(Vec::new());
+    }
+
+    get_linked_accounts(context, owner).await
+}
+
+/// Creates an empty profile for a user who has sent a message, unless they
+/// have deleted their data before.
+pub async fn record_user(context: &Context, user: &User) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    sqlx::query(
+        "INSERT INTO profile_data (user_id, user_tag) SELECT $1, $2 \
+        WHERE NOT EXISTS (SELECT 1 FROM profile_opt_outs WHERE user_id = $1) ON CONFLICT DO NOTHING"
+    )
+    .bind(user.id.get() as i64)
+    .bind(user.tag())
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Collects every row keyed to a user, grouped by table.
+pub async fn export_user_data(context: &Context, user_id: UserId) -> Result<Value, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let mut data = Map::new();
+    for table in USER_TABLES {
+        let query = format!("SELECT COALESCE(json_agg(entry), '[]')::text FROM {table} entry WHERE user_id = $1");
+        let rows: String = sqlx::query(&query).bind(user_id.get() as i64).fetch_one(&pool).await?.try_get(0)?;
+        let rows = serde_json::from_str(&rows).map_err(|err| format!("Unable to read the rows of {table}: {err}"))?;
+        data.insert(table.to_string(), rows);
+    }
+
+    Ok(Value::Object(data))
+}
+
+/// Deletes every row keyed to a user and stops them from being added back
+/// automatically.
+pub async fn delete_user_data(context: &Context, user_id: UserId) -> Result<(), TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let user_id = user_id.get() as i64;
+    let mut transaction = pool.begin().await?;
+
+    for table in USER_TABLES {
+        sqlx::query(&format!("DELETE FROM {table} WHERE user_id = $1")).bind(user_id).execute(&mut *transaction).await?;
+    }
+
+    sqlx::query("INSERT INTO profile_opt_outs (user_id) VALUES ($1)").bind(user_id).execute(&mut *transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Creates an empty profile for a user if they don't have one yet. Doing so
+/// undoes a previous deletion of their data, as they chose to set up a
+/// profile again.
+async fn create_profile(connection: &mut PgConnection, user: &User) -> Result<(), TaliyahError> {
+    let user_id = user.id.get() as i64;
+    sqlx::query("INSERT INTO profile_data (user_id, user_tag) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+        .bind(user_id)
+        .bind(user.tag())
+        .execute(&mut *connection)
+        .await?;
+
+    sqlx::query("DELETE FROM profile_opt_outs WHERE user_id = $1").bind(user_id).execute(connection).await?;
+
+    Ok(())
+}
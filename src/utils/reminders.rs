@@ -0,0 +1,221 @@
+//! Reminders
+//!
+//! Reminders are stored in the `reminders` table and delivered by a scheduler
+//! that polls it for anything due. As the table is the only record of what is
+//! pending, reminders that came due while the bot was offline are delivered as
+//! soon as it is back, marked as late. Each reminder is claimed for a while
+//! before it is delivered, so several instances of the bot can share the table.
+//!
+//! A reminder is sent to the channel it was set in, falling back to a direct
+//! message if that fails. If neither can be sent, delivery is retried a few
+//! times before the reminder is given up on.
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use serenity::{
+    builder::{CreateAllowedMentions, CreateMessage},
+    client::Context,
+    http::Http,
+    model::id::{ChannelId, UserId}
+};
+use sqlx::{PgPool, Row};
+use std::{sync::Arc, time::Duration};
+use tokio::time::interval;
+use tracing::{error, info, warn};
+
+use crate::{
+    data::DatabasePool,
+    error::TaliyahError,
+    models::reminder::{Recurrence, Reminder}
+};
+
+/// How often the scheduler checks for reminders that are due.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How many due reminders are handled per check.
+const BATCH_SIZE: i64 = 50;
+
+/// How long, in seconds, to wait before retrying a reminder that couldn't be
+/// delivered.
+const RETRY_DELAY: i64 = 300;
+
+/// How many times delivering a reminder may fail before it is given up on.
+const MAX_ATTEMPTS: i32 = 5;
+
+/// How long, in seconds, a reminder is claimed for while it is delivered. A
+/// reminder whose claim runs out, e.g. because the bot stopped while
+/// delivering it, is claimed again.
+const CLAIM_DURATION: i64 = 300;
+
+/// How late, in seconds, a reminder can be delivered before it is marked as
+/// late.
+const LATE_AFTER: i64 = 60;
+
+const COLUMNS: &str = "id, user_id, channel_id, message, CAST(EXTRACT(EPOCH FROM due_at) AS BIGINT) AS due_at, \
+    CAST(EXTRACT(EPOCH FROM created_at) AS BIGINT) AS created_at, recurrence, attempts";
+
+/// Stores a reminder for a user, returning its ID.
+pub async fn add_reminder(context: &Context, user_id: UserId, channel_id: ChannelId, message: &str, due_at: i64, recurrence: Option<Recurrence>) -> Result<i64, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let id = sqlx::query("INSERT INTO reminders (user_id, channel_id, message, due_at, recurrence) VALUES ($1, $2, $3, to_timestamp($4), $5) RETURNING id")
+        .bind(user_id.get() as i64)
+        .bind(channel_id.get() as i64)
+        .bind(message)
+        .bind(due_at)
+        .bind(recurrence.map(Recurrence::key))
+        .fetch_one(&pool)
+        .await?
+        .try_get(0)?;
+
+    Ok(id)
+}
+
+/// Returns the pending reminders of a user, soonest first.
+pub async fn get_reminders(context: &Context, user_id: UserId) -> Result<Vec<Reminder>, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let query = format!("SELECT {COLUMNS} FROM reminders WHERE user_id = $1 ORDER BY due_at, id");
+    let reminders = sqlx::query_as::<_, Reminder>(&query).bind(user_id.get() as i64).fetch_all(&pool).await?;
+
+    Ok(reminders)
+}
+
+/// Deletes one of a user's reminders, returning whether they had a reminder
+/// with that ID.
+pub async fn cancel_reminder(context: &Context, user_id: UserId, id: i64) -> Result<bool, TaliyahError> {
+    let pool = context.data.read().await.get::<DatabasePool>().cloned().unwrap();
+    let result = sqlx::query("DELETE FROM reminders WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id.get() as i64)
+        .execute(&pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delivers reminders as they come due, for as long as the bot runs.
+pub async fn run_scheduler(http: Arc<Http>, pool: PgPool) {
+    match sqlx::query("SELECT COUNT(*) FROM reminders WHERE due_at <= now()").fetch_one(&pool).await {
+        Ok(row) => {
+            let overdue: i64 = row.get(0);
+            if overdue > 0 {
+                info!("Delivering {overdue} reminder(s) that came due while offline.");
+            }
+        }
+        Err(why) => error!("Unable to count overdue reminders: {why}")
+    }
+
+    let mut ticker = interval(POLL_INTERVAL);
+    loop {
+        ticker.tick().await;
+        if let Err(why) = deliver_due(&http, &pool).await {
+            error!("Unable to deliver reminders: {why}");
+        }
+    }
+}
+
+/// Delivers up to a batch of due reminders, one at a time.
+async fn deliver_due(http: &Http, pool: &PgPool) -> Result<(), TaliyahError> {
+    for _ in 0..BATCH_SIZE {
+        if !deliver_next(http, pool).await? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Claims the reminder that has been due the longest, delivers it and then
+/// reschedules or deletes it, returning whether there was one. The claim is
+/// committed before the reminder is sent, so no row is locked in the
+/// meantime, and reminders claimed by another instance of the bot are
+/// skipped until their claim runs out.
+async fn deliver_next(http: &Http, pool: &PgPool) -> Result<bool, TaliyahError> {
+    let query = format!(
+        "UPDATE reminders SET claimed_until = now() + make_interval(secs => $1) \
+        WHERE id = ( \
+            SELECT id FROM reminders WHERE due_at <= now() AND (claimed_until IS NULL OR claimed_until <= now()) \
+            ORDER BY due_at LIMIT 1 FOR UPDATE SKIP LOCKED \
+        ) \
+        RETURNING {COLUMNS}"
+    );
+    let reminder = match sqlx::query_as::<_, Reminder>(&query).bind(CLAIM_DURATION as f64).fetch_optional(pool).await? {
+        Some(reminder) => reminder,
+        None => return Ok(false)
+    };
+
+    let delivered = match deliver(http, &reminder).await {
+        Ok(()) => true,
+        Err(why) if reminder.attempts + 1 >= MAX_ATTEMPTS => {
+            warn!("Giving up on reminder {} after {MAX_ATTEMPTS} attempts: {why}", reminder.id);
+            true
+        }
+        Err(why) => {
+            warn!("Unable to deliver reminder {}, retrying later: {why}", reminder.id);
+            false
+        }
+    };
+
+    // Reminders cancelled while they were being delivered are already gone,
+    // so these leave them be.
+    if !delivered {
+        sqlx::query("UPDATE reminders SET due_at = now() + make_interval(secs => $1), attempts = attempts + 1, claimed_until = NULL WHERE id = $2")
+            .bind(RETRY_DELAY as f64)
+            .bind(reminder.id)
+            .execute(pool)
+            .await?;
+        return Ok(true);
+    }
+
+    let timezone = user_timezone(pool, reminder.user_id).await?;
+    let next = reminder.recurrence().and_then(|recurrence| recurrence.next_after(Utc::now().with_timezone(&timezone)));
+    match next {
+        Some(next) => {
+            sqlx::query("UPDATE reminders SET due_at = to_timestamp($1), attempts = 0, claimed_until = NULL WHERE id = $2")
+                .bind(next.timestamp())
+                .bind(reminder.id)
+                .execute(pool)
+                .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM reminders WHERE id = $1").bind(reminder.id).execute(pool).await?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Sends a reminder to the channel it was set in, or to the user directly if
+/// that fails.
+async fn deliver(http: &Http, reminder: &Reminder) -> Result<(), serenity::Error> {
+    let user_id = UserId::new(reminder.user_id as u64);
+    let channel_id = ChannelId::new(reminder.channel_id as u64);
+
+    let mut content = format!("<@{user_id}>, here's your reminder: {}", reminder.message);
+    let late = Utc::now().timestamp() - reminder.due_at;
+    if late > LATE_AFTER {
+        content.push_str(&format!("\n*This was due <t:{}:R>, but couldn't be delivered on time.*", reminder.due_at));
+    }
+
+    if let Some(recurrence) = reminder.recurrence() {
+        content.push_str(&format!("\n*Repeats {}. Use `remind cancel {}` to stop it.*", recurrence.describe(), reminder.id));
+    }
+
+    let message = || CreateMessage::new().content(&content).allowed_mentions(CreateAllowedMentions::new().users(vec![user_id]));
+    if let Err(why) = channel_id.send_message(http, message()).await {
+        warn!("Unable to send reminder {} to its channel, sending it directly instead: {why}", reminder.id);
+        user_id.create_dm_channel(http).await?.id.send_message(http, message()).await?;
+    }
+
+    Ok(())
+}
+
+/// The time zone on a user's profile, or UTC if they haven't set one.
+async fn user_timezone(pool: &PgPool, user_id: i64) -> Result<Tz, TaliyahError> {
+    let timezone: Option<String> = sqlx::query("SELECT user_timezone FROM profile_data WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .and_then(|row| row.get(0));
+
+    Ok(timezone.and_then(|timezone| timezone.parse().ok()).unwrap_or(Tz::UTC))
+}
@@ -0,0 +1,390 @@
+//! Time parsing
+//!
+//! Parses the durations and points in time users write in commands, such as
+//! `2h30m`, `in 3 hours` or `tomorrow 5pm`. Points in time are read in the
+//! time zone of the user, which falls back to UTC if they haven't set one.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use chrono_tz::Tz;
+use serenity::{client::Context, model::id::UserId};
+
+use super::profiles::get_profile_field;
+use crate::{error::TaliyahError, models::profile::ProfileField};
+
+/// The hour of the day used when only a day is given, e.g. `tomorrow`.
+pub const DEFAULT_HOUR: u32 = 9;
+
+/// The longest duration accepted, which is a hundred years.
+const MAX_SECONDS: i64 = 100 * 365 * 24 * 60 * 60;
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december"
+];
+
+/// Returns the time zone on a user's own profile, if they have set one.
+pub async fn user_timezone(context: &Context, user_id: UserId) -> Result<Option<Tz>, TaliyahError> {
+    let timezone = get_profile_field(context, user_id, ProfileField::Timezone).await?;
+    Ok(timezone.and_then(|timezone| timezone.parse().ok()))
+}
+
+/// Parses a duration such as `2h30m`, `90 minutes` or `1 day 2 hours`.
+/// Durations that add up to nothing are refused.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let tokens = tokenize(input);
+    if tokens.is_empty() || tokens.len() % 2 != 0 {
+        return None;
+    }
+
+    let mut total: i64 = 0;
+    for pair in tokens.chunks(2) {
+        let amount = match pair[0].as_str() {
+            "a" | "an" => 1,
+            number => number.parse::<i64>().ok()?
+        };
+
+        let unit: i64 = match pair[1].as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+            "d" | "day" | "days" => 24 * 60 * 60,
+            "w" | "wk" | "wks" | "week" | "weeks" => 7 * 24 * 60 * 60,
+            _ => return None
+        };
+
+        total = total.checked_add(amount.checked_mul(unit)?)?;
+    }
+
+    (total > 0 && total <= MAX_SECONDS).then(|| Duration::seconds(total))
+}
+
+/// Formats a number of seconds the way [`parse_duration`] reads them, e.g.
+/// `1d 12h`, leaving out units that are zero.
+pub fn format_duration(seconds: i64) -> String {
+    let units = [("w", 7 * 24 * 60 * 60), ("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60), ("s", 1)];
+    let mut remaining = seconds.max(0);
+    let mut parts = Vec::new();
+    for (unit, length) in units {
+        if remaining >= length {
+            parts.push(format!("{}{unit}", remaining / length));
+            remaining %= length;
+        }
+    }
+
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Parses a point in time relative to `now`, such as `in 3 hours`,
+/// `tomorrow 5pm`, `friday at 9:30`, `october 20 noon` or `2026-12-31 23:59`.
+///
+/// A time of day on its own refers to the next time it comes around, and a
+/// day on its own refers to [`DEFAULT_HOUR`] on that day.
+pub fn parse_time(input: &str, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let input = input.trim().to_lowercase();
+    if input == "now" {
+        return Some(now);
+    }
+
+    let relative = input.strip_prefix("in ").or_else(|| input.strip_suffix(" from now")).unwrap_or(&input);
+    if let Some(duration) = parse_duration(relative) {
+        return now.checked_add_signed(duration);
+    }
+
+    let words: Vec<&str> = input.split_whitespace().filter(|word| !matches!(*word, "at" | "on")).collect();
+    let today = now.naive_local().date();
+    let (day, rest) = parse_day(&words, today)?;
+    let time = if rest.is_empty() { None } else { Some(parse_clock(&rest.join(" "))?) };
+
+    match (day, time) {
+        (Some(day), Some(time)) => localize(now.timezone(), day.and_time(time)),
+        (Some(day), None) => localize(now.timezone(), day.and_hms_opt(DEFAULT_HOUR, 0, 0)?),
+        (None, Some(time)) => {
+            let later_today = localize(now.timezone(), today.and_time(time))?;
+            if later_today > now {
+                Some(later_today)
+            } else {
+                localize(now.timezone(), (today + Duration::days(1)).and_time(time))
+            }
+        }
+        (None, None) => None
+    }
+}
+
+/// Parses a time of day such as `5pm`, `5:30 pm`, `17:00`, `noon` or
+/// `midnight`.
+pub fn parse_clock(input: &str) -> Option<NaiveTime> {
+    let input = input.trim().to_lowercase().replace(' ', "");
+    match input.as_str() {
+        "noon" | "midday" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(clock) = input.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (input.as_str(), None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        Some(_) => return None,
+        // A bare number is only a time with am or pm after it.
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None
+    };
+
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None => hour
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parses a day of the week, accepting both full and short names.
+pub fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None
+    }
+}
+
+/// The first date after `today` that falls on the given day of the week.
+pub fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64 - 1) % 7 + 1;
+    today + Duration::days(days)
+}
+
+/// Resolves a local date and time in the given time zone. Times skipped by a
+/// change to daylight saving time are moved an hour later.
+pub fn localize(timezone: Tz, datetime: NaiveDateTime) -> Option<DateTime<Tz>> {
+    timezone
+        .from_local_datetime(&datetime)
+        .earliest()
+        .or_else(|| timezone.from_local_datetime(&(datetime + Duration::hours(1))).earliest())
+}
+
+/// Finds a day in the given words, returning it along with the words left
+/// over. Returns `None` if the words name more than one day.
+fn parse_day<'a>(words: &[&'a str], today: NaiveDate) -> Option<(Option<NaiveDate>, Vec<&'a str>)> {
+    let mut day = None;
+    let mut rest = Vec::new();
+    let mut index = 0;
+
+    while index < words.len() {
+        let word = words[index];
+        let next = words.get(index + 1).copied();
+        let (found, used) = if let Some(weekday) = next.filter(|_| word == "next").and_then(parse_weekday) {
+            (Some(next_weekday(today, weekday)), 2)
+        } else if let Some(date) = month_day(word, next, today).or_else(|| next.and_then(|next| month_day(next, Some(word), today))) {
+            (Some(date), 2)
+        } else {
+            let date = match word {
+                "today" | "tonight" => Some(today),
+                "tomorrow" => Some(today + Duration::days(1)),
+                _ => parse_weekday(word)
+                    .map(|weekday| next_weekday(today, weekday))
+                    .or_else(|| NaiveDate::parse_from_str(word, "%Y-%m-%d").ok())
+            };
+            (date, 1)
+        };
+
+        match found {
+            Some(_) if day.is_some() => return None,
+            Some(date) => {
+                day = Some(date);
+                index += used;
+            }
+            None => {
+                rest.push(word);
+                index += 1;
+            }
+        }
+    }
+
+    Some((day, rest))
+}
+
+/// Parses a month name followed by a day, as in `october 20`, taking the
+/// next time that date comes around.
+fn month_day(month: &str, day: Option<&str>, today: NaiveDate) -> Option<NaiveDate> {
+    let month = MONTHS.iter().position(|name| month.len() >= 3 && name.starts_with(month))? as u32 + 1;
+    let day = day?;
+    let day = ["st", "nd", "rd", "th"].iter().find_map(|suffix| day.strip_suffix(suffix)).unwrap_or(day).parse::<u32>().ok()?;
+
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+    match this_year {
+        Some(date) if date >= today => Some(date),
+        _ => NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    }
+}
+
+/// Splits input into runs of digits and runs of letters, so that `2h30m` and
+/// `2 hours 30 minutes` both become pairs of an amount and a unit.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut previous = None;
+
+    for c in input.to_lowercase().chars() {
+        let kind = if c.is_ascii_digit() {
+            Some(true)
+        } else if c.is_alphabetic() {
+            Some(false)
+        } else {
+            None
+        };
+
+        match kind {
+            Some(kind) if previous == Some(kind) => tokens.last_mut().unwrap().push(c),
+            Some(_) => tokens.push(c.to_string()),
+            None => {}
+        }
+
+        previous = kind;
+    }
+
+    tokens.retain(|token| token != "and");
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    fn local(datetime: &str) -> DateTime<Tz> {
+        New_York.from_local_datetime(&NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M").unwrap()).unwrap()
+    }
+
+    fn clock(hour: u32, minute: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("2h30m"), Some(Duration::minutes(150)));
+        assert_eq!(parse_duration("1 day 2 hours"), Some(Duration::hours(26)));
+        assert_eq!(parse_duration("an hour"), Some(Duration::hours(1)));
+        assert_eq!(parse_duration("90 minutes and 30 seconds"), Some(Duration::seconds(5430)));
+    }
+
+    #[test]
+    fn refuses_empty_unknown_and_overlong_durations() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("3 fortnights"), None);
+        assert_eq!(parse_duration("5300w"), None);
+    }
+
+    #[test]
+    fn parses_clock_times() {
+        assert_eq!(parse_clock("12am"), clock(0, 0));
+        assert_eq!(parse_clock("12:30 am"), clock(0, 30));
+        assert_eq!(parse_clock("12pm"), clock(12, 0));
+        assert_eq!(parse_clock("5pm"), clock(17, 0));
+        assert_eq!(parse_clock("5:30 PM"), clock(17, 30));
+        assert_eq!(parse_clock("17:00"), clock(17, 0));
+        assert_eq!(parse_clock("noon"), clock(12, 0));
+        assert_eq!(parse_clock("midnight"), clock(0, 0));
+    }
+
+    #[test]
+    fn refuses_invalid_clock_times() {
+        assert_eq!(parse_clock("0am"), None);
+        assert_eq!(parse_clock("13pm"), None);
+        assert_eq!(parse_clock("17"), None);
+        assert_eq!(parse_clock("5:3pm"), None);
+        assert_eq!(parse_clock("25:00"), None);
+    }
+
+    #[test]
+    fn parses_relative_times() {
+        let now = local("2026-10-18 10:00");
+        assert_eq!(parse_time("now", now), Some(now));
+        assert_eq!(parse_time("in 3 hours", now), Some(local("2026-10-18 13:00")));
+        assert_eq!(parse_time("3 hours from now", now), Some(local("2026-10-18 13:00")));
+    }
+
+    #[test]
+    fn takes_the_next_time_a_clock_time_comes_around() {
+        let now = local("2026-10-18 10:00");
+        assert_eq!(parse_time("5pm", now), Some(local("2026-10-18 17:00")));
+        assert_eq!(parse_time("9am", now), Some(local("2026-10-19 09:00")));
+        assert_eq!(parse_time("12am", now), Some(local("2026-10-19 00:00")));
+        assert_eq!(parse_time("12pm", now), Some(local("2026-10-18 12:00")));
+    }
+
+    #[test]
+    fn parses_days() {
+        let now = local("2026-10-18 10:00");
+        assert_eq!(parse_time("tomorrow", now), Some(local("2026-10-19 09:00")));
+        assert_eq!(parse_time("wednesday at 9:30", now), Some(local("2026-10-21 09:30")));
+        assert_eq!(parse_time("october 20 noon", now), Some(local("2026-10-20 12:00")));
+        assert_eq!(parse_time("2026-12-31 23:59", now), Some(local("2026-12-31 23:59")));
+        assert_eq!(parse_time("friday monday", now), None);
+    }
+
+    #[test]
+    fn takes_next_week_for_the_weekday_that_is_today() {
+        // 2026-10-18 is a Sunday.
+        let now = local("2026-10-18 10:00");
+        assert_eq!(parse_time("sunday 5pm", now), Some(local("2026-10-25 17:00")));
+        assert_eq!(parse_time("next sunday", now), Some(local("2026-10-25 09:00")));
+    }
+
+    #[test]
+    fn rolls_dates_that_have_passed_over_to_next_year() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert_eq!(month_day("october", Some("18"), today), NaiveDate::from_ymd_opt(2026, 10, 18));
+        assert_eq!(month_day("oct", Some("1st"), today), NaiveDate::from_ymd_opt(2027, 10, 1));
+        assert_eq!(month_day("jan", Some("5"), today), NaiveDate::from_ymd_opt(2027, 1, 5));
+        assert_eq!(month_day("december", Some("31st"), today), NaiveDate::from_ymd_opt(2026, 12, 31));
+        assert_eq!(month_day("february", Some("30"), today), None);
+        assert_eq!(month_day("ju", Some("4"), today), None);
+
+        let leap_day = NaiveDate::from_ymd_opt(2027, 3, 1).unwrap();
+        assert_eq!(month_day("february", Some("29"), leap_day), NaiveDate::from_ymd_opt(2028, 2, 29));
+    }
+
+    #[test]
+    fn moves_times_skipped_by_daylight_saving_an_hour_later() {
+        let skipped = NaiveDateTime::parse_from_str("2026-03-08 02:30", "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(localize(New_York, skipped), Some(local("2026-03-08 03:30")));
+
+        let now = local("2026-03-07 12:00");
+        assert_eq!(parse_time("tomorrow 2:30am", now), Some(local("2026-03-08 03:30")));
+    }
+
+    #[test]
+    fn takes_the_earlier_of_repeated_times() {
+        let repeated = NaiveDateTime::parse_from_str("2026-11-01 01:30", "%Y-%m-%d %H:%M").unwrap();
+        let localized = localize(New_York, repeated).unwrap();
+        assert_eq!(localized.naive_utc(), NaiveDateTime::parse_from_str("2026-11-01 05:30", "%Y-%m-%d %H:%M").unwrap());
+    }
+}